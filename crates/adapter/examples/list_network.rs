@@ -1,14 +1,12 @@
 use std::time::Duration;
 
 use color_eyre::eyre::{Context, eyre};
-use tokio::time::sleep;
 use tokio_serial::{SerialPortBuilderExt, SerialPortType};
 use tracing::level_filters::LevelFilter;
 use tracing_subscriber::layer::SubscriberExt;
 use tracing_subscriber::util::SubscriberInitExt;
 use tracing_subscriber::{EnvFilter, fmt};
 use zstacker_znp::coordinator::Adaptor;
-use zstacker_znp::nvram;
 
 #[tokio::main]
 async fn main() -> color_eyre::Result<()> {
@@ -35,8 +33,8 @@ async fn main() -> color_eyre::Result<()> {
             .open_native_async()
             .expect("Failed to open port");
         candidate.set_exclusive(true).unwrap();
-        let mut candidate = Adaptor::start(candidate);
-        match zstacker_znp::check_connection_to_adapter(&mut candidate).await {
+        let candidate = Adaptor::start(candidate);
+        match zstacker_znp::check_connection_to_adapter(&candidate).await {
             Ok(()) => adaptor = Some(candidate),
             Err(e) => eprintln!(
                 "error while connecting: \n\tport: {}\n\terror: {e:?}\ncould be wrong port?",
@@ -48,13 +46,27 @@ async fn main() -> color_eyre::Result<()> {
     let Some(adaptor) = adaptor else {
         return Err(eyre!("No adapter found"));
     };
-    let mut coordinator =
-        zstacker_znp::start_coordinator(adaptor, vec![], true)
-            .await
-            .wrap_err("Could not start coordinator")?;
+    let coordinator = zstacker_znp::start_coordinator(adaptor, vec![], true)
+        .await
+        .wrap_err("Could not start coordinator")?;
 
-    let reply = coordinator.read_nvram_item(nvram::ids::NIB).await.unwrap();
-    dbg!(reply.len());
+    let nib = coordinator
+        .read_nib()
+        .await
+        .wrap_err("Could not read NIB")?;
+    println!(
+        "channel: {}, pan id: {:#06x}, extended pan id: {:#018x}, \
+        nwk update id: {}",
+        nib.nwk_logical_channel,
+        nib.nwk_pan_id,
+        nib.extended_panid,
+        nib.nwk_update_id
+    );
+    let frame_counter = coordinator
+        .network_frame_counter()
+        .await
+        .wrap_err("Could not read network frame counter")?;
+    println!("network frame counter: {frame_counter}");
 
     // println!("sleeping to give the coordinator time to discover the network");
     // sleep(Duration::from_secs(60)).await;
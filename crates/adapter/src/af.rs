@@ -0,0 +1,140 @@
+use std::future::ready;
+
+use futures::{Stream, StreamExt, stream};
+use tracing::{debug, instrument};
+use zstacker_znp_protocol::commands::ShortAddr;
+use zstacker_znp_protocol::commands::af::{
+    Address, ClusterId, ConfirmStatus, DataRequest, IncomingMsg,
+    IncomingMsgExt, TxOptions,
+};
+
+use crate::coordinator::{Coordinator, QueueError, SubscriptionError};
+
+/// Maximum number of hops, same as zigbee-herdsman
+pub(crate) const DEFAULT_RADIUS: u8 = 30;
+
+/// Data to send to an endpoint on another device
+#[derive(Debug, Clone)]
+pub struct Message {
+    pub dst_addr: ShortAddr,
+    pub dst_endpoint: u8,
+    /// One of the endpoints registered on the coordinator. Replies from the
+    /// device will be addressed to this endpoint.
+    pub src_endpoint: u8,
+    pub cluster_id: ClusterId,
+    pub data: Vec<u8>,
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum SendError {
+    #[error("Could not send data request or receive its confirmation")]
+    Queue(#[source] QueueError),
+    #[error("Message was not delivered, device reported: {0:?}")]
+    NotDelivered(ConfirmStatus),
+}
+
+impl Coordinator {
+    /// Returns once the message has been delivered.
+    #[instrument(skip(self), err)]
+    pub async fn send_af(&self, message: Message) -> Result<(), SendError> {
+        let trans_id = self.next_transaction_id();
+        let confirm = self
+            .queue_async(DataRequest {
+                dst_addr: message.dst_addr,
+                dst_endpoint: message.dst_endpoint,
+                src_endpoint: message.src_endpoint,
+                cluster_id: message.cluster_id,
+                trans_id,
+                options: TxOptions::DISCOVER_ROUTE,
+                radius: DEFAULT_RADIUS,
+                data: message.data,
+            })
+            .await
+            .map_err(SendError::Queue)?;
+
+        if confirm.status.is_success() {
+            Ok(())
+        } else {
+            Err(SendError::NotDelivered(confirm.status))
+        }
+    }
+
+    /// Every message that arrives at one of the coordinators endpoints from
+    /// now on. Large messages the adaptor reports as [`IncomingMsgExt`] are
+    /// included.
+    pub fn incoming_messages(
+        &self,
+    ) -> impl Stream<Item = Result<IncomingMsg, SubscriptionError>>
+    + Send
+    + Unpin
+    + 'static {
+        let extended = self.subscribe::<IncomingMsgExt>().filter_map(|msg| {
+            ready(match msg {
+                Ok(msg) => from_extended(msg).map(Ok),
+                Err(err) => Some(Err(err)),
+            })
+        });
+        stream::select(self.subscribe::<IncomingMsg>(), extended)
+    }
+}
+
+/// Messages from within the network always have a short source address,
+/// the others are inter-PAN messages which we do not handle.
+fn from_extended(msg: IncomingMsgExt) -> Option<IncomingMsg> {
+    let Address::Short(src_addr) = msg.src else {
+        debug!(src = ?msg.src, "Ignoring message without short source address");
+        return None;
+    };
+    Some(IncomingMsg {
+        group_id: msg.group_id,
+        cluster_id: msg.cluster_id,
+        src_addr,
+        src_endpoint: msg.src_endpoint,
+        dst_endpoint: msg.dst_endpoint,
+        was_broadcast: msg.was_broadcast,
+        link_quality: msg.link_quality,
+        security_use: msg.security_use,
+        timestamp: msg.timestamp,
+        trans_seq_number: msg.trans_seq_number,
+        data: msg.data.0,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use zstacker_znp_protocol::commands::{IeeeAddr, LongData};
+
+    use super::*;
+
+    fn extended(src: Address) -> IncomingMsgExt {
+        IncomingMsgExt {
+            group_id: 0,
+            cluster_id: ClusterId(0x0019),
+            src,
+            src_endpoint: 1,
+            src_pan_id: 0x1a62,
+            dst_endpoint: 1,
+            was_broadcast: false,
+            link_quality: 200,
+            security_use: false,
+            timestamp: 0,
+            trans_seq_number: 3,
+            data: LongData(vec![0; 300]),
+        }
+    }
+
+    #[test]
+    fn extended_message_keeps_short_source() {
+        let msg =
+            from_extended(extended(Address::Short(ShortAddr(0x1234)))).unwrap();
+        assert_eq!(msg.src_addr, ShortAddr(0x1234));
+        assert_eq!(msg.link_quality, 200);
+        assert_eq!(msg.data.len(), 300);
+    }
+
+    #[test]
+    fn inter_pan_message_is_skipped() {
+        let msg = extended(Address::Ieee(IeeeAddr(0x00124b0001020304)));
+        assert!(from_extended(msg).is_none());
+    }
+}
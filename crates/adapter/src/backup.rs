@@ -0,0 +1,857 @@
+//! Snapshot of the network state in the [open coordinator backup format]
+//! that zigpy and zigbee2mqtt share. A backup taken from one adaptor can be
+//! restored onto a new one, devices then keep working without re-pairing.
+//!
+//! [open coordinator backup format]: https://github.com/zigpy/open-coordinator-backup
+
+use std::collections::HashMap;
+use std::io::Cursor;
+use std::iter;
+use std::ops::RangeInclusive;
+
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use tracing::{instrument, warn};
+use zstacker_znp_protocol::commands::sys::{ExNvId, NvId, NvSysId};
+use zstacker_znp_protocol::commands::{IeeeAddr, Key, ShortAddr};
+use zstacker_znp_protocol::data_format;
+
+use crate::coordinator::Coordinator;
+use crate::error::StartUpError;
+use crate::nvram::types::{
+    AddrMgrEntry, Nib, NibError, NwkActiveKeyItems, NwkKeyDesc,
+    NwkSecMaterialDesc, TclkDevEntry, addr_mgr_user, key_attributes,
+};
+use crate::nvram::{
+    ReadError, VersionError, WriteError, ZStackVersion, ids, items,
+};
+use crate::startup;
+
+pub const FORMAT: &str = "zigpy/open-coordinator-backup";
+pub const FORMAT_VERSION: u8 = 1;
+/// Added to the network frame counter on restore. Devices drop frames with
+/// a counter they have seen before and the old adaptor kept counting after
+/// the backup was made.
+const FRAME_COUNTER_MARGIN: u32 = 10_000;
+/// The 2.4 GHz channels Zigbee uses
+const CHANNELS: RangeInclusive<u8> = 11..=26;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Backup {
+    pub metadata: Metadata,
+    #[serde(default)]
+    pub stack_specific: StackSpecific,
+    #[serde(with = "hex")]
+    pub coordinator_ieee: IeeeAddr,
+    #[serde(with = "hex")]
+    pub pan_id: u16,
+    #[serde(with = "hex")]
+    pub extended_pan_id: u64,
+    pub nwk_update_id: u8,
+    pub security_level: u8,
+    pub channel: u8,
+    /// Channels the network may move to
+    pub channel_mask: Vec<u8>,
+    pub network_key: NetworkKey,
+    pub devices: Vec<BackupDevice>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Metadata {
+    pub format: String,
+    pub version: u8,
+    /// The software that made the backup
+    pub source: String,
+    /// Extra information the source software keeps, for example the date
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub internal: Option<serde_json::Value>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StackSpecific {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub zstack: Option<ZStackSpecific>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ZStackSpecific {
+    /// Link keys of devices are derived from this seed
+    #[serde(with = "hex")]
+    pub tclk_seed: Key,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct NetworkKey {
+    #[serde(with = "hex")]
+    pub key: Key,
+    pub sequence_number: u8,
+    pub frame_counter: u32,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BackupDevice {
+    #[serde(with = "hex")]
+    pub nwk_address: ShortAddr,
+    #[serde(with = "hex")]
+    pub ieee_address: IeeeAddr,
+    /// Whether the device joined through the coordinator
+    pub is_child: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub link_key: Option<LinkKey>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LinkKey {
+    #[serde(with = "hex")]
+    pub key: Key,
+    pub tx_counter: u32,
+    pub rx_counter: u32,
+}
+
+impl Backup {
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self)
+            .expect("backup only contains strings and numbers")
+    }
+
+    pub fn from_json(json: &str) -> Result<Self, serde_json::Error> {
+        serde_json::from_str(json)
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum BackupError {
+    #[error("Could not determine the Z-Stack version")]
+    Version(#[source] VersionError),
+    #[error("Could not read the {item} from nvram")]
+    Reading {
+        item: &'static str,
+        #[source]
+        source: ReadError,
+    },
+    #[error("Could not decode the {item}")]
+    Decoding {
+        item: &'static str,
+        #[source]
+        source: data_format::Error,
+    },
+    #[error("Could not read the NIB")]
+    Nib(#[source] NibError),
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum RestoreError {
+    #[error(
+        "Backup has format {format} version {version}, only version \
+        {FORMAT_VERSION} of {FORMAT} is supported"
+    )]
+    UnsupportedFormat { format: String, version: u8 },
+    #[error("Backup uses channel {0}, Zigbee uses channels 11 to 26")]
+    InvalidChannel(u8),
+    #[error("Could not determine the Z-Stack version")]
+    Version(#[source] VersionError),
+    #[error("The adaptor has no NIB, form a network on it first")]
+    NoNib,
+    #[error("Could not read the {item} from nvram")]
+    Reading {
+        item: &'static str,
+        #[source]
+        source: ReadError,
+    },
+    #[error("Could not write the {item} to nvram")]
+    Writing {
+        item: &'static str,
+        #[source]
+        source: WriteError,
+    },
+    #[error("Could not decode the NIB")]
+    Nib(#[source] NibError),
+    #[error("The {table} of the adaptor fits {max} entries, needed: {needed}")]
+    TableFull {
+        table: &'static str,
+        max: usize,
+        needed: usize,
+    },
+    #[error("Could not restart the adaptor to load the restored state")]
+    Restarting(#[source] StartUpError),
+}
+
+/// A table stored as one nvram item per entry
+struct Table {
+    name: &'static str,
+    legacy_start: NvId,
+    legacy_end: NvId,
+    ex_item: u16,
+}
+
+const SEC_MATERIAL_TABLE: Table = Table {
+    name: "network frame counter table",
+    legacy_start: ids::LEGACY_NWK_SEC_MATERIAL_TABLE_START,
+    legacy_end: ids::LEGACY_NWK_SEC_MATERIAL_TABLE_END,
+    ex_item: ids::ex::NWK_SEC_MATERIAL_TABLE,
+};
+
+const TCLK_TABLE: Table = Table {
+    name: "trust center link key table",
+    legacy_start: ids::LEGACY_TCLK_TABLE_START,
+    legacy_end: ids::LEGACY_TCLK_TABLE_END,
+    ex_item: ids::ex::TCLK_TABLE,
+};
+
+impl Coordinator {
+    /// Reads the network state from nvram. The frame counter is the one at
+    /// the time of the backup, [`Coordinator::restore_backup`] adds a margin.
+    #[instrument(skip(self), err)]
+    pub async fn create_backup(&self) -> Result<Backup, BackupError> {
+        let version =
+            self.zstack_version().await.map_err(BackupError::Version)?;
+        let nib = self.read_nib().await.map_err(BackupError::Nib)?;
+        let key = self
+            .read::<items::NwkActiveKeyInfo>()
+            .await
+            .map_err(reading("network key"))?;
+        let frame_counter =
+            self.read_frame_counter(version, nib.extended_panid).await?;
+        // Adaptors that keep the address in their factory settings do not
+        // have the item
+        let coordinator_ieee = match self.read::<items::ExtAddr>().await {
+            Ok(ieee) => ieee,
+            Err(ReadError::DoesNotExist(_)) => self.ieee_addr,
+            Err(source) => {
+                return Err(BackupError::Reading {
+                    item: "coordinator address",
+                    source,
+                });
+            }
+        };
+
+        let tclk_seed = if version == ZStackVersion::V1_2 {
+            None
+        } else {
+            Some(
+                self.read::<items::TclkSeed>()
+                    .await
+                    .map_err(reading("TCLK seed"))?,
+            )
+        };
+        let link_keys = match tclk_seed {
+            Some(seed) => self.read_link_keys(version, &seed).await?,
+            None => HashMap::new(),
+        };
+
+        let devices = self
+            .read_address_manager(version)
+            .await
+            .map_err(reading("address manager table"))?
+            .into_iter()
+            .map(decode::<AddrMgrEntry>("address manager table"))
+            .filter(|entry| !entry.as_ref().is_ok_and(AddrMgrEntry::is_empty))
+            .map(|entry| {
+                entry.map(|entry| BackupDevice {
+                    nwk_address: entry.nwk_addr,
+                    ieee_address: entry.ext_addr,
+                    is_child: entry.user & addr_mgr_user::ASSOC != 0,
+                    link_key: link_keys.get(&entry.ext_addr).cloned(),
+                })
+            })
+            .collect::<Result<_, _>>()?;
+
+        Ok(Backup {
+            metadata: Metadata {
+                format: FORMAT.to_string(),
+                version: FORMAT_VERSION,
+                source: concat!("zstacker-znp@", env!("CARGO_PKG_VERSION"))
+                    .to_string(),
+                internal: None,
+            },
+            stack_specific: StackSpecific {
+                zstack: tclk_seed.map(|tclk_seed| ZStackSpecific { tclk_seed }),
+            },
+            coordinator_ieee,
+            pan_id: nib.nwk_pan_id,
+            extended_pan_id: nib.extended_panid,
+            nwk_update_id: nib.nwk_update_id,
+            security_level: nib.security_level,
+            channel: nib.nwk_logical_channel,
+            channel_mask: CHANNELS
+                .filter(|channel| nib.channel_list & (1 << channel) != 0)
+                .collect(),
+            network_key: NetworkKey {
+                key: key.key,
+                sequence_number: key.key_seq_num,
+                frame_counter,
+            },
+            devices,
+        })
+    }
+
+    /// Writes the network state to nvram and restarts the adaptor. The
+    /// adaptor must have formed a network before, its NIB is patched rather
+    /// then recreated. The `ieee_addr` of this coordinator is not updated,
+    /// start a new coordinator to pick up the restored address.
+    ///
+    /// Link keys that are not derived from the TCLK seed can not be
+    /// restored, those devices have to rejoin.
+    #[instrument(skip_all, err)]
+    pub async fn restore_backup(
+        &self,
+        backup: &Backup,
+    ) -> Result<(), RestoreError> {
+        if backup.metadata.format != FORMAT
+            || backup.metadata.version != FORMAT_VERSION
+        {
+            return Err(RestoreError::UnsupportedFormat {
+                format: backup.metadata.format.clone(),
+                version: backup.metadata.version,
+            });
+        }
+        if let Some(channel) = iter::once(&backup.channel)
+            .chain(&backup.channel_mask)
+            .find(|channel| !CHANNELS.contains(*channel))
+        {
+            return Err(RestoreError::InvalidChannel(*channel));
+        }
+        let version =
+            self.zstack_version().await.map_err(RestoreError::Version)?;
+        let nib = match self.read_nvram_item(ids::NIB).await {
+            Ok(nib) => nib,
+            Err(ReadError::DoesNotExist(_)) => return Err(RestoreError::NoNib),
+            Err(source) => {
+                return Err(RestoreError::Reading {
+                    item: "NIB",
+                    source,
+                });
+            }
+        };
+        let (mut nib, layout) = Nib::decode(&nib).map_err(RestoreError::Nib)?;
+
+        let channel_list = backup
+            .channel_mask
+            .iter()
+            .fold(0u32, |mask, channel| mask | 1 << channel);
+        nib.nwk_dev_address = 0x0000;
+        nib.nwk_logical_channel = backup.channel;
+        nib.channel_list = channel_list;
+        nib.nwk_pan_id = backup.pan_id;
+        nib.extended_panid = backup.extended_pan_id;
+        nib.nwk_update_id = backup.nwk_update_id;
+        nib.security_level = backup.security_level;
+        nib.nwk_key_loaded = true;
+
+        let key = NwkKeyDesc {
+            key_seq_num: backup.network_key.sequence_number,
+            key: backup.network_key.key,
+        };
+        let frame_counter = backup
+            .network_key
+            .frame_counter
+            .saturating_add(FRAME_COUNTER_MARGIN);
+
+        self.write::<items::ExtAddr>(&backup.coordinator_ieee)
+            .await
+            .map_err(writing("coordinator address"))?;
+        self.write::<items::PanId>(&backup.pan_id)
+            .await
+            .map_err(writing("PAN id"))?;
+        self.write::<items::ExtendedPanId>(&backup.extended_pan_id)
+            .await
+            .map_err(writing("extended PAN id"))?;
+        self.write::<items::ChanList>(&channel_list)
+            .await
+            .map_err(writing("channel list"))?;
+        self.write_nvram_item(ids::NIB, &nib.encode(layout))
+            .await
+            .map_err(writing("NIB"))?;
+        self.write::<items::NwkActiveKeyInfo>(&key)
+            .await
+            .map_err(writing("network key"))?;
+        self.write::<items::NwkAlternKeyInfo>(&key)
+            .await
+            .map_err(writing("alternate network key"))?;
+        self.write::<items::PreCfgKey>(&key.key)
+            .await
+            .map_err(writing("preconfigured key"))?;
+        self.write::<items::PreCfgKeysEnable>(&true)
+            .await
+            .map_err(writing("preconfigured key enable"))?;
+
+        self.write_frame_counter(version, key, frame_counter, &nib)
+            .await?;
+        self.write_address_manager(version, &backup.devices).await?;
+        if version != ZStackVersion::V1_2 {
+            self.write_link_keys(version, backup).await?;
+        }
+
+        let configured = &ids::ZNP_HAS_CONFIGURED_VALUE;
+        match version {
+            ZStackVersion::V1_2 => {
+                self.write::<items::ZnpHasConfiguredZStack1>(configured)
+                    .await
+            }
+            _ => {
+                self.write::<items::ZnpHasConfiguredZStack3>(configured)
+                    .await
+            }
+        }
+        .map_err(writing("configured marker"))?;
+        startup::run_startup_sequence(self.adaptor(), false)
+            .await
+            .map_err(RestoreError::Restarting)?;
+        Ok(())
+    }
+
+    /// Counter of the frames the coordinator sent with the network key.
+    /// Where it is stored depends on the Z-Stack release.
+    pub async fn network_frame_counter(&self) -> Result<u32, BackupError> {
+        let version =
+            self.zstack_version().await.map_err(BackupError::Version)?;
+        let nib = self.read_nib().await.map_err(BackupError::Nib)?;
+        self.read_frame_counter(version, nib.extended_panid).await
+    }
+
+    async fn read_frame_counter(
+        &self,
+        version: ZStackVersion,
+        extended_panid: u64,
+    ) -> Result<u32, BackupError> {
+        if version == ZStackVersion::V1_2 {
+            let items = self
+                .read::<items::NwkKey>()
+                .await
+                .map_err(reading("network key"))?;
+            return Ok(items.frame_counter);
+        }
+
+        let entries = self
+            .read_table(version, &SEC_MATERIAL_TABLE)
+            .await
+            .map_err(reading(SEC_MATERIAL_TABLE.name))?
+            .into_iter()
+            .map(decode::<NwkSecMaterialDesc>(SEC_MATERIAL_TABLE.name))
+            .collect::<Result<Vec<_>, _>>()?;
+        // Prefer the entry of this network over the generic one
+        let entry = entries
+            .iter()
+            .find(|entry| entry.extended_panid == extended_panid)
+            .or_else(|| {
+                entries
+                    .iter()
+                    .find(|entry| entry.extended_panid == u64::MAX)
+            });
+        Ok(entry.map_or(0, |entry| entry.frame_counter))
+    }
+
+    async fn write_frame_counter(
+        &self,
+        version: ZStackVersion,
+        key: NwkKeyDesc,
+        frame_counter: u32,
+        nib: &Nib,
+    ) -> Result<(), RestoreError> {
+        if version == ZStackVersion::V1_2 {
+            let items = NwkActiveKeyItems {
+                active: key,
+                frame_counter,
+            };
+            return self
+                .write::<items::NwkKey>(&items)
+                .await
+                .map_err(writing("network key"));
+        }
+
+        let existing = self
+            .read_table(version, &SEC_MATERIAL_TABLE)
+            .await
+            .map_err(|source| RestoreError::Reading {
+                item: SEC_MATERIAL_TABLE.name,
+                source,
+            })?
+            .len();
+        let entries = [nib.extended_panid, u64::MAX]
+            .into_iter()
+            .map(|extended_panid| NwkSecMaterialDesc {
+                frame_counter,
+                extended_panid,
+            })
+            .chain(std::iter::repeat(NwkSecMaterialDesc {
+                frame_counter: 0,
+                extended_panid: 0,
+            }))
+            .take(existing.max(2))
+            .map(|entry| encode(&entry))
+            .collect();
+        self.write_table(version, &SEC_MATERIAL_TABLE, entries)
+            .await
+    }
+
+    async fn read_link_keys(
+        &self,
+        version: ZStackVersion,
+        seed: &Key,
+    ) -> Result<HashMap<IeeeAddr, LinkKey>, BackupError> {
+        let entries = self
+            .read_table(version, &TCLK_TABLE)
+            .await
+            .map_err(reading(TCLK_TABLE.name))?;
+        let mut keys = HashMap::new();
+        for entry in entries {
+            let entry: TclkDevEntry = decode(TCLK_TABLE.name)(entry)?;
+            if entry.key_attributes != key_attributes::VERIFIED_KEY
+                || entry.ext_addr == IeeeAddr(0)
+                || entry.ext_addr == IeeeAddr(u64::MAX)
+            {
+                continue;
+            }
+            keys.insert(
+                entry.ext_addr,
+                LinkKey {
+                    key: derive_link_key(
+                        seed,
+                        entry.seed_shift,
+                        entry.ext_addr,
+                    ),
+                    tx_counter: entry.tx_frame_counter,
+                    rx_counter: entry.rx_frame_counter,
+                },
+            );
+        }
+        Ok(keys)
+    }
+
+    async fn write_link_keys(
+        &self,
+        version: ZStackVersion,
+        backup: &Backup,
+    ) -> Result<(), RestoreError> {
+        let seed = match &backup.stack_specific.zstack {
+            Some(zstack) => {
+                self.write::<items::TclkSeed>(&zstack.tclk_seed)
+                    .await
+                    .map_err(writing("TCLK seed"))?;
+                zstack.tclk_seed
+            }
+            None => self.read::<items::TclkSeed>().await.map_err(|source| {
+                RestoreError::Reading {
+                    item: "TCLK seed",
+                    source,
+                }
+            })?,
+        };
+
+        let mut entries = Vec::new();
+        for device in &backup.devices {
+            let Some(link_key) = &device.link_key else {
+                continue;
+            };
+            let ieee = device.ieee_address;
+            let Some(seed_shift) = (0..16).find(|shift| {
+                derive_link_key(&seed, *shift, ieee) == link_key.key
+            }) else {
+                warn!(
+                    "Link key of {ieee:?} is not derived from the TCLK seed, \
+                    the device has to rejoin"
+                );
+                continue;
+            };
+            entries.push(encode(&TclkDevEntry {
+                tx_frame_counter: link_key.tx_counter,
+                rx_frame_counter: link_key.rx_counter,
+                ext_addr: ieee,
+                key_attributes: key_attributes::VERIFIED_KEY,
+                key_type: 0,
+                seed_shift,
+            }));
+        }
+
+        let existing = self
+            .read_table(version, &TCLK_TABLE)
+            .await
+            .map_err(|source| RestoreError::Reading {
+                item: TCLK_TABLE.name,
+                source,
+            })?
+            .len();
+        let len = existing.max(entries.len());
+        entries.resize(len, encode(&TclkDevEntry::EMPTY));
+        self.write_table(version, &TCLK_TABLE, entries).await
+    }
+
+    /// One item per entry
+    async fn read_address_manager(
+        &self,
+        version: ZStackVersion,
+    ) -> Result<Vec<Vec<u8>>, ReadError> {
+        if version == ZStackVersion::V3x0 {
+            return self.read_ex_table(ids::ex::ADDRMGR).await;
+        }
+        let table = self.read_nvram_item(ids::ADDRMGR).await?;
+        Ok(table
+            .chunks_exact(AddrMgrEntry::SIZE)
+            .map(<[u8]>::to_vec)
+            .collect())
+    }
+
+    /// Keeps the size of the existing table, the firmware created it with
+    /// the size it was compiled with.
+    async fn write_address_manager(
+        &self,
+        version: ZStackVersion,
+        devices: &[BackupDevice],
+    ) -> Result<(), RestoreError> {
+        const TABLE: &str = "address manager table";
+        let existing = match self.read_address_manager(version).await {
+            Ok(entries) => entries.len(),
+            Err(ReadError::DoesNotExist(_)) => devices.len(),
+            Err(source) => {
+                return Err(RestoreError::Reading {
+                    item: TABLE,
+                    source,
+                });
+            }
+        };
+        if devices.len() > existing {
+            return Err(RestoreError::TableFull {
+                table: TABLE,
+                max: existing,
+                needed: devices.len(),
+            });
+        }
+
+        let mut entries: Vec<_> = devices
+            .iter()
+            .map(|device| {
+                let mut user = addr_mgr_user::SECURITY;
+                if device.is_child {
+                    user |= addr_mgr_user::ASSOC;
+                }
+                encode(&AddrMgrEntry {
+                    user,
+                    nwk_addr: device.nwk_address,
+                    ext_addr: device.ieee_address,
+                })
+            })
+            .collect();
+        entries.resize(existing, encode(&AddrMgrEntry::EMPTY));
+
+        if version == ZStackVersion::V3x0 {
+            return self.write_ex_table(TABLE, ids::ex::ADDRMGR, entries).await;
+        }
+        self.write_nvram_item(ids::ADDRMGR, &entries.concat())
+            .await
+            .map_err(|source| RestoreError::Writing {
+                item: TABLE,
+                source,
+            })
+    }
+
+    /// Reads entries until the first missing one
+    async fn read_table(
+        &self,
+        version: ZStackVersion,
+        table: &Table,
+    ) -> Result<Vec<Vec<u8>>, ReadError> {
+        if version == ZStackVersion::V3x0 {
+            return self.read_ex_table(table.ex_item).await;
+        }
+        let mut entries = Vec::new();
+        for id in table.legacy_start.0..=table.legacy_end.0 {
+            match self.read_nvram_item(NvId(id)).await {
+                Ok(entry) => entries.push(entry),
+                Err(ReadError::DoesNotExist(_)) => break,
+                Err(other) => return Err(other),
+            }
+        }
+        Ok(entries)
+    }
+
+    async fn read_ex_table(
+        &self,
+        item_id: u16,
+    ) -> Result<Vec<Vec<u8>>, ReadError> {
+        let mut entries = Vec::new();
+        for sub_id in 0..=u16::MAX {
+            let id = ExNvId {
+                sys_id: NvSysId::ZStack,
+                item_id,
+                sub_id,
+            };
+            match self.read_ex_nvram_item(id).await {
+                Ok(entry) => entries.push(entry),
+                Err(ReadError::ExDoesNotExist(_)) => break,
+                Err(other) => return Err(other),
+            }
+        }
+        Ok(entries)
+    }
+
+    async fn write_table(
+        &self,
+        version: ZStackVersion,
+        table: &Table,
+        entries: Vec<Vec<u8>>,
+    ) -> Result<(), RestoreError> {
+        if version == ZStackVersion::V3x0 {
+            return self
+                .write_ex_table(table.name, table.ex_item, entries)
+                .await;
+        }
+        let max = usize::from(table.legacy_end.0 - table.legacy_start.0) + 1;
+        if entries.len() > max {
+            return Err(RestoreError::TableFull {
+                table: table.name,
+                max,
+                needed: entries.len(),
+            });
+        }
+        for (i, entry) in entries.iter().enumerate() {
+            let id = NvId(table.legacy_start.0 + i as u16);
+            self.write_nvram_item(id, entry).await.map_err(|source| {
+                RestoreError::Writing {
+                    item: table.name,
+                    source,
+                }
+            })?;
+        }
+        Ok(())
+    }
+
+    async fn write_ex_table(
+        &self,
+        name: &'static str,
+        item_id: u16,
+        entries: Vec<Vec<u8>>,
+    ) -> Result<(), RestoreError> {
+        for (sub_id, entry) in entries.iter().enumerate() {
+            let id = ExNvId {
+                sys_id: NvSysId::ZStack,
+                item_id,
+                sub_id: sub_id as u16,
+            };
+            self.write_ex_nvram_item(id, entry)
+                .await
+                .map_err(|source| RestoreError::Writing {
+                    item: name,
+                    source,
+                })?;
+        }
+        Ok(())
+    }
+}
+
+/// Z-Stack does not store the link key of each device. It is the seed
+/// rotated by `shift` bytes xor-ed with the IEEE address repeated twice.
+fn derive_link_key(seed: &Key, shift: u8, ieee: IeeeAddr) -> Key {
+    let ieee = ieee.0.to_le_bytes();
+    Key(std::array::from_fn(|i| {
+        seed.0[(i + usize::from(shift)) % seed.0.len()] ^ ieee[i % ieee.len()]
+    }))
+}
+
+fn encode(value: &impl Serialize) -> Vec<u8> {
+    data_format::to_vec(value).expect("nvram items only contain integers")
+}
+
+fn decode<T: DeserializeOwned>(
+    item: &'static str,
+) -> impl Fn(Vec<u8>) -> Result<T, BackupError> {
+    move |bytes| {
+        data_format::from_reader(&mut Cursor::new(bytes))
+            .map_err(|source| BackupError::Decoding { item, source })
+    }
+}
+
+fn reading(item: &'static str) -> impl Fn(ReadError) -> BackupError {
+    move |source| BackupError::Reading { item, source }
+}
+
+fn writing(item: &'static str) -> impl Fn(WriteError) -> RestoreError {
+    move |source| RestoreError::Writing { item, source }
+}
+
+/// Values in the backup are hex strings, numbers and addresses are written
+/// most significant byte first.
+mod hex {
+    use serde::{Deserialize, Deserializer, Serializer, de};
+    use zstacker_znp_protocol::commands::{IeeeAddr, Key, ShortAddr};
+
+    pub(super) trait HexBytes: Sized {
+        fn to_bytes(&self) -> Vec<u8>;
+        fn from_bytes(bytes: &[u8]) -> Option<Self>;
+    }
+
+    impl HexBytes for u16 {
+        fn to_bytes(&self) -> Vec<u8> {
+            self.to_be_bytes().to_vec()
+        }
+        fn from_bytes(bytes: &[u8]) -> Option<Self> {
+            bytes.try_into().ok().map(u16::from_be_bytes)
+        }
+    }
+
+    impl HexBytes for u64 {
+        fn to_bytes(&self) -> Vec<u8> {
+            self.to_be_bytes().to_vec()
+        }
+        fn from_bytes(bytes: &[u8]) -> Option<Self> {
+            bytes.try_into().ok().map(u64::from_be_bytes)
+        }
+    }
+
+    impl HexBytes for ShortAddr {
+        fn to_bytes(&self) -> Vec<u8> {
+            self.0.to_bytes()
+        }
+        fn from_bytes(bytes: &[u8]) -> Option<Self> {
+            u16::from_bytes(bytes).map(ShortAddr)
+        }
+    }
+
+    impl HexBytes for IeeeAddr {
+        fn to_bytes(&self) -> Vec<u8> {
+            self.0.to_bytes()
+        }
+        fn from_bytes(bytes: &[u8]) -> Option<Self> {
+            u64::from_bytes(bytes).map(IeeeAddr)
+        }
+    }
+
+    impl HexBytes for Key {
+        fn to_bytes(&self) -> Vec<u8> {
+            self.0.to_vec()
+        }
+        fn from_bytes(bytes: &[u8]) -> Option<Self> {
+            bytes.try_into().ok().map(Key)
+        }
+    }
+
+    pub(super) fn serialize<T: HexBytes, S: Serializer>(
+        value: &T,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        let hex: String = value
+            .to_bytes()
+            .iter()
+            .map(|b| format!("{b:02x}"))
+            .collect();
+        serializer.serialize_str(&hex)
+    }
+
+    pub(super) fn deserialize<'de, T: HexBytes, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<T, D::Error> {
+        let hex = String::deserialize(deserializer)?;
+        let invalid =
+            || de::Error::invalid_value(de::Unexpected::Str(&hex), &"hex");
+        if hex.len() % 2 != 0 || !hex.is_ascii() {
+            return Err(invalid());
+        }
+        let bytes = (0..hex.len())
+            .step_by(2)
+            .map(|i| u8::from_str_radix(&hex[i..i + 2], 16))
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|_| invalid())?;
+        T::from_bytes(&bytes).ok_or_else(|| {
+            de::Error::invalid_length(bytes.len(), &"a different length")
+        })
+    }
+}
@@ -0,0 +1,125 @@
+//! Bindings make a device send the messages of a cluster to another device
+//! or to a group directly, without going through the coordinator.
+
+use tracing::instrument;
+use zstacker_znp_protocol::commands::af::ClusterId;
+use zstacker_znp_protocol::commands::zdo::{
+    BindDestination, BindReq, BindTable, MgmtBindReq, UnbindReq,
+};
+use zstacker_znp_protocol::commands::{IeeeAddr, ShortAddr, Status};
+
+use crate::coordinator::{Coordinator, QueueError};
+use crate::list::get_entire_list;
+use crate::resolve::ResolveError;
+
+#[derive(Debug, thiserror::Error)]
+pub enum BindError {
+    #[error("Could not look up the short address of the source device")]
+    Resolve(#[source] ResolveError),
+    #[error("Could not send request or receive its response")]
+    Queue(#[source] QueueError),
+    #[error("Device rejected the request: {0:?}")]
+    Rejected(Status),
+}
+
+impl Coordinator {
+    /// Makes endpoint `src_endpoint` of device `src_addr` send the messages
+    /// of `cluster_id` to `dst`.
+    #[instrument(skip(self), err)]
+    pub async fn bind(
+        &self,
+        src_addr: IeeeAddr,
+        src_endpoint: u8,
+        cluster_id: ClusterId,
+        dst: BindDestination,
+    ) -> Result<(), BindError> {
+        let dst_addr = self
+            .resolve_short(src_addr)
+            .await
+            .map_err(BindError::Resolve)?;
+        let (dst_address, dst_endpoint) = wire_destination(dst);
+        let rsp = self
+            .queue_async(BindReq {
+                dst_addr,
+                src_addr,
+                src_endpoint,
+                cluster_id,
+                dst_addr_mode: dst.addr_mode(),
+                dst_address,
+                dst_endpoint,
+            })
+            .await
+            .map_err(BindError::Queue)?;
+        check_status(rsp.status)
+    }
+
+    /// Removes a binding added with [`Coordinator::bind`]
+    #[instrument(skip(self), err)]
+    pub async fn unbind(
+        &self,
+        src_addr: IeeeAddr,
+        src_endpoint: u8,
+        cluster_id: ClusterId,
+        dst: BindDestination,
+    ) -> Result<(), BindError> {
+        let dst_addr = self
+            .resolve_short(src_addr)
+            .await
+            .map_err(BindError::Resolve)?;
+        let (dst_address, dst_endpoint) = wire_destination(dst);
+        let rsp = self
+            .queue_async(UnbindReq {
+                dst_addr,
+                src_addr,
+                src_endpoint,
+                cluster_id,
+                dst_addr_mode: dst.addr_mode(),
+                dst_address,
+                dst_endpoint,
+            })
+            .await
+            .map_err(BindError::Queue)?;
+        check_status(rsp.status)
+    }
+
+    /// Every entry in the binding table of the device at `addr`. Devices
+    /// return a few entries at a time, this requests all of them.
+    #[instrument(skip(self), err)]
+    pub async fn binding_table(
+        &self,
+        addr: ShortAddr,
+    ) -> Result<Vec<BindTable>, BindError> {
+        get_entire_list(async |start_index| {
+            let rsp = self
+                .queue_async(MgmtBindReq {
+                    dst_addr: addr,
+                    start_index,
+                })
+                .await
+                .map_err(BindError::Queue)?;
+            check_status(rsp.status)?;
+            Ok(rsp.binding_table)
+        })
+        .await
+    }
+}
+
+/// Bind requests always carry an IEEE address and endpoint, a group id is
+/// send in the lowest two bytes of the address.
+fn wire_destination(dst: BindDestination) -> (IeeeAddr, u8) {
+    match dst {
+        BindDestination::Group(group_id) => (IeeeAddr(u64::from(group_id)), 0),
+        BindDestination::Device {
+            ieee_addr,
+            endpoint,
+        } => (ieee_addr, endpoint),
+    }
+}
+
+fn check_status(status: Status) -> Result<(), BindError> {
+    if status.is_success() {
+        Ok(())
+    } else {
+        Err(BindError::Rejected(status))
+    }
+}
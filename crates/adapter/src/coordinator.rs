@@ -1,23 +1,40 @@
+use std::any::Any;
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU8, Ordering};
 use std::time::Duration;
 
-use tokio::sync::{mpsc, oneshot};
+use futures::{FutureExt as _, Stream};
+use tokio::sync::{Mutex, broadcast, mpsc, oneshot, watch};
 use tokio::task;
-use tokio_serial::SerialStream;
 use tokio_util::time::FutureExt as _;
-use tracing::{debug, error, instrument, trace};
+use tracing::{debug, instrument, trace};
 use zstacker_znp_protocol::commands::util::DeviceInfo;
-use zstacker_znp_protocol::commands::{AsyncReply, ReplyError, SyncReply};
 use zstacker_znp_protocol::commands::{
-    AsyncRequest, CommandError, IeeeAddr, Pattern, ShortAddr, SyncRequest,
+    AsyncNotify, AsyncRequest, CommandError, CommandType, IeeeAddr, Pattern,
+    ShortAddr, SyncRequest,
 };
+use zstacker_znp_protocol::commands::{AsyncReply, ReplyError, SyncReply};
 use zstacker_znp_protocol::framing::CommandMeta;
 
+use crate::resolve::AddressCache;
+use crate::supervisor::ConnectionState;
+use crate::transport::{BoxedTransport, Transport};
+
 type Data = Vec<u8>;
 
 mod io_task;
+pub use io_task::Error as IoTaskError;
+pub use io_task::{NETWORK_BYTE_TIMEOUT, ReaderStats, SERIAL_BYTE_TIMEOUT};
+mod subscription;
+use subscription::Notification;
+pub use subscription::SubscriptionError;
 
+#[derive(Debug)]
 struct PendingSend {
     awnser_to: oneshot::Sender<Data>,
+    /// Signalled once the request has been written to the adaptor
+    written: Option<oneshot::Sender<()>>,
     to_send: Vec<u8>,
     reply_meta: CommandMeta,
     status_reply: Option<CommandMeta>,
@@ -26,77 +43,251 @@ struct PendingSend {
     reply_pattern: Pattern,
 }
 
-#[derive(Debug)]
+impl PendingSend {
+    /// Whether the request is an SREQ, these are answered with an SRSP
+    fn expects_srsp(&self) -> bool {
+        self.status_reply.is_some() || self.reply_meta.ty == CommandType::SRSP
+    }
+}
+
+/// Cheap to clone, all clones share the same connection. The io task ends
+/// once the last clone is dropped.
+#[derive(Debug, Clone)]
 pub struct Adaptor {
+    shared: Arc<Shared>,
+}
+
+#[derive(Debug)]
+pub(crate) struct Shared {
+    /// Outlives the io task so subscriptions survive a restart
+    notifications: broadcast::Sender<Notification>,
+    connection_state: watch::Sender<ConnectionState>,
+    reader_stats: Arc<io_task::Stats>,
+    /// Kept for restarting the io task
+    byte_timeout: Duration,
+    io_task: Mutex<IoTask>,
+}
+
+#[derive(Debug)]
+struct IoTask {
+    /// Increased every time the io task is restarted
+    generation: u64,
     to_io_task: mpsc::Sender<PendingSend>,
-    io_task:
-        Option<task::JoinHandle<(SerialStream, Result<(), io_task::Error>)>>,
+    handle:
+        Option<task::JoinHandle<(BoxedTransport, Result<(), io_task::Error>)>>,
 
     // These are only some after a critical error forces the IO task to stop
-    io_task_error: Option<io_task::Error>,
-    recovered_serial: Option<SerialStream>,
+    error: Option<io_task::Error>,
+    recovered_transport: Option<BoxedTransport>,
 }
 
+impl IoTask {
+    fn spawn(
+        transport: BoxedTransport,
+        generation: u64,
+        notifications: broadcast::Sender<Notification>,
+        connection_state: watch::Sender<ConnectionState>,
+        stats: Arc<io_task::Stats>,
+        byte_timeout: Duration,
+    ) -> Self {
+        let (tx, rx) = mpsc::channel(100);
+        let handle = task::spawn(async move {
+            let io_task = io_task::io_task(
+                transport,
+                rx,
+                notifications,
+                stats,
+                byte_timeout,
+            );
+            let (transport, res) =
+                match AssertUnwindSafe(io_task).catch_unwind().await {
+                    Ok(ended) => ended,
+                    Err(panic) => {
+                        let err =
+                            io_task::Error::Panicked(panic_message(&panic));
+                        connection_state
+                            .send_replace(ConnectionState::Disconnected(err));
+                        // Callers learn about it through the join handle
+                        panic::resume_unwind(panic);
+                    }
+                };
+            if let Err(err) = &res {
+                connection_state
+                    .send_replace(ConnectionState::Disconnected(err.clone()));
+            }
+            (transport, res)
+        });
+        Self {
+            generation,
+            to_io_task: tx,
+            handle: Some(handle),
+            error: None,
+            recovered_transport: None,
+        }
+    }
+}
+
+fn panic_message(panic: &(dyn Any + Send)) -> String {
+    if let Some(message) = panic.downcast_ref::<&str>() {
+        message.to_string()
+    } else if let Some(message) = panic.downcast_ref::<String>() {
+        message.clone()
+    } else {
+        "panic payload is not a string".to_string()
+    }
+}
+
+/// Cheap to clone, clone it to issue requests from multiple tasks at
+/// once. The io task writes them to the adaptor one at a time.
+#[derive(Debug, Clone)]
 pub struct Coordinator {
     pub short_addr: ShortAddr,
     pub ieee_addr: IeeeAddr,
     adaptor: Adaptor,
+    transaction_id: Arc<AtomicU8>,
+    addresses: Arc<AddressCache>,
 }
 
+/// A [`Coordinator`] handed to another task. Coordinators are cheap to
+/// clone and all their methods take `&self`.
+pub type CoordinatorHandle = Coordinator;
+
 impl Coordinator {
     pub(crate) fn start(device_info: DeviceInfo, adaptor: Adaptor) -> Self {
         Self {
             short_addr: device_info.short_addr,
             ieee_addr: device_info.ieee_addr,
+            addresses: Arc::new(AddressCache::new(adaptor.subscribe())),
             adaptor,
+            transaction_id: Arc::new(AtomicU8::new(0)),
         }
     }
 
+    /// Used to match confirmations to the message they confirm
+    pub(crate) fn next_transaction_id(&self) -> u8 {
+        self.transaction_id
+            .fetch_add(1, Ordering::Relaxed)
+            .wrapping_add(1)
+    }
+
     pub async fn queue_sync<R: SyncRequest>(
-        &mut self,
+        &self,
         req: R,
     ) -> Result<R::Reply, QueueError> {
         self.adaptor.queue_sync(req).await
     }
     pub async fn queue_async<R: AsyncRequest>(
-        &mut self,
+        &self,
         req: R,
     ) -> Result<R::Reply, QueueError> {
         self.adaptor.queue_async(req).await
     }
+
+    /// Stream of every `N` the device sends from now on. Also yields
+    /// notifications that where the reply to a request.
+    pub fn subscribe<N: AsyncNotify + Send + 'static>(
+        &self,
+    ) -> impl Stream<Item = Result<N, SubscriptionError>> + Send + Unpin + 'static
+    {
+        self.adaptor.subscribe()
+    }
+
+    /// See [`Adaptor::connection_state`]
+    pub fn connection_state(
+        &self,
+    ) -> impl Stream<Item = ConnectionState> + Send + Unpin + 'static {
+        self.adaptor.connection_state()
+    }
+
+    /// See [`Adaptor::reader_stats`]
+    pub fn reader_stats(&self) -> ReaderStats {
+        self.adaptor.reader_stats()
+    }
+
+    pub(crate) fn adaptor(&self) -> &Adaptor {
+        &self.adaptor
+    }
+
+    pub(crate) fn addresses(&self) -> &AddressCache {
+        &self.addresses
+    }
 }
 
 impl Adaptor {
-    pub fn start(serial: SerialStream) -> Self {
-        let (tx, rx) = mpsc::channel(100);
+    /// Talk to the adaptor over any [`Transport`], such as a serial port
+    pub fn start(transport: impl Transport) -> Self {
+        Self::start_with_byte_timeout(transport, SERIAL_BYTE_TIMEOUT)
+    }
+
+    /// Like [`Adaptor::start`], for transports that may pause in the middle
+    /// of a frame. A frame is dropped once no byte arrives for
+    /// `byte_timeout`, see [`NETWORK_BYTE_TIMEOUT`].
+    pub fn start_with_byte_timeout(
+        transport: impl Transport,
+        byte_timeout: Duration,
+    ) -> Self {
+        let (notifications, _) = broadcast::channel(subscription::CAPACITY);
+        let (connection_state, _) = watch::channel(ConnectionState::Connected);
+        let reader_stats = Arc::default();
+        let io_task = IoTask::spawn(
+            Box::new(transport),
+            0,
+            notifications.clone(),
+            connection_state.clone(),
+            Arc::clone(&reader_stats),
+            byte_timeout,
+        );
         Self {
-            to_io_task: tx,
-            io_task: Some(task::spawn(io_task::io_task(serial, rx))),
-            io_task_error: None,
-            recovered_serial: None,
+            shared: Arc::new(Shared {
+                notifications,
+                connection_state,
+                reader_stats,
+                byte_timeout,
+                io_task: Mutex::new(io_task),
+            }),
         }
     }
 
-    /// May wait until there is space in the receive buffer
+    pub(crate) fn from_shared(shared: Arc<Shared>) -> Self {
+        Self { shared }
+    }
+
+    pub(crate) fn downgrade(&self) -> std::sync::Weak<Shared> {
+        Arc::downgrade(&self.shared)
+    }
+
+    /// Connect to a network attached coordinator or a serial port shared
+    /// through ser2net.
+    pub async fn connect_tcp(
+        addr: impl tokio::net::ToSocketAddrs,
+    ) -> std::io::Result<Self> {
+        let stream = tokio::net::TcpStream::connect(addr).await?;
+        // Requests are small and latency matters more then throughput
+        stream.set_nodelay(true)?;
+        Ok(Self::start_with_byte_timeout(stream, NETWORK_BYTE_TIMEOUT))
+    }
+
+    /// May wait until there is space in the receive buffer and for
+    /// earlier requests to be answered.
     #[instrument(skip(self), err)]
     pub async fn queue_sync<R: SyncRequest>(
-        &mut self,
+        &self,
         req: R,
     ) -> Result<R::Reply, QueueError> {
-        let (tx, rx) = tokio::sync::oneshot::channel();
-        self.to_io_task
-            .send(PendingSend {
-                awnser_to: tx,
-                to_send: req.to_frame().map_err(QueueError::Serializing)?,
-                reply_meta: R::Reply::META,
-                status_reply: None,
-                reply_pattern: req.reply_pattern(),
-            })
-            .await
-            .expect("Io-task is dropped after queue_requests");
+        let (tx, rx) = oneshot::channel();
+        let generation = self
+            .send_to_io_task(
+                tx,
+                req.to_frame(),
+                R::Reply::META,
+                None,
+                req.reply_pattern(),
+                R::TIMEOUT,
+            )
+            .await?;
         match rx.timeout(R::TIMEOUT).await {
             Err(_) => Err(QueueError::ReplyNotImmediate),
-            Ok(Err(_)) => Err(self.io_task_error().await),
+            Ok(Err(_)) => Err(self.io_task_error(generation).await),
             Ok(Ok(data)) => {
                 trace!("Raw reply: {data:?}");
                 let reply = R::Reply::from_data(&data)
@@ -107,29 +298,30 @@ impl Adaptor {
         }
     }
 
-    /// May wait until there is space in the receive buffer
+    /// May wait until there is space in the receive buffer and for
+    /// earlier requests to be answered.
     #[instrument(skip(self), err)]
     pub async fn queue_async<R: AsyncRequest>(
-        &mut self,
+        &self,
         req: R,
     ) -> Result<R::Reply, QueueError> {
-        let (tx, rx) = tokio::sync::oneshot::channel();
-        self.to_io_task
-            .send(PendingSend {
-                awnser_to: tx,
-                to_send: req.to_frame().map_err(QueueError::Serializing)?,
-                reply_meta: R::Reply::META,
-                status_reply: R::status_reply_meta(),
-                reply_pattern: req.reply_pattern(),
-            })
-            .await
-            .expect("io-task is dropped after queue_requests");
+        let (tx, rx) = oneshot::channel();
+        let generation = self
+            .send_to_io_task(
+                tx,
+                req.to_frame(),
+                R::Reply::META,
+                R::status_reply_meta(),
+                req.reply_pattern(),
+                R::TIMEOUT,
+            )
+            .await?;
 
         match rx.timeout(R::TIMEOUT).await {
             Err(_) => Err(QueueError::TimedOut {
                 timeout: R::TIMEOUT,
             }),
-            Ok(Err(_)) => Err(self.io_task_error().await),
+            Ok(Err(_)) => Err(self.io_task_error(generation).await),
             Ok(Ok(data)) => {
                 trace!("Raw reply: {data:?}");
                 let reply = R::Reply::from_data(&data)
@@ -140,26 +332,145 @@ impl Adaptor {
         }
     }
 
-    async fn io_task_error(&mut self) -> QueueError {
-        if let Some(err) = self.io_task_error.clone() {
+    /// Returns once the io task has written the request. Waiting behind
+    /// other requests is limited to `timeout`, the reply then gets the
+    /// full timeout again. Returns the generation of the io task that
+    /// wrote it.
+    async fn send_to_io_task(
+        &self,
+        awnser_to: oneshot::Sender<Data>,
+        to_send: Result<Vec<u8>, CommandError>,
+        reply_meta: CommandMeta,
+        status_reply: Option<CommandMeta>,
+        reply_pattern: Pattern,
+        timeout: Duration,
+    ) -> Result<u64, QueueError> {
+        let (written, is_written) = oneshot::channel();
+        let pending = PendingSend {
+            awnser_to,
+            written: Some(written),
+            to_send: to_send.map_err(QueueError::Serializing)?,
+            reply_meta,
+            status_reply,
+            reply_pattern,
+        };
+        let (generation, to_io_task) = {
+            let io_task = self.shared.io_task.lock().await;
+            (io_task.generation, io_task.to_io_task.clone())
+        };
+        if to_io_task.send(pending).await.is_err() {
+            return Err(self.io_task_error(generation).await);
+        }
+        match is_written.timeout(timeout).await {
+            Err(_) => Err(QueueError::TimedOut { timeout }),
+            Ok(Err(_)) => Err(self.io_task_error(generation).await),
+            Ok(Ok(())) => Ok(generation),
+        }
+    }
+
+    /// See [`Coordinator::subscribe`]
+    pub fn subscribe<N: AsyncNotify + Send + 'static>(
+        &self,
+    ) -> impl Stream<Item = Result<N, SubscriptionError>> + Send + Unpin + 'static
+    {
+        subscription::subscribe(self.shared.notifications.subscribe())
+    }
+
+    /// The current state of the connection followed by every change. Only
+    /// a [supervised](crate::supervisor) connection recovers after it is
+    /// lost.
+    pub fn connection_state(
+        &self,
+    ) -> impl Stream<Item = ConnectionState> + Send + Unpin + 'static {
+        let mut rx = self.connection_state_receiver();
+        rx.mark_changed();
+        Box::pin(futures::stream::unfold(rx, |mut rx| async move {
+            rx.changed().await.ok()?;
+            let state = rx.borrow_and_update().clone();
+            Some((state, rx))
+        }))
+    }
+
+    /// Counts of the frames read from the adaptor and the noise skipped
+    /// between them.
+    pub fn reader_stats(&self) -> ReaderStats {
+        self.shared.reader_stats.snapshot()
+    }
+
+    pub(crate) fn connection_state_receiver(
+        &self,
+    ) -> watch::Receiver<ConnectionState> {
+        self.shared.connection_state.subscribe()
+    }
+
+    pub(crate) fn set_connection_state(&self, state: ConnectionState) {
+        self.shared.connection_state.send_replace(state);
+    }
+
+    /// Replaces the io task with one talking over `transport`. Requests
+    /// still waiting on the previous io task fail.
+    pub(crate) async fn restart_io_task(&self, transport: BoxedTransport) {
+        let mut io_task = self.shared.io_task.lock().await;
+        if let Some(previous) = io_task.handle.take() {
+            previous.abort();
+        }
+        *io_task = IoTask::spawn(
+            transport,
+            io_task.generation + 1,
+            self.shared.notifications.clone(),
+            self.shared.connection_state.clone(),
+            Arc::clone(&self.shared.reader_stats),
+            self.shared.byte_timeout,
+        );
+    }
+
+    /// Stops the io task if it is still running and waits for it to let go
+    /// of its transport. Returns that transport if the io task ended on an
+    /// error, it may still be usable.
+    pub(crate) async fn stop_io_task(&self) -> Option<BoxedTransport> {
+        let mut io_task = self.shared.io_task.lock().await;
+        if let Some(handle) = io_task.handle.take() {
+            handle.abort();
+            let err = match handle.await {
+                Ok((transport, Err(err))) => {
+                    io_task.recovered_transport = Some(transport);
+                    err
+                }
+                Ok((_, Ok(()))) => {
+                    unreachable!(
+                        "Io task only ends without error if Self is dropped"
+                    )
+                }
+                Err(join_error) if join_error.is_panic() => {
+                    io_task::Error::Panicked(join_error.to_string())
+                }
+                // Aborted, the transport was dropped with the task
+                Err(_) => io_task::Error::Restarted,
+            };
+            io_task.error = Some(err);
+        }
+        io_task.recovered_transport.take()
+    }
+
+    async fn io_task_error(&self, generation: u64) -> QueueError {
+        let mut io_task = self.shared.io_task.lock().await;
+        if io_task.generation != generation {
+            return QueueError::IoTask(io_task::Error::Restarted);
+        }
+        if let Some(err) = io_task.error.clone() {
             return QueueError::IoTask(err);
         }
 
-        let Some(io_task) = self.io_task.take() else {
+        let Some(handle) = io_task.handle.take() else {
             unreachable!(
                 "after we take the io_task we always set the io_task_error"
             );
         };
 
-        assert!(
-            io_task.is_finished(),
-            "a requests awnser rx is only dropped when the io_task returns"
-        );
-
-        match io_task.await {
-            Ok((serial, Err(err))) => {
-                self.io_task_error = Some(err.clone());
-                self.recovered_serial = Some(serial);
+        match handle.await {
+            Ok((transport, Err(err))) => {
+                io_task.error = Some(err.clone());
+                io_task.recovered_transport = Some(transport);
                 QueueError::IoTask(err)
             }
             Ok((_, Ok(()))) => {
@@ -169,7 +480,7 @@ impl Adaptor {
             }
             Err(join_error) => {
                 let err = io_task::Error::Panicked(join_error.to_string());
-                self.io_task_error = Some(err.clone());
+                io_task.error = Some(err.clone());
                 QueueError::IoTask(err)
             }
         }
@@ -2,18 +2,24 @@ use futures::future::FutureExt;
 use futures_concurrency::future::Race;
 use reader::FrameReader;
 use std::sync::Arc;
-use tokio::sync::mpsc;
-use tracing::error;
+use std::time::Duration;
+use tokio::sync::{broadcast, mpsc};
+use tokio::time::Instant;
+use tracing::{debug, error, warn};
 
 use tokio::io::AsyncWriteExt;
-use tokio_serial::SerialStream;
+use zstacker_znp_protocol::commands::CommandType;
 use zstacker_znp_protocol::framing::CommandMeta;
 
 use super::PendingSend;
+use super::subscription::Notification;
+use crate::transport::Transport;
 
 pub mod dispatch;
 use dispatch::ReplyHandler;
 mod reader;
+pub(crate) use reader::Stats;
+pub use reader::{NETWORK_BYTE_TIMEOUT, ReaderStats, SERIAL_BYTE_TIMEOUT};
 
 #[derive(Debug, Clone, thiserror::Error)]
 pub enum Error {
@@ -27,37 +33,97 @@ pub enum Error {
     ReadingFrameIo(#[source] reader::Error),
     #[error("IO task panicked, panick info: {0:?}")]
     Panicked(String),
+    #[error("IO task was replaced by a new one after reconnecting")]
+    Restarted,
 }
 
-pub async fn io_task(
-    mut serial: SerialStream,
+/// Z-Stack handles one synchronous request at a time. If it does not
+/// respond within this time we assume it never will and move on.
+const SRSP_TIMEOUT: Duration = Duration::from_secs(6);
+
+pub async fn io_task<T: Transport>(
+    mut transport: T,
     mut rx: mpsc::Receiver<PendingSend>,
-) -> (SerialStream, Result<(), Error>) {
+    notifications: broadcast::Sender<Notification>,
+    stats: Arc<Stats>,
+    byte_timeout: Duration,
+) -> (T, Result<(), Error>) {
     let mut reply_handler = ReplyHandler::new();
 
     enum Event {
         Received(Option<PendingSend>),
         ReadMeta(Result<(CommandMeta, Vec<u8>), reader::Error>),
+        SrspTimedOut,
+        WaitingCallerGone,
     }
 
-    let mut reader = FrameReader::default();
+    let mut reader = FrameReader::new(stats, byte_timeout);
+    // Set while Z-Stack has not yet responded to the last SREQ we wrote,
+    // new requests wait in the channel until it does.
+    let mut awaiting_srsp: Option<Instant> = None;
     loop {
         reply_handler.collect_garbage();
-        let res = match (
-            rx.recv().map(Event::Received),
-            reader.read(&mut serial).map(Event::ReadMeta),
-        )
-            .race()
-            .await
-        {
+        let event = if let Some(deadline) = awaiting_srsp {
+            (
+                tokio::time::sleep_until(deadline).map(|_| Event::SrspTimedOut),
+                reader.read(&mut transport).map(Event::ReadMeta),
+            )
+                .race()
+                .await
+        } else if let Some(pending) = reply_handler.next_unblocked() {
+            Event::Received(Some(pending))
+        } else {
+            (
+                rx.recv().map(Event::Received),
+                reader.read(&mut transport).map(Event::ReadMeta),
+                reply_handler
+                    .waiting_caller_gone()
+                    .map(|_| Event::WaitingCallerGone),
+            )
+                .race()
+                .await
+        };
+        let res = match event {
             Event::Received(None) => {
                 tracing::warn!("Coordinator dropped, ending IO task");
-                return (serial, Ok(()));
+                return (transport, Ok(()));
+            }
+            Event::Received(Some(pending))
+                if reply_handler.conflicts(&pending) =>
+            {
+                debug!(
+                    "Request would get the same reply as one still pending, \
+                    queuing it until that one is answered"
+                );
+                reply_handler.queue(pending);
+                Ok(())
             }
             Event::Received(Some(pending)) => {
-                send_pending(&mut serial, pending, &mut reply_handler).await
+                if pending.expects_srsp() {
+                    awaiting_srsp = Some(Instant::now() + SRSP_TIMEOUT);
+                }
+                send_pending(&mut transport, pending, &mut reply_handler).await
             }
+            Event::SrspTimedOut => {
+                warn!(
+                    "Adaptor did not respond to SREQ within {SRSP_TIMEOUT:?}"
+                );
+                awaiting_srsp = None;
+                Ok(())
+            }
+            // Queued requests are checked at the top of the loop
+            Event::WaitingCallerGone => Ok(()),
             Event::ReadMeta(Ok((meta, data))) => {
+                if meta.ty == CommandType::SRSP {
+                    awaiting_srsp = None;
+                }
+                if meta.ty == CommandType::AREQ {
+                    let _no_subscribers_is_ok =
+                        notifications.send(Notification {
+                            meta: meta.clone(),
+                            data: data.clone(),
+                        });
+                }
                 reply_handler.process_reply(&meta, data);
                 Ok(())
             }
@@ -69,25 +135,28 @@ pub async fn io_task(
                 "Io task ran into error, coordinator needs to be restarted to \
                 recover. Error was: {err:?}"
             );
-            return (serial, Err(err));
+            return (transport, Err(err));
         }
     }
 }
 
 async fn send_pending(
-    serial: &mut SerialStream,
-    pending: PendingSend,
+    transport: &mut impl Transport,
+    mut pending: PendingSend,
     requests_expecting_reply: &mut ReplyHandler,
 ) -> Result<(), Error> {
     let to_send = pending.to_send.clone();
-    requests_expecting_reply.register(pending).expect(
-        "Having multiple requests with the same command \
-            pending is not supported",
-    );
-    serial
+    let written = pending.written.take();
+    requests_expecting_reply
+        .register(pending)
+        .expect("conflicting requests are queued instead of send");
+    transport
         .write_all(&to_send)
         .await
         .map_err(Arc::new)
         .map_err(Error::WritingIo)?;
+    if let Some(written) = written {
+        let _caller_gone_is_ok = written.send(());
+    }
     Ok(())
 }
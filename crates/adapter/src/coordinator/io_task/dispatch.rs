@@ -1,15 +1,18 @@
 // Future work: rewrite using `trie` data structure?
 // Future work: track usage per route too. Prevent overloading route
 
-use std::collections::HashMap;
 use std::collections::hash_map::Entry;
+use std::collections::{HashMap, VecDeque};
 use std::iter;
 use std::time::{Duration, Instant};
 
+use futures::future;
 use tokio::sync::oneshot::Sender;
 use zstacker_znp_protocol::commands::Pattern;
 use zstacker_znp_protocol::framing::CommandMeta;
 
+use crate::coordinator::PendingSend;
+
 #[derive(Debug)]
 struct PendingStatusReply {
     awnser_to: Sender<Vec<u8>>,
@@ -22,6 +25,11 @@ pub struct ReplyHandler {
     last_garbage_collect: Instant,
     status_handlers: HashMap<CommandMeta, PendingStatusReply>,
     normal_handlers: HashMap<CommandMeta, HashMap<Pattern, Sender<Vec<u8>>>>,
+    /// Requests that would be answered by the same reply as a request
+    /// already waiting for one, keyed by that reply. Send once that request
+    /// is done, in order for each reply. A request for another reply does
+    /// not wait behind them.
+    queued: HashMap<(CommandMeta, Pattern), VecDeque<PendingSend>>,
 }
 
 #[derive(Debug, thiserror::Error)]
@@ -34,13 +42,93 @@ impl ReplyHandler {
             last_garbage_collect: Instant::now(),
             status_handlers: HashMap::new(),
             normal_handlers: HashMap::new(),
+            queued: HashMap::new(),
+        }
+    }
+
+    /// Whether a reply to `pending` could not be told apart from the
+    /// reply to a request that is still waiting.
+    pub(crate) fn conflicts(&self, pending: &PendingSend) -> bool {
+        if let Some(meta) = &pending.status_reply
+            && self
+                .status_handlers
+                .get(meta)
+                .is_some_and(|h| !h.awnser_to.is_closed())
+        {
+            return true;
+        }
+        in_handlers(&self.normal_handlers, pending)
+            || self.status_handlers.values().any(|h| {
+                h.reply_meta == pending.reply_meta
+                    && h.reply_pattern == pending.reply_pattern
+                    && !h.awnser_to.is_closed()
+            })
+    }
+
+    /// Holds on to `pending` until it no longer conflicts
+    pub(crate) fn queue(&mut self, pending: PendingSend) {
+        let key = (pending.reply_meta.clone(), pending.reply_pattern.clone());
+        self.queued.entry(key).or_default().push_back(pending);
+    }
+
+    /// A queued request that can now be send, the oldest one for its
+    /// reply. Requests whose caller stopped waiting are dropped.
+    pub(crate) fn next_unblocked(&mut self) -> Option<PendingSend> {
+        self.queued.retain(|_, queue| {
+            queue.retain(|pending| !pending.awnser_to.is_closed());
+            !queue.is_empty()
+        });
+        let key = self
+            .queued
+            .iter()
+            .find(|(_, queue)| {
+                queue
+                    .front()
+                    .is_some_and(|pending| !self.conflicts(pending))
+            })
+            .map(|(key, _)| key.clone())?;
+        let Entry::Occupied(mut queue) = self.queued.entry(key) else {
+            unreachable!("key was just found in the queue")
+        };
+        let pending = queue.get_mut().pop_front();
+        if queue.get().is_empty() {
+            queue.remove();
         }
+        pending
+    }
+
+    /// Resolves once the caller of a request that is still waiting for
+    /// its reply goes away, that may unblock a queued request. Never
+    /// resolves while nothing is queued.
+    pub(crate) async fn waiting_caller_gone(&mut self) {
+        if self.queued.is_empty() {
+            return future::pending().await;
+        }
+        let closed: Vec<_> = self
+            .status_handlers
+            .values_mut()
+            .map(|handler| &mut handler.awnser_to)
+            .chain(
+                self.normal_handlers
+                    .values_mut()
+                    .flat_map(|p| p.values_mut()),
+            )
+            .filter(|sender| !sender.is_closed())
+            .map(|sender| Box::pin(sender.closed()))
+            .collect();
+        if closed.is_empty() {
+            return future::pending().await;
+        }
+        future::select_all(closed).await;
     }
 
     pub(crate) fn register(
         &mut self,
-        mut pending: crate::coordinator::PendingSend,
+        mut pending: PendingSend,
     ) -> Result<(), DuplicateEntry> {
+        if self.conflicts(&pending) {
+            return Err(DuplicateEntry);
+        }
         if let Some(meta) = pending.status_reply.take() {
             self.register_for_status_reply(meta, pending)
         } else {
@@ -48,23 +136,11 @@ impl ReplyHandler {
         }
     }
 
-    pub(crate) fn register_for_status_reply(
+    fn register_for_status_reply(
         &mut self,
         meta: CommandMeta,
-        pending: crate::coordinator::PendingSend,
+        pending: PendingSend,
     ) -> Result<(), DuplicateEntry> {
-        if self
-            .status_handlers
-            .get(&meta)
-            .is_some_and(|h| !h.awnser_to.is_closed())
-        {
-            return Err(DuplicateEntry);
-        }
-
-        if in_handlers(&self.normal_handlers, &pending) {
-            return Err(DuplicateEntry);
-        }
-
         self.status_handlers.insert(
             meta,
             PendingStatusReply {
@@ -78,17 +154,14 @@ impl ReplyHandler {
 
     fn register_for_normal_reply(
         &mut self,
-        pending: crate::coordinator::PendingSend,
+        pending: PendingSend,
     ) -> Result<(), DuplicateEntry> {
-        let mut res = Ok(());
         match self.normal_handlers.entry(pending.reply_meta) {
             Entry::Occupied(occupied) => {
-                match occupied.into_mut().entry(pending.reply_pattern) {
-                    Entry::Occupied(_) => res = Err(DuplicateEntry),
-                    Entry::Vacant(vacent) => {
-                        vacent.insert(pending.awnser_to);
-                    }
-                }
+                // Any existing handler is closed, checked by `conflicts`
+                occupied
+                    .into_mut()
+                    .insert(pending.reply_pattern, pending.awnser_to);
             }
             Entry::Vacant(vacant_entry) => {
                 let patterns =
@@ -97,7 +170,7 @@ impl ReplyHandler {
                 vacant_entry.insert(patterns);
             }
         }
-        res
+        Ok(())
     }
 
     pub(crate) fn process_reply(
@@ -157,7 +230,7 @@ impl ReplyHandler {
             self.status_handlers.remove(&key);
         }
 
-        for (_, handlers) in self.normal_handlers.iter_mut() {
+        for handlers in self.normal_handlers.values_mut() {
             let expired: Vec<Pattern> = handlers
                 .iter()
                 .filter(|(_, handler)| handler.is_closed())
@@ -173,14 +246,111 @@ impl ReplyHandler {
 
 fn in_handlers(
     handlers: &HashMap<CommandMeta, HashMap<Pattern, Sender<Vec<u8>>>>,
-    pending: &crate::coordinator::PendingSend,
+    pending: &PendingSend,
 ) -> bool {
     handlers
         .get(&pending.reply_meta)
         .map(|patterns| {
             patterns
                 .get(&pending.reply_pattern)
-                .is_some_and(|sender| sender.is_closed())
+                .is_some_and(|sender| !sender.is_closed())
         })
         .unwrap_or(false)
 }
+
+#[cfg(test)]
+mod tests {
+    use tokio::sync::oneshot;
+    use zstacker_znp_protocol::commands::zdo::{MgmtLqiReq, MgmtLqiRsp};
+    use zstacker_znp_protocol::commands::{
+        AsyncReply, AsyncRequest, ShortAddr,
+    };
+
+    use super::*;
+
+    fn lqi_req() -> (PendingSend, oneshot::Receiver<Vec<u8>>) {
+        lqi_req_to(ShortAddr(2))
+    }
+
+    fn lqi_req_to(
+        dst_addr: ShortAddr,
+    ) -> (PendingSend, oneshot::Receiver<Vec<u8>>) {
+        let req = MgmtLqiReq {
+            dst_addr,
+            start_index: 0,
+        };
+        let (tx, rx) = oneshot::channel();
+        let pending = PendingSend {
+            awnser_to: tx,
+            written: None,
+            to_send: Vec::new(),
+            reply_meta: MgmtLqiRsp::META,
+            status_reply: MgmtLqiReq::status_reply_meta(),
+            reply_pattern: req.reply_pattern(),
+        };
+        (pending, rx)
+    }
+
+    #[test]
+    fn identical_request_waits_for_first() {
+        let mut handler = ReplyHandler::new();
+        let (first, first_rx) = lqi_req();
+        handler.register(first).unwrap();
+        handler
+            .process_reply(&MgmtLqiReq::status_reply_meta().unwrap(), vec![0]);
+
+        let (second, _second_rx) = lqi_req();
+        assert!(handler.conflicts(&second));
+        handler.queue(second);
+        assert!(handler.next_unblocked().is_none());
+
+        drop(first_rx);
+        let second = handler.next_unblocked().unwrap();
+        handler.register(second).unwrap();
+    }
+
+    #[tokio::test]
+    async fn wakes_when_blocking_caller_leaves() {
+        let mut handler = ReplyHandler::new();
+        let (first, first_rx) = lqi_req();
+        handler.register(first).unwrap();
+        let (second, _second_rx) = lqi_req();
+        handler.queue(second);
+
+        tokio::spawn(async move {
+            tokio::task::yield_now().await;
+            drop(first_rx);
+        });
+        handler.waiting_caller_gone().await;
+        assert!(handler.next_unblocked().is_some());
+    }
+
+    #[test]
+    fn unrelated_request_is_not_delayed() {
+        let mut handler = ReplyHandler::new();
+        let (first, _first_rx) = lqi_req();
+        handler.register(first).unwrap();
+
+        // Both wait for the status reply of the first request
+        let (same, _same_rx) = lqi_req();
+        handler.queue(same);
+        let (other, _other_rx) = lqi_req_to(ShortAddr(3));
+        assert!(handler.conflicts(&other));
+        handler.queue(other);
+
+        handler
+            .process_reply(&MgmtLqiReq::status_reply_meta().unwrap(), vec![0]);
+        let next = handler.next_unblocked().unwrap();
+        assert_eq!(
+            next.reply_pattern,
+            MgmtLqiReq {
+                dst_addr: ShortAddr(3),
+                start_index: 0,
+            }
+            .reply_pattern()
+        );
+        handler.register(next).unwrap();
+        // The request to the first device still waits for its reply
+        assert!(handler.next_unblocked().is_none());
+    }
+}
@@ -1,13 +1,14 @@
 //! Pretty inefficient but it is cancel safe
 
+use std::collections::VecDeque;
 use std::iter;
 use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::time::Duration;
 
-use tokio::io::AsyncReadExt;
-use tokio_serial::SerialStream;
+use tokio::io::{AsyncRead, AsyncReadExt};
 use tokio_util::time::FutureExt;
-use tracing::{instrument, trace};
+use tracing::{debug, instrument, trace};
 use zstacker_znp_protocol::commands::START_OF_FRAME;
 use zstacker_znp_protocol::framing::{CommandMeta, CommandMetaError};
 
@@ -18,7 +19,7 @@ pub enum Error {
         START_OF_FRAME
     )]
     ExpectedStartOfFrame,
-    #[error("Could not read from serial")]
+    #[error("Could not read from the transport")]
     Io(#[source] Arc<std::io::Error>),
     #[error("Could not read from serial")]
     Deserialize(#[source] CommandMetaError),
@@ -28,13 +29,77 @@ pub enum Error {
     CheckSumMismatch,
 }
 
+/// Bytes without a start of frame in between after which we give up
+const MAX_GARBAGE: usize = 1024;
+/// Corrupt frames without a good one in between after which we give up
+const MAX_BAD_FRAMES: u32 = 8;
+/// Frames from a serial port arrive in one go, a longer pause between two
+/// bytes of a frame means it is cut short
+pub const SERIAL_BYTE_TIMEOUT: Duration = Duration::from_millis(50);
+/// Over TCP a frame may be split across packets that arrive far apart, for
+/// example when ser2net forwards a serial port over wifi
+pub const NETWORK_BYTE_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// Counts since the adaptor was started, these survive io task restarts
+#[derive(Debug, Default)]
+pub(crate) struct Stats {
+    frames: AtomicU64,
+    dropped_bytes: AtomicU64,
+    bad_frames: AtomicU64,
+}
+
+impl Stats {
+    pub(crate) fn snapshot(&self) -> ReaderStats {
+        ReaderStats {
+            frames: self.frames.load(Ordering::Relaxed),
+            dropped_bytes: self.dropped_bytes.load(Ordering::Relaxed),
+            bad_frames: self.bad_frames.load(Ordering::Relaxed),
+        }
+    }
+}
+
+/// How well reading frames from the adaptor is going. Some noise, for
+/// example from the bootloader after a reset, is normal.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ReaderStats {
+    /// Good frames read
+    pub frames: u64,
+    /// Bytes skipped while looking for the start of a frame. The start byte
+    /// of a bad frame is not counted here but in `bad_frames`, the bytes
+    /// after it are scanned again and counted if they do not start a frame.
+    pub dropped_bytes: u64,
+    /// Frames that were incomplete, had an invalid header or checksum
+    pub bad_frames: u64,
+}
+
+#[derive(Debug)]
+pub struct FrameReader {
+    state: State,
+    input: Input,
+    stats: Arc<Stats>,
+    garbage_in_a_row: usize,
+    bad_frames_in_a_row: u32,
+}
+
+/// The bytes of a frame that turns out to be bad may hold the start of a
+/// good one. Those are scanned again before reading further.
+#[derive(Debug)]
+struct Input {
+    /// Bytes to scan again before reading from the transport
+    replay: VecDeque<u8>,
+    /// Bytes read after the start of the current frame
+    in_frame: Vec<u8>,
+    /// Longest wait for the next byte once a frame started
+    byte_timeout: Duration,
+}
+
 #[derive(Debug)]
-pub enum FrameReader {
+enum State {
     ReadingMeta(MetaReader),
     ReadingData(DataReader),
 }
 
-impl Default for FrameReader {
+impl Default for State {
     fn default() -> Self {
         Self::ReadingMeta(MetaReader::default())
     }
@@ -56,57 +121,141 @@ pub struct DataReader {
 
 type Data = Vec<u8>;
 impl FrameReader {
+    pub(crate) fn new(stats: Arc<Stats>, byte_timeout: Duration) -> Self {
+        Self {
+            state: State::default(),
+            input: Input {
+                replay: VecDeque::new(),
+                in_frame: Vec::new(),
+                byte_timeout,
+            },
+            stats,
+            garbage_in_a_row: 0,
+            bad_frames_in_a_row: 0,
+        }
+    }
+
+    /// Skips anything that is not a valid frame, only errors on io errors
+    /// or sustained corruption.
+    ///
     /// # Cancel safety
     ///
     /// This is cancel safe
     pub async fn read(
         &mut self,
-        serial: &mut SerialStream,
+        transport: &mut (impl AsyncRead + Unpin),
+    ) -> Result<(CommandMeta, Data), Error> {
+        loop {
+            let err = match self.read_frame(transport).await {
+                Ok(frame) => {
+                    self.input.in_frame.clear();
+                    self.garbage_in_a_row = 0;
+                    self.bad_frames_in_a_row = 0;
+                    self.stats.frames.fetch_add(1, Ordering::Relaxed);
+                    return Ok(frame);
+                }
+                Err(err @ Error::Io(_)) => return Err(err),
+                Err(err) => err,
+            };
+
+            self.state = State::default();
+            if let Error::ExpectedStartOfFrame = err {
+                self.stats.dropped_bytes.fetch_add(1, Ordering::Relaxed);
+                self.garbage_in_a_row += 1;
+                if self.garbage_in_a_row > MAX_GARBAGE {
+                    return Err(err);
+                }
+            } else {
+                debug!("Skipping bad frame: {err:?}");
+                // Only the start of frame byte is known to be bogus
+                self.input.rescan_frame();
+                self.stats.bad_frames.fetch_add(1, Ordering::Relaxed);
+                self.bad_frames_in_a_row += 1;
+                if self.bad_frames_in_a_row > MAX_BAD_FRAMES {
+                    return Err(err);
+                }
+            }
+        }
+    }
+
+    async fn read_frame(
+        &mut self,
+        transport: &mut (impl AsyncRead + Unpin),
     ) -> Result<(CommandMeta, Data), Error> {
-        let data_reader = match self {
-            Self::ReadingMeta(meta_reader) => {
-                let data_reader = meta_reader.read(serial).await?;
-                *self = Self::ReadingData(data_reader);
-                let Self::ReadingData(reader) = self else {
+        let data_reader = match &mut self.state {
+            State::ReadingMeta(meta_reader) => {
+                let data_reader =
+                    meta_reader.read(&mut self.input, transport).await?;
+                self.state = State::ReadingData(data_reader);
+                let State::ReadingData(reader) = &mut self.state else {
                     unreachable!()
                 };
                 reader
             }
-            Self::ReadingData(data_reader) => data_reader,
+            State::ReadingData(data_reader) => data_reader,
         };
 
-        let (meta, data) = data_reader.read(serial).await?;
-        *self = Self::ReadingMeta(MetaReader::default());
+        let (meta, data) = data_reader.read(&mut self.input, transport).await?;
+        self.state = State::default();
         Ok((meta, data))
     }
 }
 
-impl MetaReader {
-    pub async fn read(
+impl Input {
+    /// # Cancel safety
+    ///
+    /// This is cancel safe
+    async fn read_u8(
         &mut self,
-        serial: &mut SerialStream,
-    ) -> Result<DataReader, Error> {
-        if self.n_read == 0 {
-            if serial
+        transport: &mut (impl AsyncRead + Unpin),
+        timeout: Option<Duration>,
+    ) -> Result<u8, Error> {
+        let byte = if let Some(byte) = self.replay.pop_front() {
+            byte
+        } else if let Some(timeout) = timeout {
+            transport
+                .read_u8()
+                .timeout(timeout)
+                .await
+                .map_err(|_| Error::Timeout)?
+                .map_err(Arc::new)
+                .map_err(Error::Io)?
+        } else {
+            transport
                 .read_u8()
                 .await
                 .map_err(Arc::new)
                 .map_err(Error::Io)?
-                != START_OF_FRAME
-            {
+        };
+        self.in_frame.push(byte);
+        Ok(byte)
+    }
+
+    /// Scan the bytes read since the start of the frame again
+    fn rescan_frame(&mut self) {
+        for byte in self.in_frame.drain(..).rev() {
+            self.replay.push_front(byte);
+        }
+    }
+}
+
+impl MetaReader {
+    async fn read(
+        &mut self,
+        input: &mut Input,
+        transport: &mut (impl AsyncRead + Unpin),
+    ) -> Result<DataReader, Error> {
+        if self.n_read == 0 {
+            let byte = input.read_u8(transport, None).await?;
+            input.in_frame.clear();
+            if byte != START_OF_FRAME {
                 return Err(Error::ExpectedStartOfFrame);
-            };
+            }
         }
 
         trace!("new frame started");
         for byte in self.buffer.iter_mut().skip(self.n_read) {
-            *byte = serial
-                .read_u8()
-                .timeout(Duration::from_millis(50))
-                .await
-                .map_err(|_| Error::Timeout)?
-                .map_err(Arc::new)
-                .map_err(Error::Io)?;
+            *byte = input.read_u8(transport, Some(input.byte_timeout)).await?;
             self.n_read += 1;
         }
 
@@ -124,10 +273,11 @@ impl MetaReader {
 }
 
 impl DataReader {
-    #[instrument(skip(serial))]
-    pub async fn read(
+    #[instrument(skip(input, transport))]
+    async fn read(
         &mut self,
-        serial: &mut SerialStream,
+        input: &mut Input,
+        transport: &mut (impl AsyncRead + Unpin),
     ) -> Result<(CommandMeta, Data), Error> {
         const CHECKSUM_LENGTH: usize = 1;
         trace!("reading frame data");
@@ -136,13 +286,7 @@ impl DataReader {
             self.data_length + CHECKSUM_LENGTH - self.bytes_read.len();
         for _ in 0..left_to_read {
             self.bytes_read.push(
-                serial
-                    .read_u8()
-                    .timeout(Duration::from_millis(50))
-                    .await
-                    .map_err(|_| Error::Timeout)?
-                    .map_err(Arc::new)
-                    .map_err(Error::Io)?,
+                input.read_u8(transport, Some(input.byte_timeout)).await?,
             );
         }
         let checksum_in_frame = self.bytes_read.pop().unwrap();
@@ -160,3 +304,104 @@ impl DataReader {
         Ok((self.meta.clone(), self.bytes_read.clone()))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use zstacker_znp_protocol::commands::{CommandType, SubSystem};
+
+    use super::*;
+
+    const META: CommandMeta = CommandMeta {
+        ty: CommandType::SRSP,
+        sub_system: SubSystem::Sys,
+        id: 0x01,
+    };
+
+    fn frame(data: &[u8]) -> Vec<u8> {
+        let mut frame = vec![START_OF_FRAME, data.len() as u8];
+        frame.extend(META.serialize());
+        frame.extend_from_slice(data);
+        let checksum = frame[1..].iter().fold(0, |checksum, b| checksum ^ b);
+        frame.push(checksum);
+        frame
+    }
+
+    #[tokio::test]
+    async fn skips_noise_and_corrupt_frames() {
+        let mut corrupt = frame(&[1, 2, 3]);
+        *corrupt.last_mut().unwrap() ^= 0xff;
+        let input =
+            [&[0x00, 0x13, 0x37][..], &corrupt, &frame(&[4, 5])].concat();
+
+        let stats = Arc::default();
+        let mut reader =
+            FrameReader::new(Arc::clone(&stats), SERIAL_BYTE_TIMEOUT);
+        let (meta, data) = reader.read(&mut input.as_slice()).await.unwrap();
+        assert_eq!(meta, META);
+        assert_eq!(data, [4, 5]);
+        assert_eq!(
+            stats.snapshot(),
+            ReaderStats {
+                frames: 1,
+                // The noise and, once scanned again, the corrupt frame
+                // after its start byte
+                dropped_bytes: 3 + 7,
+                bad_frames: 1,
+            }
+        );
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn resyncs_after_a_bogus_start_of_frame() {
+        let input = [&[START_OF_FRAME][..], &frame(&[4, 5])].concat();
+        // Keep the writer so the reader waits for more data instead of
+        // running into the end of the input
+        let (mut transport, mut adaptor) = tokio::io::duplex(64);
+        tokio::io::AsyncWriteExt::write_all(&mut adaptor, &input)
+            .await
+            .unwrap();
+
+        let stats = Arc::default();
+        let mut reader =
+            FrameReader::new(Arc::clone(&stats), SERIAL_BYTE_TIMEOUT);
+        let (meta, data) = reader.read(&mut transport).await.unwrap();
+        assert_eq!(meta, META);
+        assert_eq!(data, [4, 5]);
+        assert_eq!(stats.snapshot().bad_frames, 1);
+    }
+
+    #[tokio::test]
+    async fn sustained_corruption_is_fatal() {
+        let mut corrupt = frame(&[1]);
+        *corrupt.last_mut().unwrap() ^= 0xff;
+        let input = corrupt.repeat(MAX_BAD_FRAMES as usize + 1);
+
+        let mut reader = FrameReader::new(Arc::default(), SERIAL_BYTE_TIMEOUT);
+        let res = reader.read(&mut input.as_slice()).await;
+        assert!(matches!(res, Err(Error::CheckSumMismatch)));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn waits_byte_timeout_for_the_rest_of_a_frame() {
+        let frame = frame(&[4, 5]);
+        let (head, tail) = frame.split_at(3);
+        let (head, tail) = (head.to_vec(), tail.to_vec());
+        let (mut transport, mut adaptor) = tokio::io::duplex(64);
+        tokio::spawn(async move {
+            use tokio::io::AsyncWriteExt;
+            adaptor.write_all(&head).await.unwrap();
+            tokio::time::sleep(Duration::from_millis(500)).await;
+            adaptor.write_all(&tail).await.unwrap();
+            // Keep the writer so the reader does not run into the end
+            std::future::pending::<()>().await;
+        });
+
+        let stats = Arc::default();
+        let mut reader =
+            FrameReader::new(Arc::clone(&stats), NETWORK_BYTE_TIMEOUT);
+        let (meta, data) = reader.read(&mut transport).await.unwrap();
+        assert_eq!(meta, META);
+        assert_eq!(data, [4, 5]);
+        assert_eq!(stats.snapshot().bad_frames, 0);
+    }
+}
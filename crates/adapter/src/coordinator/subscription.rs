@@ -0,0 +1,49 @@
+use futures::Stream;
+use tokio::sync::broadcast;
+use tokio::sync::broadcast::error::RecvError;
+use zstacker_znp_protocol::commands::{AsyncNotify, ReplyError};
+use zstacker_znp_protocol::framing::CommandMeta;
+
+/// Every AREQ the device sends is broadcast to all subscriptions.
+/// Subscriptions that fall this far behind start missing notifications.
+pub(crate) const CAPACITY: usize = 256;
+
+/// An AREQ frame as read by the io task
+#[derive(Debug, Clone)]
+pub(crate) struct Notification {
+    pub(crate) meta: CommandMeta,
+    pub(crate) data: Vec<u8>,
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum SubscriptionError {
+    #[error(
+        "Subscriber could not keep up, missed {0} notifications from the \
+        device"
+    )]
+    Lagged(u64),
+    #[error("Could not deserialize notification")]
+    Deserializing(#[source] ReplyError),
+}
+
+/// The stream ends when the adaptor is dropped
+pub(crate) fn subscribe<N: AsyncNotify + Send + 'static>(
+    rx: broadcast::Receiver<Notification>,
+) -> impl Stream<Item = Result<N, SubscriptionError>> + Send + Unpin + 'static {
+    Box::pin(futures::stream::unfold(rx, |mut rx| async move {
+        loop {
+            let item = match rx.recv().await {
+                Ok(Notification { meta, data }) if meta == N::META => {
+                    N::from_data(&data)
+                        .map_err(SubscriptionError::Deserializing)
+                }
+                Ok(_) => continue,
+                Err(RecvError::Lagged(missed)) => {
+                    Err(SubscriptionError::Lagged(missed))
+                }
+                Err(RecvError::Closed) => return None,
+            };
+            return Some((item, rx));
+        }
+    }))
+}
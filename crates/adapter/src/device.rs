@@ -0,0 +1,249 @@
+use std::time::Duration;
+
+use futures::StreamExt;
+use tracing::{debug, instrument};
+use zstacker_zcl::global::{
+    DefaultResponse, ReadAttributes, ReadAttributesResponse,
+    WriteAttributeRecord, WriteAttributes, WriteAttributesResponse,
+};
+use zstacker_zcl::{
+    Attribute, ClusterCommand, Command, DataType, Frame, ParseError, Status,
+};
+use zstacker_znp_protocol::commands::ShortAddr;
+use zstacker_znp_protocol::commands::af::ClusterId;
+
+use crate::af::{Message, SendError};
+use crate::coordinator::{Coordinator, SubscriptionError};
+
+mod clusters;
+pub use clusters::{
+    Basic, ColorControl, Groups, IasZone, LevelControl, OnOff, Ota,
+    PowerConfiguration, RelativeHumidity, TemperatureMeasurement,
+};
+
+/// Endpoint on the coordinator that sends and receives ZCL commands, this
+/// is the home automation endpoint registered at startup.
+pub(crate) const ZCL_ENDPOINT: u8 = 1;
+/// How long to wait for the device to respond to a ZCL command
+const RESPONSE_TIMEOUT: Duration = Duration::from_secs(10);
+
+#[derive(Debug, thiserror::Error)]
+pub enum ZclError {
+    #[error("Could not encode the ZCL command")]
+    Encoding(#[source] zstacker_zcl::data_format::Error),
+    #[error("Could not send the command to the device")]
+    Send(#[source] SendError),
+    #[error("Lost track of incoming messages while waiting for a response")]
+    Incoming(#[source] SubscriptionError),
+    #[error("Device did not respond within {0:?}")]
+    Timeout(Duration),
+    #[error("Could not parse the response of the device")]
+    Parse(#[source] ParseError),
+    #[error("Device rejected the command, status: {0:?}")]
+    Rejected(Status),
+    #[error("Response does not mention the requested attribute")]
+    MissingAttribute,
+    #[error("Attribute has data type {got:?} while we expected {expected:?}")]
+    UnexpectedType { expected: DataType, got: DataType },
+}
+
+/// An endpoint on another device in the network. Talks ZCL to it through
+/// the coordinator. Cheap to clone, like the [`Coordinator`] it holds.
+#[derive(Debug, Clone)]
+pub struct Device {
+    coordinator: Coordinator,
+    pub addr: ShortAddr,
+    pub endpoint: u8,
+}
+
+impl Coordinator {
+    pub fn device(&self, addr: ShortAddr, endpoint: u8) -> Device {
+        Device {
+            coordinator: self.clone(),
+            addr,
+            endpoint,
+        }
+    }
+}
+
+impl Device {
+    pub fn basic(&self) -> Basic {
+        Basic(self.clone())
+    }
+    pub fn on_off(&self) -> OnOff {
+        OnOff(self.clone())
+    }
+    pub fn level_control(&self) -> LevelControl {
+        LevelControl(self.clone())
+    }
+    pub fn color_control(&self) -> ColorControl {
+        ColorControl(self.clone())
+    }
+    pub fn temperature(&self) -> TemperatureMeasurement {
+        TemperatureMeasurement(self.clone())
+    }
+    pub fn humidity(&self) -> RelativeHumidity {
+        RelativeHumidity(self.clone())
+    }
+    pub fn power(&self) -> PowerConfiguration {
+        PowerConfiguration(self.clone())
+    }
+    pub fn ias_zone(&self) -> IasZone {
+        IasZone(self.clone())
+    }
+    pub fn ota(&self) -> Ota {
+        Ota(self.clone())
+    }
+    pub fn groups(&self) -> Groups {
+        Groups(self.clone())
+    }
+
+    #[instrument(skip(self), fields(addr = ?self.addr), err)]
+    pub async fn read<A: Attribute>(&self) -> Result<A::Value, ZclError> {
+        let response: ReadAttributesResponse = self
+            .request(
+                A::CLUSTER,
+                &ReadAttributes {
+                    attributes: vec![A::ID],
+                },
+            )
+            .await?;
+        let record = response
+            .records
+            .into_iter()
+            .find(|record| record.id == A::ID)
+            .ok_or(ZclError::MissingAttribute)?;
+        let value = record.value.map_err(ZclError::Rejected)?;
+        let got = value.data_type();
+        A::from_value(value).ok_or(ZclError::UnexpectedType {
+            expected: A::DATA_TYPE,
+            got,
+        })
+    }
+
+    #[instrument(skip(self, value), fields(addr = ?self.addr), err)]
+    pub async fn write<A: Attribute>(
+        &self,
+        value: A::Value,
+    ) -> Result<(), ZclError> {
+        let response: WriteAttributesResponse = self
+            .request(
+                A::CLUSTER,
+                &WriteAttributes {
+                    records: vec![WriteAttributeRecord {
+                        id: A::ID,
+                        value: A::to_value(value),
+                    }],
+                },
+            )
+            .await?;
+        match response.records.iter().find(|r| !r.status.is_success()) {
+            Some(failed) => Err(ZclError::Rejected(failed.status)),
+            None => Ok(()),
+        }
+    }
+
+    /// Returns once the device confirmed it executed the command
+    #[instrument(skip(self), fields(addr = ?self.addr), err)]
+    pub async fn command<C: ClusterCommand>(
+        &self,
+        command: &C,
+    ) -> Result<(), ZclError> {
+        self.request::<_, DefaultResponse>(C::CLUSTER, command)
+            .await
+            .map(|_| ())
+    }
+
+    /// Sends the command without waiting for any response, default
+    /// responses are disabled.
+    #[instrument(skip(self), fields(addr = ?self.addr), err)]
+    pub async fn notify<C: ClusterCommand>(
+        &self,
+        command: &C,
+    ) -> Result<(), ZclError> {
+        let sequence_number = self.coordinator.next_transaction_id();
+        let frame = Frame::new(sequence_number, command)
+            .map_err(ZclError::Encoding)?
+            .without_default_response();
+        self.send(C::CLUSTER, &frame).await
+    }
+
+    async fn send(
+        &self,
+        cluster: zstacker_zcl::ClusterId,
+        frame: &Frame,
+    ) -> Result<(), ZclError> {
+        self.coordinator
+            .send_af(Message {
+                dst_addr: self.addr,
+                dst_endpoint: self.endpoint,
+                src_endpoint: ZCL_ENDPOINT,
+                cluster_id: ClusterId(cluster),
+                data: frame.to_bytes(),
+            })
+            .await
+            .map_err(ZclError::Send)
+    }
+
+    /// Sends `command` and waits for a response of type `R`. A failed
+    /// default response is returned as [`ZclError::Rejected`].
+    pub async fn request<C: Command, R: Command>(
+        &self,
+        cluster: zstacker_zcl::ClusterId,
+        command: &C,
+    ) -> Result<R, ZclError> {
+        let sequence_number = self.coordinator.next_transaction_id();
+        let frame =
+            Frame::new(sequence_number, command).map_err(ZclError::Encoding)?;
+
+        // Subscribe before sending so the response can not be missed
+        let mut incoming = self.coordinator.incoming_messages();
+        self.send(cluster, &frame).await?;
+
+        let wait_for_response = async {
+            while let Some(msg) = incoming.next().await {
+                let msg = msg.map_err(ZclError::Incoming)?;
+                if msg.src_addr != self.addr
+                    || msg.src_endpoint != self.endpoint
+                    || msg.cluster_id != ClusterId(cluster)
+                {
+                    continue;
+                }
+                let Ok(response) = Frame::from_bytes(&msg.data) else {
+                    debug!("Ignoring message that is not a ZCL frame");
+                    continue;
+                };
+                // Responses travel in the opposite direction
+                if response.header.sequence_number == sequence_number
+                    && response.header.frame_control.direction
+                        != frame.header.frame_control.direction
+                {
+                    return Ok(response);
+                }
+            }
+            unreachable!(
+                "the coordinator keeps the incoming messages stream open"
+            )
+        };
+        let response =
+            tokio::time::timeout(RESPONSE_TIMEOUT, wait_for_response)
+                .await
+                .map_err(|_| ZclError::Timeout(RESPONSE_TIMEOUT))??;
+
+        if response.is::<DefaultResponse>() {
+            let default: DefaultResponse =
+                response.parse().map_err(ZclError::Parse)?;
+            if !default.status.is_success() {
+                return Err(ZclError::Rejected(default.status));
+            }
+        }
+        response.parse().map_err(ZclError::Parse)
+    }
+}
+
+/// ZCL transition times are in tenths of a second
+fn tenths_of_second(duration: Duration) -> u16 {
+    (duration.as_millis() / 100)
+        .try_into()
+        .unwrap_or(u16::MAX - 1)
+}
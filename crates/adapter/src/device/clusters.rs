@@ -0,0 +1,279 @@
+//! Handles to a single cluster on a [`Device`], obtained through for
+//! example [`Device::on_off`].
+
+use std::time::Duration;
+
+use zstacker_zcl::clusters::{
+    basic, color_control, groups, ias_zone, level_control, on_off, ota,
+    power_configuration, relative_humidity, temperature_measurement,
+};
+
+use super::{Device, ZclError, tenths_of_second};
+
+pub struct Basic(pub(super) Device);
+
+impl Basic {
+    pub async fn manufacturer_name(&self) -> Result<String, ZclError> {
+        self.0.read::<basic::ManufacturerName>().await
+    }
+
+    pub async fn model_identifier(&self) -> Result<String, ZclError> {
+        self.0.read::<basic::ModelIdentifier>().await
+    }
+
+    pub async fn sw_build_id(&self) -> Result<String, ZclError> {
+        self.0.read::<basic::SwBuildId>().await
+    }
+
+    pub async fn power_source(&self) -> Result<basic::PowerSupply, ZclError> {
+        let raw = self.0.read::<basic::PowerSource>().await?;
+        Ok(basic::PowerSupply::from_raw(raw))
+    }
+
+    /// Resets all clusters on the endpoint to their factory defaults, the
+    /// device stays in the network.
+    pub async fn reset_to_factory_defaults(&self) -> Result<(), ZclError> {
+        self.0.command(&basic::ResetToFactoryDefaults).await
+    }
+}
+
+pub struct OnOff(pub(super) Device);
+
+impl OnOff {
+    pub async fn on(&self) -> Result<(), ZclError> {
+        self.0.command(&on_off::On).await
+    }
+
+    pub async fn off(&self) -> Result<(), ZclError> {
+        self.0.command(&on_off::Off).await
+    }
+
+    pub async fn toggle(&self) -> Result<(), ZclError> {
+        self.0.command(&on_off::Toggle).await
+    }
+
+    pub async fn is_on(&self) -> Result<bool, ZclError> {
+        self.0.read::<on_off::OnOff>().await
+    }
+}
+
+pub struct LevelControl(pub(super) Device);
+
+impl LevelControl {
+    /// Switches the device on if `level` is above the minimum and off if
+    /// it is the minimum.
+    pub async fn move_to_level(
+        &self,
+        level: u8,
+        transition: Duration,
+    ) -> Result<(), ZclError> {
+        self.0
+            .command(&level_control::MoveToLevelWithOnOff {
+                level,
+                transition_time: tenths_of_second(transition),
+            })
+            .await
+    }
+
+    pub async fn stop(&self) -> Result<(), ZclError> {
+        self.0.command(&level_control::Stop).await
+    }
+
+    pub async fn current_level(&self) -> Result<u8, ZclError> {
+        self.0.read::<level_control::CurrentLevel>().await
+    }
+}
+
+pub struct ColorControl(pub(super) Device);
+
+impl ColorControl {
+    pub async fn move_to_color_temperature(
+        &self,
+        mireds: u16,
+        transition: Duration,
+    ) -> Result<(), ZclError> {
+        self.0
+            .command(&color_control::MoveToColorTemperature {
+                color_temperature_mireds: mireds,
+                transition_time: tenths_of_second(transition),
+            })
+            .await
+    }
+
+    /// `x` and `y` are CIE 1931 coordinates between 0 and 1
+    pub async fn move_to_color(
+        &self,
+        x: f32,
+        y: f32,
+        transition: Duration,
+    ) -> Result<(), ZclError> {
+        let to_zcl = |c: f32| (c.clamp(0.0, 1.0) * 65_279.0) as u16;
+        self.0
+            .command(&color_control::MoveToColor {
+                color_x: to_zcl(x),
+                color_y: to_zcl(y),
+                transition_time: tenths_of_second(transition),
+            })
+            .await
+    }
+
+    pub async fn move_to_hue_and_saturation(
+        &self,
+        hue: u8,
+        saturation: u8,
+        transition: Duration,
+    ) -> Result<(), ZclError> {
+        self.0
+            .command(&color_control::MoveToHueAndSaturation {
+                hue,
+                saturation,
+                transition_time: tenths_of_second(transition),
+            })
+            .await
+    }
+
+    pub async fn color_temperature(&self) -> Result<u16, ZclError> {
+        self.0.read::<color_control::ColorTemperatureMireds>().await
+    }
+}
+
+pub struct TemperatureMeasurement(pub(super) Device);
+
+impl TemperatureMeasurement {
+    /// Degrees Celsius, `None` if the sensor does not know
+    pub async fn celsius(&self) -> Result<Option<f32>, ZclError> {
+        let measured = self
+            .0
+            .read::<temperature_measurement::MeasuredValue>()
+            .await?;
+        Ok(temperature_measurement::to_celsius(measured))
+    }
+}
+
+pub struct RelativeHumidity(pub(super) Device);
+
+impl RelativeHumidity {
+    /// `None` if the sensor does not know
+    pub async fn percent(&self) -> Result<Option<f32>, ZclError> {
+        let measured =
+            self.0.read::<relative_humidity::MeasuredValue>().await?;
+        Ok(relative_humidity::to_percent(measured))
+    }
+}
+
+pub struct PowerConfiguration(pub(super) Device);
+
+impl PowerConfiguration {
+    /// `None` if the device does not know
+    pub async fn battery_percentage(&self) -> Result<Option<f32>, ZclError> {
+        let remaining = self
+            .0
+            .read::<power_configuration::BatteryPercentageRemaining>()
+            .await?;
+        Ok(power_configuration::to_percent(remaining))
+    }
+
+    /// Volts, `None` if the device does not know
+    pub async fn battery_voltage(&self) -> Result<Option<f32>, ZclError> {
+        let tenths =
+            self.0.read::<power_configuration::BatteryVoltage>().await?;
+        Ok((tenths != u8::MAX).then(|| f32::from(tenths) / 10.0))
+    }
+}
+
+pub struct IasZone(pub(super) Device);
+
+impl IasZone {
+    /// Makes the coordinator the receiver of the zones notifications and
+    /// enrolls the zone under `zone_id`. Some devices want the enroll
+    /// response only after they send a [`ias_zone::ZoneEnrollRequest`],
+    /// others accept it unsolicited.
+    pub async fn enroll(&self, zone_id: u8) -> Result<(), ZclError> {
+        let coordinator = self.0.coordinator.ieee_addr.0;
+        self.0.write::<ias_zone::IasCieAddress>(coordinator).await?;
+        self.0
+            .command(&ias_zone::ZoneEnrollResponse {
+                response: ias_zone::EnrollResponseCode::Success,
+                zone_id,
+            })
+            .await
+    }
+
+    /// See [`ias_zone::zone_status`] for the meaning of the bits
+    pub async fn zone_status(&self) -> Result<u16, ZclError> {
+        self.0.read::<ias_zone::ZoneStatus>().await
+    }
+}
+
+pub struct Ota(pub(super) Device);
+
+impl Ota {
+    pub async fn current_file_version(&self) -> Result<u32, ZclError> {
+        self.0.read::<ota::CurrentFileVersion>().await
+    }
+
+    /// Asks the device to query for a new image. Devices do not respond
+    /// to this.
+    pub async fn notify_image_available(&self) -> Result<(), ZclError> {
+        self.0
+            .notify(&ota::ImageNotify {
+                payload_type: 0x00,
+                query_jitter: 100,
+            })
+            .await
+    }
+}
+
+pub struct Groups(pub(super) Device);
+
+impl Groups {
+    /// Devices that do not store group names ignore `name`
+    pub async fn add(&self, group_id: u16, name: &str) -> Result<(), ZclError> {
+        let response: groups::AddGroupResponse = self
+            .0
+            .request(
+                groups::ID,
+                &groups::AddGroup {
+                    group_id,
+                    group_name: name.to_owned(),
+                },
+            )
+            .await?;
+        checked(response.status)
+    }
+
+    pub async fn remove(&self, group_id: u16) -> Result<(), ZclError> {
+        let response: groups::RemoveGroupResponse = self
+            .0
+            .request(groups::ID, &groups::RemoveGroup { group_id })
+            .await?;
+        checked(response.status)
+    }
+
+    pub async fn remove_all(&self) -> Result<(), ZclError> {
+        self.0.command(&groups::RemoveAllGroups).await
+    }
+
+    /// All groups the endpoint is a member of
+    pub async fn membership(&self) -> Result<Vec<u16>, ZclError> {
+        let response: groups::GetGroupMembershipResponse = self
+            .0
+            .request(
+                groups::ID,
+                &groups::GetGroupMembership {
+                    group_count: 0,
+                    group_list: Vec::new(),
+                },
+            )
+            .await?;
+        Ok(response.group_list)
+    }
+}
+
+fn checked(status: zstacker_zcl::Status) -> Result<(), ZclError> {
+    if status.is_success() {
+        Ok(())
+    } else {
+        Err(ZclError::Rejected(status))
+    }
+}
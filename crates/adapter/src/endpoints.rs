@@ -1,12 +1,13 @@
+use zstacker_zcl::clusters::{ias_ace, ias_wd, ias_zone, ota, time};
 use zstacker_znp_protocol::commands::af::{
     ClusterId, LatencyRequirement, Register,
 };
 
-const SS_IAS_ZONE: ClusterId = ClusterId(1280);
-const SS_IAS_ACE: ClusterId = ClusterId(1281);
-const SS_IAS_WD: ClusterId = ClusterId(1282);
-const GEN_TIME: ClusterId = ClusterId(10);
-const GEN_OTA: ClusterId = ClusterId(25);
+const SS_IAS_ZONE: ClusterId = ClusterId(ias_zone::ID);
+const SS_IAS_ACE: ClusterId = ClusterId(ias_ace::ID);
+const SS_IAS_WD: ClusterId = ClusterId(ias_wd::ID);
+const GEN_TIME: ClusterId = ClusterId(time::ID);
+const GEN_OTA: ClusterId = ClusterId(ota::ID);
 
 const fn new_register(endpoint: u8, app_prof_id: u16) -> Register {
     Register {
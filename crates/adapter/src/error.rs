@@ -1,6 +1,10 @@
+use std::time::Duration;
+
 use zstacker_znp_protocol::commands::DeviceState;
+use zstacker_znp_protocol::commands::appconfig::BdbCommissioningStatus;
 
-use crate::coordinator::QueueError;
+use crate::coordinator::{QueueError, SubscriptionError};
+use crate::nvram::{VersionError, WriteError, ZStackVersion};
 
 #[derive(Debug, thiserror::Error)]
 pub enum StartUpError {
@@ -12,14 +16,17 @@ pub enum StartUpError {
     GetVersion(#[source] QueueError),
     #[error(
         "Device is not running as coordinator. Instead its state is: {0:?}. \
-        Run zigbee2mqtt with this adaptor to let it configure it"
+        Form a network with `form_network` or restore a backup first"
     )]
     NotRunningAsCoordinator(DeviceState),
     #[error("Could not register endpoint")]
     RegisterEndpoints(#[source] RegisterEndpointsError),
     #[error("Could not request device to start in the network")]
     RequestStartup(#[source] QueueError),
-    #[error("Device did not recover existing network")]
+    #[error(
+        "Device did not recover existing network, form a network with \
+        `form_network` or restore a backup first"
+    )]
     NetworkRecoverFailed,
     #[error("Could not add device to green power group")]
     AddingToGreenPowerGroup(#[source] QueueError),
@@ -38,3 +45,47 @@ pub enum RegisterEndpointsError {
     #[error("Device returned status failed")]
     Failed,
 }
+
+#[derive(Debug, thiserror::Error)]
+pub enum FormError {
+    #[error(
+        "Channel mask {0:#010x} must select at least one of the channels 11 \
+        to 26 and no others"
+    )]
+    InvalidChannelMask(u32),
+    #[error("Could not get adaptor info")]
+    GetDeviceInfo(#[source] QueueError),
+    #[error("Could not determine the Z-Stack version")]
+    Version(#[source] VersionError),
+    #[error(
+        "Forming a network needs BDB commissioning, Z-Stack {0:?} does not \
+        support it"
+    )]
+    UnsupportedVersion(ZStackVersion),
+    #[error("Could not ask the adaptor to clear its network state")]
+    ClearingState(#[source] WriteError),
+    #[error("Could not reset the adaptor")]
+    Reset(#[source] StartUpError),
+    #[error("Could not write the {item} to nvram")]
+    WritingConfig {
+        item: &'static str,
+        #[source]
+        source: WriteError,
+    },
+    #[error("Could not set the channels to form the network on")]
+    SetChannel(#[source] QueueError),
+    #[error("Device returned an error when setting the channels")]
+    SetChannelFailed,
+    #[error("Could not start commissioning")]
+    StartCommissioning(#[source] QueueError),
+    #[error("Device returned an error when starting commissioning")]
+    StartCommissioningFailed,
+    #[error("Lost track of the commissioning notifications")]
+    Notifications(#[source] SubscriptionError),
+    #[error("Forming the network failed with status: {0:?}")]
+    Commissioning(BdbCommissioningStatus),
+    #[error("Network was not formed within {0:?}")]
+    Timeout(Duration),
+    #[error("Could not start the newly formed network")]
+    Starting(#[source] StartUpError),
+}
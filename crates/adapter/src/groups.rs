@@ -0,0 +1,171 @@
+//! Groups: a message send to a group id reaches every endpoint that is a
+//! member.
+//!
+//! The coordinator manages the membership of its own endpoints here, which
+//! it needs to receive group messages. Remote devices manage theirs through
+//! the genGroups cluster, see [`Device::groups`].
+//!
+//! [`Device::groups`]: crate::device::Device::groups
+
+use tracing::instrument;
+use zstacker_zcl::{ClusterCommand, Frame};
+use zstacker_znp_protocol::commands::LongData;
+use zstacker_znp_protocol::commands::af::{
+    Address, ClusterId, DataRequestExt, TxOptions,
+};
+use zstacker_znp_protocol::commands::zdo::{
+    ExtAddGroup, ExtCountAllGroups, ExtFindAllGroupsEndpoint,
+    ExtRemoveAllGroup, ExtRemoveGroup,
+};
+
+use crate::af::{DEFAULT_RADIUS, SendError};
+use crate::coordinator::{Coordinator, QueueError};
+use crate::device::{ZCL_ENDPOINT, ZclError};
+
+/// Longest group name the adaptor stores
+pub const MAX_NAME_LEN: usize = 16;
+/// Delivers to every endpoint of the members
+const ALL_ENDPOINTS: u8 = 0xff;
+
+#[derive(Debug, thiserror::Error)]
+pub enum GroupError {
+    #[error("Could not send group request or receive its reply")]
+    Queue(#[source] QueueError),
+    #[error(
+        "Group name is {0} bytes long, the adaptor stores at most \
+        {MAX_NAME_LEN}"
+    )]
+    NameTooLong(usize),
+    #[error(
+        "Adaptor refused, the endpoint may already be a member, not be a \
+        member or the group table may be full"
+    )]
+    Failed,
+}
+
+/// Data to send to every member of a group
+#[derive(Debug, Clone)]
+pub struct GroupMessage {
+    pub group_id: u16,
+    /// One of the endpoints registered on the coordinator
+    pub src_endpoint: u8,
+    pub cluster_id: ClusterId,
+    pub data: Vec<u8>,
+}
+
+impl Coordinator {
+    /// Makes `endpoint` on the coordinator a member of the group
+    #[instrument(skip(self), err)]
+    pub async fn add_group(
+        &self,
+        endpoint: u8,
+        group_id: u16,
+        name: &str,
+    ) -> Result<(), GroupError> {
+        if name.len() > MAX_NAME_LEN {
+            return Err(GroupError::NameTooLong(name.len()));
+        }
+        self.queue_sync(ExtAddGroup {
+            endpoint,
+            group_id,
+            group_name: name.as_bytes().to_vec(),
+        })
+        .await
+        .map_err(GroupError::Queue)?
+        .map_err(GroupError::Failed)
+    }
+
+    #[instrument(skip(self), err)]
+    pub async fn remove_group(
+        &self,
+        endpoint: u8,
+        group_id: u16,
+    ) -> Result<(), GroupError> {
+        self.queue_sync(ExtRemoveGroup { endpoint, group_id })
+            .await
+            .map_err(GroupError::Queue)?
+            .map_err(GroupError::Failed)
+    }
+
+    #[instrument(skip(self), err)]
+    pub async fn remove_all_groups(
+        &self,
+        endpoint: u8,
+    ) -> Result<(), GroupError> {
+        self.queue_sync(ExtRemoveAllGroup { endpoint })
+            .await
+            .map_err(GroupError::Queue)?
+            .map_err(GroupError::Failed)
+    }
+
+    /// The groups `endpoint` on the coordinator is a member of
+    #[instrument(skip(self), err)]
+    pub async fn groups(&self, endpoint: u8) -> Result<Vec<u16>, GroupError> {
+        self.queue_sync(ExtFindAllGroupsEndpoint { endpoint })
+            .await
+            .map(|reply| reply.group_list)
+            .map_err(GroupError::Queue)
+    }
+
+    /// Memberships of all endpoints on the coordinator together
+    #[instrument(skip(self), err)]
+    pub async fn group_count(&self) -> Result<u8, GroupError> {
+        self.queue_sync(ExtCountAllGroups)
+            .await
+            .map(|reply| reply.count)
+            .map_err(GroupError::Queue)
+    }
+
+    /// Returns once the adaptor has send the message. Members do not
+    /// acknowledge group messages so delivery is not confirmed.
+    #[instrument(skip(self), err)]
+    pub async fn send_to_group(
+        &self,
+        message: GroupMessage,
+    ) -> Result<(), SendError> {
+        let trans_id = self.next_transaction_id();
+        let confirm = self
+            .queue_async(DataRequestExt {
+                dst: Address::Group(message.group_id),
+                dst_endpoint: ALL_ENDPOINTS,
+                dst_pan_id: 0,
+                src_endpoint: message.src_endpoint,
+                cluster_id: message.cluster_id,
+                trans_id,
+                options: TxOptions::NONE,
+                radius: DEFAULT_RADIUS,
+                data: LongData(message.data),
+            })
+            .await
+            .map_err(SendError::Queue)?;
+
+        if confirm.status.is_success() {
+            Ok(())
+        } else {
+            Err(SendError::NotDelivered(confirm.status))
+        }
+    }
+
+    /// Sends a ZCL command to every member of the group, for example to
+    /// switch a room of lights at once. Default responses are disabled as
+    /// there would be one from every member.
+    #[instrument(skip(self), err)]
+    pub async fn group_command<C: ClusterCommand>(
+        &self,
+        group_id: u16,
+        command: &C,
+    ) -> Result<(), ZclError> {
+        let sequence_number = self.next_transaction_id();
+        let frame = Frame::new(sequence_number, command)
+            .map_err(ZclError::Encoding)?
+            .without_default_response();
+        self.send_to_group(GroupMessage {
+            group_id,
+            src_endpoint: ZCL_ENDPOINT,
+            cluster_id: ClusterId(C::CLUSTER),
+            data: frame.to_bytes(),
+        })
+        .await
+        .map_err(ZclError::Send)
+    }
+}
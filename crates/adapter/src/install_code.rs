@@ -0,0 +1,230 @@
+//! Install codes let a Zigbee 3.0 device join with a link key only it and
+//! the coordinator know, instead of the well known default key.
+
+use std::str::FromStr;
+
+use aes::Aes128;
+use aes::cipher::{BlockEncrypt, KeyInit};
+use tracing::instrument;
+use zstacker_znp_protocol::commands::appconfig::{
+    AddInstallCode, InstallCodeFormat,
+};
+use zstacker_znp_protocol::commands::{IeeeAddr, Key};
+
+use crate::coordinator::{Coordinator, QueueError};
+
+/// Length of the code without its CRC, the Zigbee specification allows
+/// 6, 8, 12 and 16 byte codes.
+const CODE_LENGTHS: [usize; 4] = [6, 8, 12, 16];
+const CRC_LEN: usize = 2;
+
+#[derive(Debug, thiserror::Error, PartialEq, Eq)]
+pub enum InstallCodeError {
+    #[error("Install code should only contain hex digits, found: {0:?}")]
+    NotHex(char),
+    #[error("Install code should have an even number of hex digits")]
+    OddLength,
+    #[error(
+        "Install code with CRC should be 8, 10, 14 or 18 bytes long, got: {0}"
+    )]
+    Length(usize),
+    #[error(
+        "CRC of install code does not match, expected: {expected:04x}, \
+        found: {found:04x}. Check for typos."
+    )]
+    Crc { expected: u16, found: u16 },
+    #[error("QR code should contain `Z:<ieee address>$I:<install code>`")]
+    QrCode,
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum AddInstallCodeError {
+    #[error("Could not send install code to adaptor")]
+    Queue(#[source] QueueError),
+    #[error("Adaptor rejected the install code")]
+    Rejected,
+}
+
+/// An install code with a valid CRC
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InstallCode {
+    /// Including the CRC
+    bytes: Vec<u8>,
+}
+
+impl InstallCode {
+    /// Checks the length and the CRC that follows the code
+    pub fn from_bytes(bytes: Vec<u8>) -> Result<Self, InstallCodeError> {
+        let code_len = bytes.len().saturating_sub(CRC_LEN);
+        if !CODE_LENGTHS.contains(&code_len) {
+            return Err(InstallCodeError::Length(bytes.len()));
+        }
+        let (code, crc) = bytes.split_at(code_len);
+        let expected = crc16(code);
+        let found = u16::from_le_bytes([crc[0], crc[1]]);
+        if expected != found {
+            return Err(InstallCodeError::Crc { expected, found });
+        }
+        Ok(Self { bytes })
+    }
+
+    /// Parses the contents of the QR code on Zigbee 3.0 devices, which
+    /// holds the IEEE address of the device and its install code.
+    pub fn from_qr_code(
+        qr_code: &str,
+    ) -> Result<(IeeeAddr, Self), InstallCodeError> {
+        let field = |name: &str| {
+            qr_code
+                .split(['$', '%'])
+                .find_map(|field| field.strip_prefix(name))
+                .ok_or(InstallCodeError::QrCode)
+        };
+        let ieee = parse_hex(field("Z:")?)?;
+        let ieee: [u8; 8] =
+            ieee.try_into().map_err(|_| InstallCodeError::QrCode)?;
+        let code = Self::from_bytes(parse_hex(field("I:")?)?)?;
+        Ok((IeeeAddr(u64::from_be_bytes(ieee)), code))
+    }
+
+    /// The code followed by its CRC
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.bytes
+    }
+
+    /// The link key the device will join with, the Matyas-Meyer-Oseas hash
+    /// of the code and its CRC.
+    pub fn link_key(&self) -> Key {
+        Key(mmo_hash(&self.bytes))
+    }
+}
+
+impl FromStr for InstallCode {
+    type Err = InstallCodeError;
+
+    /// Accepts the code as printed on the device label, hex digits
+    /// optionally separated by spaces, dashes or colons.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::from_bytes(parse_hex(s)?)
+    }
+}
+
+impl Coordinator {
+    /// Allow the device with `ieee_addr` to join using the link key derived
+    /// from `code`. Joining still has to be permitted.
+    #[instrument(skip(self), err)]
+    pub async fn add_install_code(
+        &self,
+        ieee_addr: IeeeAddr,
+        code: &InstallCode,
+    ) -> Result<(), AddInstallCodeError> {
+        let reply = self
+            .queue_sync(AddInstallCode {
+                format: InstallCodeFormat::KeyDerivedFromInstallCode,
+                ieee_addr,
+                code: code.link_key().0.to_vec(),
+            })
+            .await
+            .map_err(AddInstallCodeError::Queue)?;
+
+        if reply.is_ok() {
+            Ok(())
+        } else {
+            Err(AddInstallCodeError::Rejected)
+        }
+    }
+}
+
+fn parse_hex(s: &str) -> Result<Vec<u8>, InstallCodeError> {
+    let digits = s
+        .chars()
+        .filter(|c| !matches!(c, ' ' | '-' | ':'))
+        .map(|c| {
+            c.to_digit(16)
+                .map(|d| d as u8)
+                .ok_or(InstallCodeError::NotHex(c))
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    if digits.len() % 2 != 0 {
+        return Err(InstallCodeError::OddLength);
+    }
+    Ok(digits
+        .chunks(2)
+        .map(|pair| pair[0] << 4 | pair[1])
+        .collect())
+}
+
+/// CRC-16/X-25 as used for install codes
+fn crc16(data: &[u8]) -> u16 {
+    let mut crc = 0xFFFFu16;
+    for byte in data {
+        crc ^= u16::from(*byte);
+        for _ in 0..8 {
+            crc = if crc & 1 == 1 {
+                (crc >> 1) ^ 0x8408
+            } else {
+                crc >> 1
+            };
+        }
+    }
+    !crc
+}
+
+/// Hash from the Zigbee specification (B.6), built from AES-128. Only
+/// supports messages shorter then 8 kB which is plenty for install codes.
+fn mmo_hash(message: &[u8]) -> [u8; 16] {
+    let bit_len = u16::try_from(message.len() * 8)
+        .expect("install codes are at most 18 bytes");
+    let mut padded = message.to_vec();
+    padded.push(0x80);
+    while padded.len() % 16 != 14 {
+        padded.push(0);
+    }
+    padded.extend(bit_len.to_be_bytes());
+
+    let mut hash = [0u8; 16];
+    for block in padded.chunks(16) {
+        let cipher = Aes128::new(&hash.into());
+        let mut encrypted = *aes::Block::from_slice(block);
+        cipher.encrypt_block(&mut encrypted);
+        for (h, (e, m)) in hash.iter_mut().zip(encrypted.iter().zip(block)) {
+            *h = e ^ m;
+        }
+    }
+    hash
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Example from the Zigbee Base Device Behavior specification
+    const CODE: &str = "83FE D340 7A93 9723 A5C6 39B2 6916 D505 C3B5";
+    const KEY: [u8; 16] = [
+        0x66, 0xB6, 0x90, 0x09, 0x81, 0xE1, 0xEE, 0x3C, 0xA4, 0x20, 0x6B, 0x6B,
+        0x86, 0x1C, 0x02, 0xBB,
+    ];
+
+    #[test]
+    fn derives_link_key() {
+        let code: InstallCode = CODE.parse().unwrap();
+        assert_eq!(code.link_key(), Key(KEY));
+    }
+
+    #[test]
+    fn rejects_typo() {
+        let typo = CODE.replace("D340", "D341");
+        assert!(matches!(
+            typo.parse::<InstallCode>(),
+            Err(InstallCodeError::Crc { .. })
+        ));
+    }
+
+    #[test]
+    fn parses_qr_code() {
+        let qr = "Z:00124B0001020304$I:83FED3407A939723A5C639B26916D505C3B5\
+            %G$M:ABC";
+        let (ieee, code) = InstallCode::from_qr_code(qr).unwrap();
+        assert_eq!(ieee, IeeeAddr(0x00124B0001020304));
+        assert_eq!(code, CODE.parse().unwrap());
+    }
+}
@@ -0,0 +1,179 @@
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+use tracing::{debug, instrument};
+use zstacker_znp_protocol::commands::zdo::{
+    ActiveEpReq, NodeDescReq, PowerDescReq, SimpleDescReq, SimpleDescriptor,
+};
+use zstacker_znp_protocol::commands::{AsyncRequest, ShortAddr, Status};
+
+use crate::coordinator::{Coordinator, QueueError};
+use crate::nvram::items::LogicalDeviceType;
+
+/// Sleepy end devices only receive when they poll their parent, which
+/// they may not do before a request times out.
+const ATTEMPTS: usize = 3;
+const RETRY_DELAY: Duration = Duration::from_secs(1);
+
+#[derive(Debug, thiserror::Error)]
+pub enum InterviewError {
+    #[error("Could not request the {descriptor} descriptor")]
+    Request {
+        descriptor: &'static str,
+        #[source]
+        source: QueueError,
+    },
+    #[error("Device could not provide its {descriptor} descriptor: {status:?}")]
+    Failed {
+        descriptor: &'static str,
+        status: Status,
+    },
+    #[error("Device reported an unknown logical type: {0}")]
+    UnknownLogicalType(u8),
+}
+
+/// What the device is powered by at the moment
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum PowerSource {
+    Mains,
+    RechargeableBattery,
+    DisposableBattery,
+    Unknown,
+}
+
+impl PowerSource {
+    fn from_current_source(current_source_level: u8) -> Self {
+        match current_source_level & 0b1111 {
+            0b0001 => Self::Mains,
+            0b0010 => Self::RechargeableBattery,
+            0b0100 => Self::DisposableBattery,
+            _ => Self::Unknown,
+        }
+    }
+}
+
+/// Everything the ZDO descriptors tell about a device
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DeviceDescription {
+    pub addr: ShortAddr,
+    pub manufacturer_code: u16,
+    pub logical_type: LogicalDeviceType,
+    /// Mac capabilities bitfield, from the node descriptor. `None` for
+    /// devices imported from a source that does not record it.
+    pub mac_capabilities: Option<u8>,
+    pub power_source: PowerSource,
+    pub endpoints: Vec<SimpleDescriptor>,
+}
+
+impl Coordinator {
+    /// Requests the node, power, active endpoint and simple descriptors of
+    /// the device. Requests that time out are retried, sleepy end devices
+    /// often miss the first.
+    #[instrument(skip(self), err)]
+    pub async fn interview(
+        &self,
+        addr: ShortAddr,
+    ) -> Result<DeviceDescription, InterviewError> {
+        let node = self
+            .queue_with_retries(
+                "node",
+                NodeDescReq {
+                    dst_addr: addr,
+                    nwk_addr_of_interest: addr,
+                },
+            )
+            .await?;
+        check_status("node", node.status)?;
+        let logical_type = match node.logical_type_flags & 0b111 {
+            0 => LogicalDeviceType::Coordinator,
+            1 => LogicalDeviceType::Router,
+            2 => LogicalDeviceType::EndDevice,
+            other => return Err(InterviewError::UnknownLogicalType(other)),
+        };
+
+        let power = self
+            .queue_with_retries(
+                "power",
+                PowerDescReq {
+                    dst_addr: addr,
+                    nwk_addr_of_interest: addr,
+                },
+            )
+            .await?;
+        check_status("power", power.status)?;
+
+        let active_endpoints = self
+            .queue_with_retries(
+                "active endpoint",
+                ActiveEpReq {
+                    dst_addr: addr,
+                    nwk_addr_of_interest: addr,
+                },
+            )
+            .await?;
+        check_status("active endpoint", active_endpoints.status)?;
+
+        let mut endpoints = Vec::new();
+        for endpoint in active_endpoints.active_endpoints {
+            let simple = self
+                .queue_with_retries(
+                    "simple",
+                    SimpleDescReq {
+                        dst_addr: addr,
+                        nwk_addr_of_interest: addr,
+                        endpoint,
+                    },
+                )
+                .await?;
+            check_status("simple", simple.status)?;
+            endpoints.extend(simple.descriptor);
+        }
+
+        Ok(DeviceDescription {
+            addr,
+            manufacturer_code: node.manufacturer_code,
+            logical_type,
+            mac_capabilities: Some(node.mac_capabilities),
+            power_source: PowerSource::from_current_source(
+                power.current_source_level,
+            ),
+            endpoints,
+        })
+    }
+
+    async fn queue_with_retries<R: AsyncRequest + Clone>(
+        &self,
+        descriptor: &'static str,
+        req: R,
+    ) -> Result<R::Reply, InterviewError> {
+        let mut attempt = 1;
+        loop {
+            match self.queue_async(req.clone()).await {
+                Err(QueueError::TimedOut { .. }) if attempt < ATTEMPTS => {
+                    debug!(
+                        "{descriptor} descriptor request timed out, retrying"
+                    );
+                    attempt += 1;
+                    tokio::time::sleep(RETRY_DELAY).await;
+                }
+                other => {
+                    return other.map_err(|source| InterviewError::Request {
+                        descriptor,
+                        source,
+                    });
+                }
+            }
+        }
+    }
+}
+
+fn check_status(
+    descriptor: &'static str,
+    status: Status,
+) -> Result<(), InterviewError> {
+    if status.is_success() {
+        Ok(())
+    } else {
+        Err(InterviewError::Failed { descriptor, status })
+    }
+}
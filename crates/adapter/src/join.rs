@@ -0,0 +1,165 @@
+use std::collections::HashMap;
+use std::time::Duration;
+
+use futures::{Stream, StreamExt};
+use tokio::time::Instant;
+use tracing::instrument;
+use zstacker_znp_protocol::commands::zdo::{
+    EndDeviceAnnceInd, MgmtPermitJoinReq, PermitJoinAddrMode, TcDeviceInd,
+};
+use zstacker_znp_protocol::commands::{IeeeAddr, ShortAddr, Status};
+
+use crate::coordinator::{Coordinator, QueueError, SubscriptionError};
+
+/// Broadcast address of every router, including the coordinator
+const ALL_ROUTERS: ShortAddr = ShortAddr(0xFFFC);
+/// Longest window Z-Stack supports, 255 keeps joining open indefinitely
+const MAX_PERMIT_DURATION: u8 = 254;
+/// A device announces itself right after the trust center lets it in. If it
+/// does not within this time it failed to join.
+const ANNOUNCE_TIMEOUT: Duration = Duration::from_secs(60);
+
+#[derive(Debug, thiserror::Error)]
+pub enum PermitJoinError {
+    #[error("Could not send permit join request or receive its response")]
+    Queue(#[source] QueueError),
+    #[error("Permit join was rejected, device reported: {0:?}")]
+    Rejected(Status),
+}
+
+/// A device that joined or rejoined the network
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DeviceJoined {
+    pub ieee_addr: IeeeAddr,
+    pub short_addr: ShortAddr,
+    /// The router the device joined through. Unknown for devices that
+    /// rejoined without involving the trust center.
+    pub parent: Option<ShortAddr>,
+    /// Mac capabilities bitfield of the device
+    pub capabilities: u8,
+}
+
+enum JoinEvent {
+    TrustCenter(TcDeviceInd),
+    Announce(EndDeviceAnnceInd),
+}
+
+/// Parents of devices the trust center let in that have not announced
+/// themselves yet
+#[derive(Debug, Default)]
+struct Parents(HashMap<IeeeAddr, (ShortAddr, Instant)>);
+
+impl Parents {
+    /// Also forgets devices that did not announce themselves in time
+    fn insert(&mut self, device: IeeeAddr, parent: ShortAddr, now: Instant) {
+        self.0
+            .retain(|_, (_, since)| now - *since < ANNOUNCE_TIMEOUT);
+        self.0.insert(device, (parent, now));
+    }
+
+    fn take(&mut self, device: &IeeeAddr, now: Instant) -> Option<ShortAddr> {
+        self.0
+            .remove(device)
+            .filter(|(_, since)| now - *since < ANNOUNCE_TIMEOUT)
+            .map(|(parent, _)| parent)
+    }
+}
+
+impl Coordinator {
+    /// Allow devices to join for `duration`, which is rounded down to whole
+    /// seconds and capped at 254 seconds. A zero duration closes the
+    /// network again. Without a `target` joining is allowed through every
+    /// router, otherwise only through the router at `target`.
+    #[instrument(skip(self), err)]
+    pub async fn permit_join(
+        &self,
+        duration: Duration,
+        target: Option<ShortAddr>,
+    ) -> Result<(), PermitJoinError> {
+        let (addr_mode, dst_addr) = match target {
+            Some(addr) => (PermitJoinAddrMode::Addr16Bit, addr),
+            None => (PermitJoinAddrMode::Broadcast, ALL_ROUTERS),
+        };
+        let duration = duration
+            .as_secs()
+            .min(MAX_PERMIT_DURATION.into())
+            .try_into()
+            .expect("capped to fit in an u8");
+
+        let rsp = self
+            .queue_async(MgmtPermitJoinReq {
+                addr_mode,
+                dst_addr,
+                duration,
+                tc_significance: true,
+            })
+            .await
+            .map_err(PermitJoinError::Queue)?;
+
+        if rsp.status.is_success() {
+            Ok(())
+        } else {
+            Err(PermitJoinError::Rejected(rsp.status))
+        }
+    }
+
+    /// Every device that joins or rejoins the network from now on. A device
+    /// is reported once it announces itself, its parent is taken from the
+    /// trust center indication that precedes the announcement.
+    pub fn joined_devices(
+        &self,
+    ) -> impl Stream<Item = Result<DeviceJoined, SubscriptionError>>
+    + Send
+    + Unpin
+    + 'static {
+        let trust_center = self
+            .subscribe::<TcDeviceInd>()
+            .map(|ind| ind.map(JoinEvent::TrustCenter));
+        let announce = self
+            .subscribe::<EndDeviceAnnceInd>()
+            .map(|ind| ind.map(JoinEvent::Announce));
+
+        futures::stream::select(trust_center, announce)
+            .scan(Parents::default(), |parents, event| {
+                let joined = match event {
+                    Err(e) => Some(Err(e)),
+                    Ok(JoinEvent::TrustCenter(ind)) => {
+                        parents.insert(
+                            ind.ext_addr,
+                            ind.parent_addr,
+                            Instant::now(),
+                        );
+                        None
+                    }
+                    Ok(JoinEvent::Announce(ind)) => Some(Ok(DeviceJoined {
+                        ieee_addr: ind.ieee_addr,
+                        short_addr: ind.nwk_addr,
+                        parent: parents.take(&ind.ieee_addr, Instant::now()),
+                        capabilities: ind.capabilities,
+                    })),
+                };
+                futures::future::ready(Some(joined))
+            })
+            .filter_map(futures::future::ready)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn forgets_devices_that_never_announce() {
+        let start = Instant::now();
+        let mut parents = Parents::default();
+        parents.insert(IeeeAddr(1), ShortAddr(0x0e0f), start);
+        parents.insert(IeeeAddr(2), ShortAddr(0x0e0f), start);
+        assert_eq!(parents.take(&IeeeAddr(1), start), Some(ShortAddr(0x0e0f)));
+
+        let later = start + ANNOUNCE_TIMEOUT;
+        parents.insert(IeeeAddr(3), ShortAddr(0x1234), later);
+        assert_eq!(parents.0.len(), 1);
+        assert_eq!(parents.take(&IeeeAddr(2), later), None);
+        assert_eq!(parents.take(&IeeeAddr(3), later + ANNOUNCE_TIMEOUT), None);
+    }
+}
@@ -0,0 +1,118 @@
+//! Removing devices from the network, for example decommissioned hardware
+//! that should no longer show up in the topology.
+
+use tracing::{debug, instrument};
+use zstacker_znp_protocol::commands::zdo::{
+    MgmtLeaveReq, SecDeviceRemove, leave_options,
+};
+use zstacker_znp_protocol::commands::{IeeeAddr, ShortAddr, Status};
+
+use crate::coordinator::{Coordinator, QueueError};
+use crate::resolve::ResolveError;
+
+#[derive(Debug, thiserror::Error)]
+pub enum RemoveDeviceError {
+    #[error("Could not look up the short address of the device")]
+    Resolve(#[source] ResolveError),
+    #[error("Could not send leave request or receive its response")]
+    Queue(#[source] QueueError),
+    #[error("Device refused to leave: {0:?}")]
+    Rejected(Status),
+    #[error(
+        "Device could not be reached, it can only be removed without \
+        rejoining"
+    )]
+    Unreachable,
+    #[error("Adaptor could not remove the device from the trust center")]
+    ForceFailed,
+}
+
+/// How a device was removed
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Removal {
+    /// The device confirmed it is leaving
+    Left,
+    /// The device could not be reached. It was removed from the trust
+    /// center, which forgot its link key and address.
+    Forced,
+}
+
+impl Coordinator {
+    /// Asks the device with `ieee_addr` to leave the network, and to rejoin
+    /// afterwards if `rejoin` is set. A router also asks its children to
+    /// leave if `remove_children` is set.
+    ///
+    /// A device that does not answer is presumed dead and is removed from
+    /// the trust center instead, unless it should rejoin.
+    #[instrument(skip(self), err)]
+    pub async fn remove_device(
+        &self,
+        ieee_addr: IeeeAddr,
+        rejoin: bool,
+        remove_children: bool,
+    ) -> Result<Removal, RemoveDeviceError> {
+        let mut options = 0;
+        if rejoin {
+            options |= leave_options::REJOIN;
+        }
+        if remove_children {
+            options |= leave_options::REMOVE_CHILDREN;
+        }
+
+        let left = match self.resolve_short(ieee_addr).await {
+            Ok(short_addr) => {
+                self.request_leave(short_addr, ieee_addr, options).await?
+            }
+            Err(
+                ResolveError::Failed(_)
+                | ResolveError::Queue(QueueError::TimedOut { .. }),
+            ) => false,
+            Err(e) => return Err(RemoveDeviceError::Resolve(e)),
+        };
+
+        let removal = if left {
+            Removal::Left
+        } else if rejoin {
+            return Err(RemoveDeviceError::Unreachable);
+        } else {
+            debug!("Device did not answer, removing it from trust center");
+            self.queue_sync(SecDeviceRemove { ieee_addr })
+                .await
+                .map_err(RemoveDeviceError::Queue)?
+                .map_err(RemoveDeviceError::ForceFailed)?;
+            Removal::Forced
+        };
+
+        if !rejoin {
+            self.addresses().forget(ieee_addr);
+        }
+        Ok(removal)
+    }
+
+    /// Whether the device answered the request
+    async fn request_leave(
+        &self,
+        short_addr: ShortAddr,
+        ieee_addr: IeeeAddr,
+        options: u8,
+    ) -> Result<bool, RemoveDeviceError> {
+        let rsp = match self
+            .queue_async(MgmtLeaveReq {
+                dst_addr: short_addr,
+                device_address: ieee_addr,
+                options,
+            })
+            .await
+        {
+            Ok(rsp) => rsp,
+            Err(QueueError::TimedOut { .. }) => return Ok(false),
+            Err(e) => return Err(RemoveDeviceError::Queue(e)),
+        };
+
+        if rsp.status.is_success() {
+            Ok(true)
+        } else {
+            Err(RemoveDeviceError::Rejected(rsp.status))
+        }
+    }
+}
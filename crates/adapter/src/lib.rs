@@ -1,8 +1,26 @@
-pub mod error;
-pub mod startup;
+pub mod af;
+pub mod backup;
+pub mod bind;
+pub mod coordinator;
+pub mod device;
 pub mod endpoints;
-pub mod nvram;
+pub mod error;
+pub mod groups;
+pub mod install_code;
+pub mod interview;
+pub mod join;
+pub mod leave;
 pub mod list;
-pub mod coordinator;
+pub mod nvram;
+pub mod resolve;
+pub mod startup;
+pub mod store;
+pub mod supervisor;
+pub mod topology;
+pub mod transport;
+#[cfg(feature = "z2m")]
+pub mod z2m;
 
-pub use startup::{check_connection_to_adapter, start_coordinator};
+pub use startup::{
+    NetworkConfig, check_connection_to_adapter, form_network, start_coordinator,
+};
@@ -1,7 +1,10 @@
 use std::collections::HashMap;
 use std::collections::HashSet;
+use std::collections::VecDeque;
 
-use tracing::debug;
+use futures::StreamExt;
+use futures::stream::FuturesUnordered;
+use tracing::{debug, instrument};
 use zstacker_znp_protocol::commands::zdo::{NeighborLqi, RoutingEntry};
 use zstacker_znp_protocol::commands::{self, PartialList, ShortAddr};
 
@@ -27,9 +30,25 @@ pub enum LqiTableError {
     },
 }
 
+/// A device that did not answer during a scan
+#[derive(Debug, thiserror::Error)]
+#[error("Device {device} did not answer")]
+pub struct Unreachable {
+    pub device: ShortAddr,
+    #[source]
+    pub cause: QueueError,
+}
+
+/// What a scan found before and after devices failed to answer
+#[derive(Debug)]
+pub struct Scan<T> {
+    pub found: T,
+    pub unreachable: Vec<Unreachable>,
+}
+
 impl Coordinator {
-    async fn routing_table_for(
-        &mut self,
+    pub(crate) async fn routing_table_for(
+        &self,
         addr: ShortAddr,
     ) -> Result<Vec<RoutingEntry>, QueueError> {
         get_entire_list(Box::new(async |start_index| {
@@ -43,8 +62,8 @@ impl Coordinator {
         .await
     }
 
-    async fn lqi_table_for(
-        &mut self,
+    pub(crate) async fn lqi_table_for(
+        &self,
         addr: ShortAddr,
     ) -> Result<Vec<NeighborLqi>, QueueError> {
         get_entire_list(Box::new(async |start_index| {
@@ -59,7 +78,7 @@ impl Coordinator {
     }
 
     pub async fn list_addresses_on_network(
-        &mut self,
+        &self,
     ) -> Result<HashSet<ShortAddr>, ListAddressesError> {
         let coordinator_table = self
             .routing_table_for(self.short_addr)
@@ -81,7 +100,7 @@ impl Coordinator {
             let table =
                 self.routing_table_for(addr).await.map_err(|cause| {
                     ListAddressesError {
-                        device: self.short_addr,
+                        device: addr,
                         cause,
                     }
                 })?;
@@ -92,9 +111,12 @@ impl Coordinator {
         Ok(to_ask)
     }
 
+    /// The neighbor table of every device found by
+    /// [`Self::list_addresses_on_network`], see [`Self::topology`] for a
+    /// map that also holds the routes.
     pub async fn lqi_table(
-        &mut self,
-    ) -> Result<HashMap<ShortAddr, Vec<u8>>, LqiTableError> {
+        &self,
+    ) -> Result<HashMap<ShortAddr, Vec<NeighborLqi>>, LqiTableError> {
         let to_ask = self
             .list_addresses_on_network()
             .await
@@ -109,17 +131,115 @@ impl Coordinator {
                     cause,
                 }
             })?;
-            res.insert(
-                addr,
-                table.into_iter().map(|entry| entry.lqi).collect(),
-            );
+            res.insert(addr, table);
         }
 
         Ok(res)
     }
+
+    /// Like [`Self::list_addresses_on_network`] but keeps going when a
+    /// device does not answer, asking up to `max_concurrent` devices at a
+    /// time. Unreachable devices are found, as some routing table lists
+    /// them, but their routes are missing.
+    #[instrument(skip(self))]
+    pub async fn scan_addresses_on_network(
+        &self,
+        max_concurrent: usize,
+    ) -> Scan<HashSet<ShortAddr>> {
+        let (tables, unreachable) =
+            crawl([self.short_addr], max_concurrent, async |addr| {
+                let destinations: Vec<_> = self
+                    .routing_table_for(addr)
+                    .await?
+                    .into_iter()
+                    .map(|entry| entry.destination_address)
+                    .collect();
+                Ok((destinations.clone(), destinations))
+            })
+            .await;
+
+        Scan {
+            found: tables.into_values().flatten().collect(),
+            unreachable,
+        }
+    }
+
+    /// Like [`Self::lqi_table`] but keeps going when a device does not
+    /// answer, asking up to `max_concurrent` devices at a time.
+    #[instrument(skip(self))]
+    pub async fn scan_lqi_table(
+        &self,
+        max_concurrent: usize,
+    ) -> Scan<HashMap<ShortAddr, Vec<NeighborLqi>>> {
+        let Scan {
+            found: addresses,
+            mut unreachable,
+        } = self.scan_addresses_on_network(max_concurrent).await;
+
+        // Do not wait for devices that already failed to answer again
+        let to_ask = addresses.into_iter().filter(|addr| {
+            !unreachable.iter().any(|failed| failed.device == *addr)
+        });
+        let (tables, also_unreachable) =
+            crawl(to_ask, max_concurrent, async |addr| {
+                Ok((self.lqi_table_for(addr).await?, Vec::new()))
+            })
+            .await;
+        unreachable.extend(also_unreachable);
+
+        Scan {
+            found: tables,
+            unreachable,
+        }
+    }
+}
+
+/// Visits `start` and every address the visits return, each once and at
+/// most `max_concurrent` at a time. A failed visit is recorded and does
+/// not stop the crawl.
+pub(crate) async fn crawl<T>(
+    start: impl IntoIterator<Item = ShortAddr>,
+    max_concurrent: usize,
+    visit: impl AsyncFn(ShortAddr) -> Result<(T, Vec<ShortAddr>), QueueError>,
+) -> (HashMap<ShortAddr, T>, Vec<Unreachable>) {
+    let mut to_visit: VecDeque<_> = start.into_iter().collect();
+    let mut seen: HashSet<_> = to_visit.iter().copied().collect();
+    let mut in_flight = FuturesUnordered::new();
+    let mut visited = HashMap::new();
+    let mut unreachable = Vec::new();
+
+    loop {
+        while in_flight.len() < max_concurrent.max(1)
+            && let Some(addr) = to_visit.pop_front()
+        {
+            let visit = &visit;
+            in_flight.push(async move { (addr, visit(addr).await) });
+        }
+        let Some((addr, result)) = in_flight.next().await else {
+            break;
+        };
+        match result {
+            Ok((value, discovered)) => {
+                for next in discovered {
+                    if seen.insert(next) {
+                        to_visit.push_back(next);
+                    }
+                }
+                visited.insert(addr, value);
+            }
+            Err(cause) => {
+                debug!("{addr:?} did not answer, continuing without it");
+                unreachable.push(Unreachable {
+                    device: addr,
+                    cause,
+                });
+            }
+        }
+    }
+    (visited, unreachable)
 }
 
-async fn get_entire_list<T, E>(
+pub(crate) async fn get_entire_list<T, E>(
     mut partial_list_getter: impl AsyncFnMut(u8) -> Result<PartialList<T>, E>,
 ) -> Result<Vec<T>, E> {
     let mut next_start = 0;
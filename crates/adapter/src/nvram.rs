@@ -1,42 +1,140 @@
 use zstacker_znp_protocol::commands;
-use zstacker_znp_protocol::commands::sys::{NvId, OsalNvLengthReply};
+use zstacker_znp_protocol::commands::LongData;
+use zstacker_znp_protocol::commands::sys::{
+    ExNvId, NvId, NvStatus, OsalNvLengthReply,
+};
+use zstacker_znp_protocol::data_format;
 
 use crate::coordinator::{Coordinator, QueueError};
+use types::{Nib, NibError};
 
 pub mod ids;
+pub mod items;
 pub mod types;
 
+/// Bytes per read or write request, keeps frames well below the 250 byte
+/// limit.
+const CHUNK_SIZE: usize = 240;
+
+/// An nvram item along with the rust type of its value, see [`items`] for
+/// the ones we know about
+pub trait NvItem {
+    const ID: NvId;
+    type Value;
+
+    fn decode(bytes: &[u8]) -> Result<Self::Value, data_format::Error>;
+    fn encode(value: &Self::Value) -> Vec<u8>;
+}
+
+/// The layout of nvram differs between Z-Stack releases
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ZStackVersion {
+    /// Z-Stack Home 1.2, found on the CC2530 and CC2531
+    V1_2,
+    /// Z-Stack 3.0.x
+    V3_0,
+    /// Z-Stack 3.x.0, stores tables in the extended nvram
+    V3x0,
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum VersionError {
+    #[error("Could not ask the coordinator for its version")]
+    Querying(#[source] QueueError),
+    #[error("Unknown Z-Stack product id: {0}")]
+    UnknownProduct(u8),
+}
+
 #[derive(Debug, thiserror::Error)]
 pub enum ReadError {
     #[error("Could not ask the coordinator for the length")]
     QueryingLength(#[source] QueueError),
     #[error("There is no item with this ID in nvram")]
     DoesNotExist(NvId),
+    #[error("There is no item with this ID in the extended nvram")]
+    ExDoesNotExist(ExNvId),
     #[error("Could not read bytes from nvram")]
     ReadingItem(#[source] QueueError),
     #[error("Coordinator reported status Failure")]
     ReadFailed,
+    #[error("Could not decode the item")]
+    Decoding(#[source] data_format::Error),
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum WriteError {
+    #[error("Could not ask the coordinator for the length")]
+    QueryingLength(#[source] QueueError),
+    #[error("Item can be at most {max} bytes, got: {got}")]
+    TooLong { max: usize, got: usize },
+    #[error("Could not remove the item before recreating it")]
+    Deleting(#[source] DeleteError),
+    #[error("Could not create the item")]
+    Creating(#[source] QueueError),
+    #[error("Could not write bytes to nvram")]
+    WritingItem(#[source] QueueError),
+    #[error("Coordinator reported status: {0:?}")]
+    WriteFailed(NvStatus),
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum DeleteError {
+    #[error("Could not ask the coordinator for the length")]
+    QueryingLength(#[source] QueueError),
+    #[error("Could not delete the item")]
+    Deleting(#[source] QueueError),
+    #[error("Coordinator reported status: {0:?}")]
+    DeleteFailed(NvStatus),
 }
 
 impl Coordinator {
+    pub async fn zstack_version(&self) -> Result<ZStackVersion, VersionError> {
+        let reply = self
+            .queue_sync(commands::sys::Version)
+            .await
+            .map_err(VersionError::Querying)?;
+        match reply.product {
+            0 => Ok(ZStackVersion::V1_2),
+            1 => Ok(ZStackVersion::V3_0),
+            2 => Ok(ZStackVersion::V3x0),
+            other => Err(VersionError::UnknownProduct(other)),
+        }
+    }
+
+    /// Handles the NIB layout of every supported chip and Z-Stack release,
+    /// see [`types::NibLayout`].
+    pub async fn read_nib(&self) -> Result<Nib, NibError> {
+        let bytes = self
+            .read_nvram_item(ids::NIB)
+            .await
+            .map_err(NibError::Reading)?;
+        Nib::decode(&bytes).map(|(nib, _)| nib)
+    }
+
+    pub async fn read<I: NvItem>(&self) -> Result<I::Value, ReadError> {
+        let bytes = self.read_nvram_item(I::ID).await?;
+        I::decode(&bytes).map_err(ReadError::Decoding)
+    }
+
+    pub async fn write<I: NvItem>(
+        &self,
+        value: &I::Value,
+    ) -> Result<(), WriteError> {
+        self.write_nvram_item(I::ID, &I::encode(value)).await
+    }
+
     pub async fn read_nvram_item(
-        &mut self,
+        &self,
         item_id: NvId,
     ) -> Result<Vec<u8>, ReadError> {
-        let reply = self
-            .queue_sync(commands::sys::OsalNvLength { item_id })
+        let length = self
+            .nvram_item_len(item_id)
             .await
-            .map_err(ReadError::QueryingLength)?;
-
-        let length = match reply {
-            OsalNvLengthReply::ItemExists { length } => length,
-            OsalNvLengthReply::ItemDoesNotExist => {
-                return Err(ReadError::DoesNotExist(item_id));
-            }
-        };
+            .map_err(ReadError::QueryingLength)?
+            .ok_or(ReadError::DoesNotExist(item_id))?;
 
         let mut res = Vec::new();
-        while res.len() < length.get().into() {
+        while res.len() < length.into() {
             let reply = self
                 .queue_sync(commands::sys::OsalNvReadExt {
                     id: item_id,
@@ -53,4 +151,194 @@ impl Coordinator {
 
         Ok(res)
     }
+
+    /// Creates the item if needed, an existing item with a different length
+    /// is recreated. Large items are written in chunks.
+    pub async fn write_nvram_item(
+        &self,
+        item_id: NvId,
+        value: &[u8],
+    ) -> Result<(), WriteError> {
+        let len =
+            u16::try_from(value.len()).map_err(|_| WriteError::TooLong {
+                max: u16::MAX.into(),
+                got: value.len(),
+            })?;
+        let existing = self
+            .nvram_item_len(item_id)
+            .await
+            .map_err(WriteError::QueryingLength)?;
+        if existing.is_some_and(|existing| existing != len) {
+            self.delete_nvram_item(item_id)
+                .await
+                .map_err(WriteError::Deleting)?;
+        }
+        if existing != Some(len) {
+            let reply = self
+                .queue_sync(commands::sys::OsalNvItemInit {
+                    id: item_id,
+                    len,
+                    init_value: Vec::new(),
+                })
+                .await
+                .map_err(WriteError::Creating)?;
+            // Success means the item already existed
+            match reply.status {
+                NvStatus::ItemUninit | NvStatus::Success => (),
+                other => return Err(WriteError::WriteFailed(other)),
+            }
+        }
+
+        for (i, chunk) in value.chunks(CHUNK_SIZE).enumerate() {
+            let reply = self
+                .queue_sync(commands::sys::OsalNvWriteExt {
+                    id: item_id,
+                    offset: (i * CHUNK_SIZE) as u16,
+                    value: LongData(chunk.to_vec()),
+                })
+                .await
+                .map_err(WriteError::WritingItem)?;
+            if !reply.status.is_success() {
+                return Err(WriteError::WriteFailed(reply.status));
+            }
+        }
+        Ok(())
+    }
+
+    /// Does nothing if the item does not exist
+    pub async fn delete_nvram_item(
+        &self,
+        item_id: NvId,
+    ) -> Result<(), DeleteError> {
+        let Some(len) = self
+            .nvram_item_len(item_id)
+            .await
+            .map_err(DeleteError::QueryingLength)?
+        else {
+            return Ok(());
+        };
+        let reply = self
+            .queue_sync(commands::sys::OsalNvDelete { id: item_id, len })
+            .await
+            .map_err(DeleteError::Deleting)?;
+        if reply.status.is_success() {
+            Ok(())
+        } else {
+            Err(DeleteError::DeleteFailed(reply.status))
+        }
+    }
+
+    /// Reads an item from the extended nvram that Z-Stack 3.x.0 uses for
+    /// tables, for example one entry of the address manager table.
+    pub async fn read_ex_nvram_item(
+        &self,
+        id: ExNvId,
+    ) -> Result<Vec<u8>, ReadError> {
+        let length = self
+            .ex_nvram_item_len(id)
+            .await
+            .map_err(ReadError::QueryingLength)?
+            .ok_or(ReadError::ExDoesNotExist(id))?;
+
+        let mut res = Vec::new();
+        while res.len() < length as usize {
+            let left = length as usize - res.len();
+            let reply = self
+                .queue_sync(commands::sys::NvRead {
+                    id,
+                    offset: res.len() as u16,
+                    len: left.min(CHUNK_SIZE) as u8,
+                })
+                .await
+                .map_err(ReadError::ReadingItem)?;
+            if !reply.status.is_success() || reply.value.is_empty() {
+                return Err(ReadError::ReadFailed);
+            }
+            res.extend(reply.value);
+        }
+        Ok(res)
+    }
+
+    /// See [`Coordinator::write_nvram_item`]
+    pub async fn write_ex_nvram_item(
+        &self,
+        id: ExNvId,
+        value: &[u8],
+    ) -> Result<(), WriteError> {
+        let len =
+            u16::try_from(value.len()).map_err(|_| WriteError::TooLong {
+                max: u16::MAX.into(),
+                got: value.len(),
+            })?;
+        let len = u32::from(len);
+        let existing = self
+            .ex_nvram_item_len(id)
+            .await
+            .map_err(WriteError::QueryingLength)?;
+        if existing.is_some_and(|existing| existing != len) {
+            self.delete_ex_nvram_item(id)
+                .await
+                .map_err(WriteError::Deleting)?;
+        }
+        if existing != Some(len) {
+            let reply = self
+                .queue_sync(commands::sys::NvCreate { id, len })
+                .await
+                .map_err(WriteError::Creating)?;
+            if !reply.status.is_success() {
+                return Err(WriteError::WriteFailed(reply.status));
+            }
+        }
+
+        for (i, chunk) in value.chunks(CHUNK_SIZE).enumerate() {
+            let reply = self
+                .queue_sync(commands::sys::NvWrite {
+                    id,
+                    offset: (i * CHUNK_SIZE) as u16,
+                    value: chunk.to_vec(),
+                })
+                .await
+                .map_err(WriteError::WritingItem)?;
+            if !reply.status.is_success() {
+                return Err(WriteError::WriteFailed(reply.status));
+            }
+        }
+        Ok(())
+    }
+
+    /// Does nothing if the item does not exist
+    pub async fn delete_ex_nvram_item(
+        &self,
+        id: ExNvId,
+    ) -> Result<(), DeleteError> {
+        let reply = self
+            .queue_sync(commands::sys::NvDelete { id })
+            .await
+            .map_err(DeleteError::Deleting)?;
+        match reply.status {
+            NvStatus::Success | NvStatus::ItemUninit => Ok(()),
+            other => Err(DeleteError::DeleteFailed(other)),
+        }
+    }
+
+    async fn nvram_item_len(
+        &self,
+        item_id: NvId,
+    ) -> Result<Option<u16>, QueueError> {
+        let reply = self
+            .queue_sync(commands::sys::OsalNvLength { item_id })
+            .await?;
+        Ok(match reply {
+            OsalNvLengthReply::ItemExists { length } => Some(length.get()),
+            OsalNvLengthReply::ItemDoesNotExist => None,
+        })
+    }
+
+    async fn ex_nvram_item_len(
+        &self,
+        id: ExNvId,
+    ) -> Result<Option<u32>, QueueError> {
+        let reply = self.queue_sync(commands::sys::NvLength { id }).await?;
+        Ok((reply.len != 0).then_some(reply.len))
+    }
 }
@@ -1,3 +1,141 @@
+//! Ids of the Z-Stack nvram items, named as in `ZComDef.h` without the
+//! `ZCD_NV_` prefix. Items that have a known layout are also available as
+//! typed [`items`](super::items).
+
 use zstacker_znp_protocol::commands::sys::NvId;
 
+/// IEEE address of the coordinator
+pub const EXTADDR: NvId = NvId(0x0001);
+pub const BOOTCOUNTER: NvId = NvId(0x0002);
+/// See [`startup_option`](super::items::startup_option)
+pub const STARTUP_OPTION: NvId = NvId(0x0003);
+pub const START_DELAY: NvId = NvId(0x0004);
+
+/// Network Information Base, see [`Nib`](super::types::Nib)
 pub const NIB: NvId = NvId(0x0021);
+pub const DEVICE_LIST: NvId = NvId(0x0022);
+/// Address manager table, a single item before Z-Stack 3.x.0
+pub const ADDRMGR: NvId = NvId(0x0023);
+pub const POLL_RATE_OLD16: NvId = NvId(0x0024);
+pub const QUEUED_POLL_RATE: NvId = NvId(0x0025);
+pub const RESPONSE_POLL_RATE: NvId = NvId(0x0026);
+pub const REJOIN_POLL_RATE: NvId = NvId(0x0027);
+pub const DATA_RETRIES: NvId = NvId(0x0028);
+pub const POLL_FAILURE_RETRIES: NvId = NvId(0x0029);
+pub const STACK_PROFILE: NvId = NvId(0x002A);
+pub const INDIRECT_MSG_TIMEOUT: NvId = NvId(0x002B);
+pub const ROUTE_EXPIRY_TIME: NvId = NvId(0x002C);
+pub const EXTENDED_PAN_ID: NvId = NvId(0x002D);
+pub const BCAST_RETRIES: NvId = NvId(0x002E);
+pub const PASSIVE_ACK_TIMEOUT: NvId = NvId(0x002F);
+pub const BCAST_DELIVERY_TIME: NvId = NvId(0x0030);
+pub const NWK_MODE: NvId = NvId(0x0031);
+pub const CONCENTRATOR_ENABLE: NvId = NvId(0x0032);
+pub const CONCENTRATOR_DISCOVERY: NvId = NvId(0x0033);
+pub const CONCENTRATOR_RADIUS: NvId = NvId(0x0034);
+pub const POLL_RATE: NvId = NvId(0x0035);
+pub const CONCENTRATOR_RC: NvId = NvId(0x0036);
+pub const NWK_MGR_MODE: NvId = NvId(0x0037);
+pub const SRC_RTG_EXPIRY_TIME: NvId = NvId(0x0038);
+pub const ROUTE_DISCOVERY_TIME: NvId = NvId(0x0039);
+/// The network key in use
+pub const NWK_ACTIVE_KEY_INFO: NvId = NvId(0x003A);
+/// The network key to switch to
+pub const NWK_ALTERN_KEY_INFO: NvId = NvId(0x003B);
+pub const ROUTER_OFF_ASSOC_CLEANUP: NvId = NvId(0x003C);
+pub const NWK_LEAVE_REQ_ALLOWED: NvId = NvId(0x003D);
+pub const NWK_CHILD_AGE_ENABLE: NvId = NvId(0x003E);
+pub const DEVICE_LIST_KA_TIMEOUT: NvId = NvId(0x003F);
+
+pub const BINDING_TABLE: NvId = NvId(0x0041);
+pub const GROUP_TABLE: NvId = NvId(0x0042);
+pub const APS_FRAME_RETRIES: NvId = NvId(0x0043);
+pub const APS_ACK_WAIT_DURATION: NvId = NvId(0x0044);
+pub const APS_ACK_WAIT_MULTIPLIER: NvId = NvId(0x0045);
+pub const BINDING_TIME: NvId = NvId(0x0046);
+pub const APS_USE_EXT_PANID: NvId = NvId(0x0047);
+pub const APS_USE_INSECURE_JOIN: NvId = NvId(0x0048);
+pub const COMMISSIONED_NWK_ADDR: NvId = NvId(0x0049);
+pub const APS_NONMEMBER_RADIUS: NvId = NvId(0x004B);
+/// Link keys of devices on Z-Stack 1.2
+pub const APS_LINK_KEY_TABLE: NvId = NvId(0x004C);
+pub const APS_DUPREJ_TIMEOUT_INC: NvId = NvId(0x004D);
+pub const APS_DUPREJ_TIMEOUT_COUNT: NvId = NvId(0x004E);
+pub const APS_DUPREJ_TABLE_SIZE: NvId = NvId(0x004F);
+pub const DIAGNOSTIC_STATS: NvId = NvId(0x0050);
+pub const NWK_PARENT_INFO: NvId = NvId(0x0051);
+pub const NWK_ENDDEV_TIMEOUT_DEF: NvId = NvId(0x0052);
+pub const END_DEV_TIMEOUT_VALUE: NvId = NvId(0x0053);
+pub const END_DEV_CONFIGURATION: NvId = NvId(0x0054);
+pub const BDBNODEISONANETWORK: NvId = NvId(0x0055);
+pub const BDBREPORTINGCONFIG: NvId = NvId(0x0056);
+
+/// Set to [`ZNP_HAS_CONFIGURED_VALUE`] once the network is configured.
+/// Without it Z-Stack 3 clears the network state on startup.
+pub const ZNP_HAS_CONFIGURED_ZSTACK3: NvId = NvId(0x0060);
+pub const SECURITY_LEVEL: NvId = NvId(0x0061);
+/// Network key used when forming a network
+pub const PRECFGKEY: NvId = NvId(0x0062);
+/// Whether the coordinator distributes the preconfigured key to joining
+/// devices
+pub const PRECFGKEYS_ENABLE: NvId = NvId(0x0063);
+pub const SECURITY_MODE: NvId = NvId(0x0064);
+pub const SECURE_PERMIT_JOIN: NvId = NvId(0x0065);
+pub const APS_LINK_KEY_TYPE: NvId = NvId(0x0066);
+pub const APS_ALLOW_R19_SECURITY: NvId = NvId(0x0067);
+pub const DISTRIBUTED_KEY: NvId = NvId(0x0068);
+pub const USE_DEFAULT_TCLK: NvId = NvId(0x006D);
+pub const RNG_COUNTER: NvId = NvId(0x006F);
+pub const RANDOM_SEED: NvId = NvId(0x0070);
+pub const TRUSTCENTER_ADDR: NvId = NvId(0x0071);
+
+/// First of the network frame counter items on Z-Stack 3.0.x, one item
+/// per [`NwkSecMaterialDesc`](super::types::NwkSecMaterialDesc)
+pub const LEGACY_NWK_SEC_MATERIAL_TABLE_START: NvId = NvId(0x0075);
+pub const LEGACY_NWK_SEC_MATERIAL_TABLE_END: NvId = NvId(0x0080);
+
+pub const USERDESC: NvId = NvId(0x0081);
+/// Network key and frame counter on Z-Stack 1.2
+pub const NWKKEY: NvId = NvId(0x0082);
+pub const PANID: NvId = NvId(0x0083);
+/// Bitmask of the channels to form a network on
+pub const CHANLIST: NvId = NvId(0x0084);
+pub const LEAVE_CTRL: NvId = NvId(0x0085);
+pub const SCAN_DURATION: NvId = NvId(0x0086);
+/// See [`LogicalType`](super::items::LogicalType)
+pub const LOGICAL_TYPE: NvId = NvId(0x0087);
+pub const NWKMGR_MIN_TX: NvId = NvId(0x0088);
+pub const NWKMGR_ADDR: NvId = NvId(0x0089);
+pub const ZDO_DIRECT_CB: NvId = NvId(0x008F);
+
+/// Link keys of devices are derived from this seed
+pub const TCLK_SEED: NvId = NvId(0x0101);
+pub const TCLK_JOIN_DEV: NvId = NvId(0x0102);
+pub const TCLK_DEFAULT: NvId = NvId(0x0103);
+/// First of the install code items on Z-Stack 3.0.x
+pub const LEGACY_TCLK_IC_TABLE_START: NvId = NvId(0x0104);
+pub const LEGACY_TCLK_IC_TABLE_END: NvId = NvId(0x0110);
+/// First of the trust center link key items on Z-Stack 3.0.x, one item per
+/// [`TclkDevEntry`](super::types::TclkDevEntry)
+pub const LEGACY_TCLK_TABLE_START: NvId = NvId(0x0111);
+pub const LEGACY_TCLK_TABLE_END: NvId = NvId(0x01FF);
+/// First of the APS link key items on Z-Stack 3.0.x
+pub const LEGACY_APS_LINK_KEY_DATA_START: NvId = NvId(0x0201);
+pub const LEGACY_APS_LINK_KEY_DATA_END: NvId = NvId(0x02FF);
+
+/// See [`ZNP_HAS_CONFIGURED_ZSTACK3`], used by Z-Stack 1.2
+pub const ZNP_HAS_CONFIGURED_ZSTACK1: NvId = NvId(0x0F00);
+pub const ZNP_HAS_CONFIGURED_VALUE: u8 = 0x55;
+
+/// Item ids in the extended nvram of Z-Stack 3.x.0, each table entry is a
+/// sub item.
+pub mod ex {
+    pub const LEGACY: u16 = 0x0000;
+    pub const ADDRMGR: u16 = 0x0001;
+    pub const BINDING_TABLE: u16 = 0x0002;
+    pub const DEVICE_LIST: u16 = 0x0003;
+    pub const TCLK_TABLE: u16 = 0x0004;
+    pub const TCLK_IC_TABLE: u16 = 0x0005;
+    pub const APS_KEY_DATA_TABLE: u16 = 0x0006;
+    pub const NWK_SEC_MATERIAL_TABLE: u16 = 0x0007;
+}
@@ -0,0 +1,118 @@
+//! Typed nvram items, read them with [`Coordinator::read`] and write them
+//! with [`Coordinator::write`].
+//!
+//! [`Coordinator::read`]: crate::coordinator::Coordinator::read
+//! [`Coordinator::write`]: crate::coordinator::Coordinator::write
+
+use std::io::Cursor;
+
+use serde_repr::{Deserialize_repr, Serialize_repr};
+use zstacker_znp_protocol::commands::{IeeeAddr, Key};
+use zstacker_znp_protocol::data_format;
+
+use super::NvItem;
+use super::ids;
+use super::types::{AddrMgrEntry, NwkActiveKeyItems, NwkKeyDesc};
+
+/// Defines a type implementing [`NvItem`] for a value that is stored as
+/// its `data_format` encoding.
+macro_rules! nv_item {
+    ($(#[$doc:meta])* $name:ident = $id:path => $value:ty) => {
+        $(#[$doc])*
+        #[derive(Debug, Clone, Copy)]
+        pub struct $name;
+
+        impl NvItem for $name {
+            const ID: zstacker_znp_protocol::commands::sys::NvId = $id;
+            type Value = $value;
+
+            fn decode(bytes: &[u8]) -> Result<$value, data_format::Error> {
+                data_format::from_reader(&mut Cursor::new(bytes))
+            }
+
+            fn encode(value: &$value) -> Vec<u8> {
+                data_format::to_vec(value)
+                    .expect("nvram items only contain integers and keys")
+            }
+        }
+    };
+}
+
+/// Bitflags of [`StartupOption`], applied on the next reset
+pub mod startup_option {
+    pub const CLEAR_CONFIG: u8 = 0x01;
+    pub const CLEAR_STATE: u8 = 0x02;
+}
+
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, Serialize_repr, Deserialize_repr,
+)]
+#[repr(u8)]
+pub enum LogicalDeviceType {
+    Coordinator = 0,
+    Router = 1,
+    EndDevice = 2,
+}
+
+nv_item! {
+    /// IEEE address of the coordinator, not every adaptor has this item
+    ExtAddr = ids::EXTADDR => IeeeAddr
+}
+nv_item! {
+    /// Bitflags from [`startup_option`]
+    StartupOption = ids::STARTUP_OPTION => u8
+}
+nv_item! { PanId = ids::PANID => u16 }
+nv_item! { ExtendedPanId = ids::EXTENDED_PAN_ID => u64 }
+nv_item! {
+    /// Bitmask of the channels to form a network on, bit 11 to 26
+    ChanList = ids::CHANLIST => u32
+}
+nv_item! { SecurityLevel = ids::SECURITY_LEVEL => u8 }
+nv_item! { PreCfgKey = ids::PRECFGKEY => Key }
+nv_item! { PreCfgKeysEnable = ids::PRECFGKEYS_ENABLE => bool }
+nv_item! { LogicalType = ids::LOGICAL_TYPE => LogicalDeviceType }
+nv_item! {
+    /// Network key and frame counter, only on Z-Stack 1.2
+    NwkKey = ids::NWKKEY => NwkActiveKeyItems
+}
+nv_item! { NwkActiveKeyInfo = ids::NWK_ACTIVE_KEY_INFO => NwkKeyDesc }
+nv_item! { NwkAlternKeyInfo = ids::NWK_ALTERN_KEY_INFO => NwkKeyDesc }
+nv_item! {
+    /// Not on Z-Stack 1.2
+    TclkSeed = ids::TCLK_SEED => Key
+}
+nv_item! {
+    /// Set to [`ids::ZNP_HAS_CONFIGURED_VALUE`] on Z-Stack 1.2
+    ZnpHasConfiguredZStack1 = ids::ZNP_HAS_CONFIGURED_ZSTACK1 => u8
+}
+nv_item! {
+    /// Set to [`ids::ZNP_HAS_CONFIGURED_VALUE`] on Z-Stack 3
+    ZnpHasConfiguredZStack3 = ids::ZNP_HAS_CONFIGURED_ZSTACK3 => u8
+}
+
+/// The address manager table before Z-Stack 3.x.0, which stores each entry
+/// in the extended nvram.
+#[derive(Debug, Clone, Copy)]
+pub struct AddrMgr;
+
+impl NvItem for AddrMgr {
+    const ID: zstacker_znp_protocol::commands::sys::NvId = ids::ADDRMGR;
+    type Value = Vec<AddrMgrEntry>;
+
+    fn decode(bytes: &[u8]) -> Result<Self::Value, data_format::Error> {
+        bytes
+            .chunks(AddrMgrEntry::SIZE)
+            .map(|entry| data_format::from_reader(&mut Cursor::new(entry)))
+            .collect()
+    }
+
+    fn encode(value: &Self::Value) -> Vec<u8> {
+        value
+            .iter()
+            .flat_map(|entry| {
+                data_format::to_vec(entry).expect("entry only has integers")
+            })
+            .collect()
+    }
+}
@@ -1,77 +1,251 @@
+use std::io::Cursor;
+
 use serde::{Deserialize, Serialize};
-use zstacker_znp_protocol::commands::IeeeAddr;
+use zstacker_znp_protocol::commands::{IeeeAddr, Key, ShortAddr};
+use zstacker_znp_protocol::data_format;
+
+use super::ReadError;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NwkKeyDesc {
+    pub key_seq_num: u8,
+    pub key: Key,
+}
+
+/// Content of [`ids::NWKKEY`](super::ids::NWKKEY) on Z-Stack 1.2
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NwkActiveKeyItems {
+    pub active: NwkKeyDesc,
+    pub frame_counter: u32,
+}
+
+/// Network frame counter on Z-Stack 3, one per network
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NwkSecMaterialDesc {
+    pub frame_counter: u32,
+    /// All ones for the entry that applies to any network
+    pub extended_panid: u64,
+}
+
+pub mod addr_mgr_user {
+    pub const DEFAULT: u8 = 0x00;
+    /// Device is a child of the coordinator
+    pub const ASSOC: u8 = 0x01;
+    /// Device has a trust center link key
+    pub const SECURITY: u8 = 0x02;
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AddrMgrEntry {
+    /// Bitflags from [`addr_mgr_user`]
+    pub user: u8,
+    pub nwk_addr: ShortAddr,
+    pub ext_addr: IeeeAddr,
+}
 
+impl AddrMgrEntry {
+    pub const SIZE: usize = 11;
+    pub const EMPTY: Self = Self {
+        user: addr_mgr_user::DEFAULT,
+        nwk_addr: ShortAddr(0xFFFF),
+        ext_addr: IeeeAddr(u64::MAX),
+    };
+
+    pub fn is_empty(&self) -> bool {
+        self.user == addr_mgr_user::DEFAULT
+            || self.ext_addr == IeeeAddr(0)
+            || self.ext_addr == IeeeAddr(u64::MAX)
+    }
+}
+
+pub mod key_attributes {
+    pub const VERIFIED_KEY: u8 = 0x02;
+}
+
+/// Trust center link key of one device. The key itself is not stored, it
+/// is the TCLK seed rotated by `seed_shift` xor-ed with the IEEE address.
 #[derive(Debug, Clone, Serialize, Deserialize)]
-struct NwkKeyDesc {
-    key_seq_num: u8,
-    key: Vec<u8>,
+pub struct TclkDevEntry {
+    pub tx_frame_counter: u32,
+    pub rx_frame_counter: u32,
+    pub ext_addr: IeeeAddr,
+    pub key_attributes: u8,
+    pub key_type: u8,
+    pub seed_shift: u8,
+}
+
+impl TclkDevEntry {
+    pub const EMPTY: Self = Self {
+        tx_frame_counter: 0,
+        rx_frame_counter: 0,
+        ext_addr: IeeeAddr(0),
+        key_attributes: 0,
+        key_type: 0,
+        seed_shift: 0,
+    };
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum NibError {
+    #[error("Could not read the NIB from nvram")]
+    Reading(#[source] ReadError),
+    #[error("A NIB is 110 or 116 bytes long, got: {0}")]
+    UnknownLength(usize),
+    #[error("Could not decode the NIB")]
+    Decoding(#[source] data_format::Error),
 }
 
+/// How the NIB is stored. This depends on the chip rather then the Z-Stack
+/// release, the CC26xx and CC13xx pad fields to their alignment while the
+/// CC2530 and CC2531 do not.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NibLayout {
+    Packed,
+    Aligned,
+}
+
+impl NibLayout {
+    const PACKED_LEN: usize = 110;
+    const ALIGNED_LEN: usize = 116;
+    /// Offsets of the padding bytes in the aligned layout
+    const PADDING: [usize; 6] = [15, 21, 25, 39, 109, 115];
+
+    pub fn from_len(len: usize) -> Result<Self, NibError> {
+        match len {
+            Self::PACKED_LEN => Ok(Self::Packed),
+            Self::ALIGNED_LEN => Ok(Self::Aligned),
+            other => Err(NibError::UnknownLength(other)),
+        }
+    }
+}
+
+/// The Network Information Base, the state of the network as the
+/// coordinator sees it. Read it with [`Coordinator::read_nib`].
+///
 /// https://github.com/zigpy/zigpy-znp/blob/dev/zigpy_znp/types/structs.py#L73
+///
+/// [`Coordinator::read_nib`]: crate::coordinator::Coordinator::read_nib
 #[derive(Debug, Clone, Serialize, Deserialize)]
-struct Nib {
-    sequence_num: u8,
-    passive_ack_timeout: u8,
-    max_broadcast_retries: u8,
-    max_children: u8,
-    max_depth: u8,
-    max_routers: u8,
-    dummy_neighbor_table: u8,
-    broadcast_delivery_time: u8,
-    report_constant_cost: u8,
-    route_disc_retries: u8,
-    dummy_routing_table: u8,
-    secure_all_frames: u8,
-    security_level: u8,
-    sym_link: u8,
-    capability_flags: u8,
-
-    transaction_persistence_time: u16,
-
-    nwk_protocol_version: u8,
-    route_discovery_time: u8,
-    route_expiry_time: u8,
-
-    nwk_dev_address: u16,
-
-    nwk_logical_channel: u8,
-
-    nwk_coord_address: u16,
-    nwk_coord_ext_address: IeeeAddr,
-    nwk_pan_id: u16,
-
-    nwk_state: u16,
-    channel_list: u32,
-
-    beacon_order: u8,
-    super_frame_order: u8,
-    scan_duration: u8,
-    batt_life_ext: u8,
-
-    allocated_router_addresses: u32,
-    allocated_end_device_addresses: u32,
-
-    node_depth: u8,
-
-    extended_panid: u64,
-
-    nwk_key_loaded: bool,
-
-    spare1: NwkKeyDesc,
-    spare2: NwkKeyDesc,
-
-    spare3: u8,
-    spare4: u8,
-
-    nwk_link_status_period: u8,
-    nwk_router_age_limit: u8,
-    nwk_use_multi_cast: bool,
-    nwk_is_concentrator: bool,
-    nwk_concentrator_discovery_time: u8,
-    nwk_concentrator_radius: u8,
-    nwk_all_fresh: u8,
-
-    nwk_manager_addr: u16,
-    nwk_total_transmissions: u16,
-    nwk_update_id: u8,
+pub struct Nib {
+    pub sequence_num: u8,
+    pub passive_ack_timeout: u8,
+    pub max_broadcast_retries: u8,
+    pub max_children: u8,
+    pub max_depth: u8,
+    pub max_routers: u8,
+    pub dummy_neighbor_table: u8,
+    pub broadcast_delivery_time: u8,
+    pub report_constant_cost: u8,
+    pub route_disc_retries: u8,
+    pub dummy_routing_table: u8,
+    pub secure_all_frames: u8,
+    pub security_level: u8,
+    pub sym_link: u8,
+    pub capability_flags: u8,
+
+    pub transaction_persistence_time: u16,
+
+    pub nwk_protocol_version: u8,
+    pub route_discovery_time: u8,
+    pub route_expiry_time: u8,
+
+    pub nwk_dev_address: u16,
+
+    /// The channel the network is on
+    pub nwk_logical_channel: u8,
+
+    pub nwk_coord_address: u16,
+    pub nwk_coord_ext_address: IeeeAddr,
+    pub nwk_pan_id: u16,
+
+    pub nwk_state: u8,
+    /// Bitmask of the channels the network may use, bit 11 to 26
+    pub channel_list: u32,
+
+    pub beacon_order: u8,
+    pub super_frame_order: u8,
+    pub scan_duration: u8,
+    pub batt_life_ext: u8,
+
+    pub allocated_router_addresses: u32,
+    pub allocated_end_device_addresses: u32,
+
+    pub node_depth: u8,
+
+    pub extended_panid: u64,
+
+    pub nwk_key_loaded: bool,
+
+    pub spare1: NwkKeyDesc,
+    pub spare2: NwkKeyDesc,
+
+    pub spare3: u8,
+    pub spare4: u8,
+
+    pub nwk_link_status_period: u8,
+    pub nwk_router_age_limit: u8,
+    pub nwk_use_multi_cast: bool,
+    pub nwk_is_concentrator: bool,
+    pub nwk_concentrator_discovery_time: u8,
+    pub nwk_concentrator_radius: u8,
+    pub nwk_all_fresh: u8,
+
+    pub nwk_manager_addr: u16,
+    pub nwk_total_transmissions: u16,
+    /// Incremented every time the network changes channel
+    pub nwk_update_id: u8,
+}
+
+impl Nib {
+    /// The layout is derived from the length of `bytes`
+    pub fn decode(bytes: &[u8]) -> Result<(Self, NibLayout), NibError> {
+        let layout = NibLayout::from_len(bytes.len())?;
+        let packed: Vec<u8> = match layout {
+            NibLayout::Packed => bytes.to_vec(),
+            NibLayout::Aligned => bytes
+                .iter()
+                .enumerate()
+                .filter(|(i, _)| !NibLayout::PADDING.contains(i))
+                .map(|(_, byte)| *byte)
+                .collect(),
+        };
+        let nib = data_format::from_reader(&mut Cursor::new(packed))
+            .map_err(NibError::Decoding)?;
+        Ok((nib, layout))
+    }
+
+    pub fn encode(&self, layout: NibLayout) -> Vec<u8> {
+        let mut bytes = data_format::to_vec(self)
+            .expect("the NIB only contains integers and keys");
+        if layout == NibLayout::Aligned {
+            for offset in NibLayout::PADDING {
+                bytes.insert(offset, 0);
+            }
+        }
+        bytes
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn aligned_layout_roundtrip() {
+        let mut bytes: Vec<u8> = (0..116).collect();
+        for offset in NibLayout::PADDING {
+            bytes[offset] = 0;
+        }
+        // bools must be 0 or 1
+        bytes[65] = 1;
+        bytes[104] = 0;
+        bytes[105] = 1;
+
+        let (nib, layout) = Nib::decode(&bytes).unwrap();
+        assert_eq!(layout, NibLayout::Aligned);
+        assert_eq!(nib.nwk_dev_address, u16::from_le_bytes([22, 23]));
+        assert_eq!(nib.nwk_pan_id, u16::from_le_bytes([36, 37]));
+        assert_eq!(nib.nwk_update_id, 114);
+        assert_eq!(nib.encode(layout), bytes);
+    }
 }
@@ -0,0 +1,171 @@
+//! Translates between the IEEE address of a device and its short address.
+//!
+//! Lookups are cached. A device gets a new short address when it rejoins,
+//! it then announces itself and the cache follows the announcement.
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+use futures::{Stream, StreamExt};
+use tokio::task;
+use tracing::{debug, instrument};
+use zstacker_znp_protocol::commands::zdo::{
+    AddrReqType, EndDeviceAnnceInd, IeeeAddrReq, NwkAddrReq,
+};
+use zstacker_znp_protocol::commands::{IeeeAddr, ShortAddr, Status};
+
+use crate::coordinator::{Coordinator, QueueError, SubscriptionError};
+
+#[derive(Debug, thiserror::Error)]
+pub enum ResolveError {
+    #[error("Could not look up the address")]
+    Queue(#[source] QueueError),
+    #[error("Device could not provide its address: {0:?}")]
+    Failed(Status),
+}
+
+type Announcement = Result<EndDeviceAnnceInd, SubscriptionError>;
+type Addresses = HashMap<IeeeAddr, ShortAddr>;
+
+/// Shared by all clones of a [`Coordinator`]
+#[derive(Debug)]
+pub(crate) struct AddressCache {
+    addresses: Arc<Mutex<Addresses>>,
+    /// Applies the announcements to the cache as they come in
+    follow_announcements: task::AbortHandle,
+}
+
+impl AddressCache {
+    pub(crate) fn new(
+        announcements: impl Stream<Item = Announcement> + Send + Unpin + 'static,
+    ) -> Self {
+        let addresses = Arc::new(Mutex::new(HashMap::new()));
+        let follow_announcements = task::spawn(follow_announcements(
+            announcements,
+            Arc::clone(&addresses),
+        ))
+        .abort_handle();
+        Self {
+            addresses,
+            follow_announcements,
+        }
+    }
+
+    fn short_addr(&self, ieee_addr: IeeeAddr) -> Option<ShortAddr> {
+        self.lock().get(&ieee_addr).copied()
+    }
+
+    fn ieee_addr(&self, short_addr: ShortAddr) -> Option<IeeeAddr> {
+        self.lock()
+            .iter()
+            .find(|(_, short)| **short == short_addr)
+            .map(|(ieee, _)| *ieee)
+    }
+
+    fn insert(&self, ieee_addr: IeeeAddr, short_addr: ShortAddr) {
+        insert(&mut self.lock(), ieee_addr, short_addr);
+    }
+
+    /// For devices that left the network
+    pub(crate) fn forget(&self, ieee_addr: IeeeAddr) {
+        self.lock().remove(&ieee_addr);
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, Addresses> {
+        self.addresses.lock().expect("address cache mutex poisoned")
+    }
+}
+
+impl Drop for AddressCache {
+    fn drop(&mut self) {
+        self.follow_announcements.abort();
+    }
+}
+
+/// Any other device that had `short_addr` has left or moved
+fn insert(
+    addresses: &mut Addresses,
+    ieee_addr: IeeeAddr,
+    short_addr: ShortAddr,
+) {
+    addresses.retain(|_, short| *short != short_addr);
+    addresses.insert(ieee_addr, short_addr);
+}
+
+async fn follow_announcements(
+    mut announcements: impl Stream<Item = Announcement> + Unpin,
+    addresses: Arc<Mutex<Addresses>>,
+) {
+    while let Some(announcement) = announcements.next().await {
+        let mut addresses =
+            addresses.lock().expect("address cache mutex poisoned");
+        match announcement {
+            Ok(ind) => insert(&mut addresses, ind.ieee_addr, ind.nwk_addr),
+            Err(e @ SubscriptionError::Lagged(_)) => {
+                debug!("Clearing address cache, it may be stale: {e}");
+                addresses.clear();
+            }
+            Err(e) => debug!("Ignoring announcement: {e}"),
+        }
+    }
+}
+
+impl Coordinator {
+    /// The short address of the device with `ieee_addr`. If it is not
+    /// cached the request is broadcast, the device itself answers.
+    #[instrument(skip(self), err)]
+    pub async fn resolve_short(
+        &self,
+        ieee_addr: IeeeAddr,
+    ) -> Result<ShortAddr, ResolveError> {
+        if ieee_addr == self.ieee_addr {
+            return Ok(self.short_addr);
+        }
+        if let Some(short_addr) = self.addresses().short_addr(ieee_addr) {
+            return Ok(short_addr);
+        }
+
+        let rsp = self
+            .queue_async(NwkAddrReq {
+                ieee_addr,
+                req_type: AddrReqType::Single,
+                start_index: 0,
+            })
+            .await
+            .map_err(ResolveError::Queue)?;
+        if !rsp.status.is_success() {
+            return Err(ResolveError::Failed(rsp.status));
+        }
+        self.addresses().insert(rsp.ieee_addr, rsp.nwk_addr);
+        Ok(rsp.nwk_addr)
+    }
+
+    /// The IEEE address of the device at `short_addr`. If it is not cached
+    /// the device is asked directly.
+    #[instrument(skip(self), err)]
+    pub async fn resolve_ieee(
+        &self,
+        short_addr: ShortAddr,
+    ) -> Result<IeeeAddr, ResolveError> {
+        if short_addr == self.short_addr {
+            return Ok(self.ieee_addr);
+        }
+        if let Some(ieee_addr) = self.addresses().ieee_addr(short_addr) {
+            return Ok(ieee_addr);
+        }
+
+        let rsp = self
+            .queue_async(IeeeAddrReq {
+                short_addr,
+                req_type: AddrReqType::Single,
+                start_index: 0,
+            })
+            .await
+            .map_err(ResolveError::Queue)?;
+        if !rsp.status.is_success() {
+            return Err(ResolveError::Failed(rsp.status));
+        }
+        self.addresses().insert(rsp.ieee_addr, rsp.nwk_addr);
+        Ok(rsp.ieee_addr)
+    }
+}
@@ -1,42 +1,193 @@
 use std::thread;
 use std::time::Duration;
 
+use futures::StreamExt;
 use tracing::{debug, info, instrument};
 
+use zstacker_znp_protocol::commands::appconfig::{
+    BdbCommissioningNotification, BdbCommissioningStatus, commissioning_mode,
+};
 use zstacker_znp_protocol::commands::sys::{ResetInd, ResetType};
 use zstacker_znp_protocol::commands::util::DeviceInfo;
-use zstacker_znp_protocol::commands::{self, DeviceState};
+use zstacker_znp_protocol::commands::{self, DeviceState, Key};
 
 use crate::coordinator::{Adaptor, Coordinator};
 use crate::endpoints::default_endpoints;
-use crate::error::{RegisterEndpointsError, StartUpError};
+use crate::error::{FormError, RegisterEndpointsError, StartUpError};
+use crate::nvram::items::{self, LogicalDeviceType, startup_option};
+use crate::nvram::{ZStackVersion, ids};
+
+/// How long the adaptor may take to form the network
+const FORMATION_TIMEOUT: Duration = Duration::from_secs(60);
+/// Channels 11 to 26, the 2.4 GHz channels Zigbee uses
+const ALL_CHANNELS: u32 = 0x07FF_F800;
 
 type Endpoint = commands::af::Register;
 
 #[instrument(skip(adaptor))]
 pub async fn start_coordinator(
-    mut adaptor: Adaptor,
+    adaptor: Adaptor,
     endpoints: Vec<Endpoint>,
     skip_reset: bool,
 ) -> Result<Coordinator, StartUpError> {
+    let device_info = run_startup_sequence(&adaptor, skip_reset).await?;
+    Ok(Coordinator::start(device_info, adaptor))
+}
+
+/// The network to form with [`form_network`]
+#[derive(Debug, Clone)]
+pub struct NetworkConfig {
+    /// Bitmask of the channels to pick from, bit 11 to 26. The adaptor
+    /// picks the quietest one.
+    pub channel_mask: u32,
+    pub pan_id: u16,
+    pub extended_pan_id: u64,
+    pub network_key: Key,
+}
+
+/// Wipes the network state of the adaptor, forms a new network and starts
+/// it as coordinator. Every device that was part of the old network has to
+/// join again. Needs Z-Stack 3, Z-Stack 1.2 can not form networks through
+/// BDB commissioning.
+#[instrument(skip(adaptor, config))]
+pub async fn form_network(
+    adaptor: Adaptor,
+    config: NetworkConfig,
+) -> Result<Coordinator, FormError> {
+    if config.channel_mask == 0 || config.channel_mask & !ALL_CHANNELS != 0 {
+        return Err(FormError::InvalidChannelMask(config.channel_mask));
+    }
+    // The nvram is only reachable through a coordinator
+    let device_info = adaptor
+        .queue_sync(commands::util::GetDeviceInfo)
+        .await
+        .map_err(FormError::GetDeviceInfo)?;
+    let coordinator = Coordinator::start(device_info, adaptor.clone());
+    let version = coordinator
+        .zstack_version()
+        .await
+        .map_err(FormError::Version)?;
+    if version == ZStackVersion::V1_2 {
+        return Err(FormError::UnsupportedVersion(version));
+    }
+
+    coordinator
+        .write::<items::StartupOption>(
+            &(startup_option::CLEAR_CONFIG | startup_option::CLEAR_STATE),
+        )
+        .await
+        .map_err(FormError::ClearingState)?;
+    reset_device(&adaptor).await.map_err(FormError::Reset)?;
+    debug!("cleared network state");
+
+    let write = |item: &'static str| {
+        move |source| FormError::WritingConfig { item, source }
+    };
+    coordinator
+        .write::<items::LogicalType>(&LogicalDeviceType::Coordinator)
+        .await
+        .map_err(write("logical type"))?;
+    coordinator
+        .write::<items::PanId>(&config.pan_id)
+        .await
+        .map_err(write("PAN id"))?;
+    coordinator
+        .write::<items::ExtendedPanId>(&config.extended_pan_id)
+        .await
+        .map_err(write("extended PAN id"))?;
+    coordinator
+        .write::<items::ChanList>(&config.channel_mask)
+        .await
+        .map_err(write("channel list"))?;
+    coordinator
+        .write::<items::PreCfgKey>(&config.network_key)
+        .await
+        .map_err(write("network key"))?;
+    coordinator
+        .write::<items::PreCfgKeysEnable>(&true)
+        .await
+        .map_err(write("network key enable"))?;
+    // Send ZDO callbacks to us rather then handling them on the adaptor
+    coordinator
+        .write_nvram_item(ids::ZDO_DIRECT_CB, &[1])
+        .await
+        .map_err(write("ZDO direct callback"))?;
+
+    for (is_primary, channel) in [(true, config.channel_mask), (false, 0)] {
+        adaptor
+            .queue_sync(commands::appconfig::BdbSetChannel {
+                is_primary,
+                channel,
+            })
+            .await
+            .map_err(FormError::SetChannel)?
+            .map_err(FormError::SetChannelFailed)?;
+    }
+    commission_network(&adaptor).await?;
+    debug!("network formed");
+
+    coordinator
+        .write::<items::ZnpHasConfiguredZStack3>(&ids::ZNP_HAS_CONFIGURED_VALUE)
+        .await
+        .map_err(write("configured marker"))?;
+
+    let device_info = run_startup_sequence(&adaptor, false)
+        .await
+        .map_err(FormError::Starting)?;
+    Ok(Coordinator::start(device_info, adaptor))
+}
+
+async fn commission_network(adaptor: &Adaptor) -> Result<(), FormError> {
+    // Subscribe before starting so the notification can not be missed
+    let mut notifications = adaptor.subscribe::<BdbCommissioningNotification>();
+    adaptor
+        .queue_sync(commands::appconfig::BdbStartCommissioning {
+            mode: commissioning_mode::NWK_FORMATION,
+        })
+        .await
+        .map_err(FormError::StartCommissioning)?
+        .map_err(FormError::StartCommissioningFailed)?;
+
+    let wait_for_formation = async {
+        while let Some(notification) = notifications.next().await {
+            let notification =
+                notification.map_err(FormError::Notifications)?;
+            match notification.status {
+                BdbCommissioningStatus::InProgress => continue,
+                BdbCommissioningStatus::Success => return Ok(()),
+                other => return Err(FormError::Commissioning(other)),
+            }
+        }
+        unreachable!("the adaptor keeps the notification stream open")
+    };
+    tokio::time::timeout(FORMATION_TIMEOUT, wait_for_formation)
+        .await
+        .map_err(|_| FormError::Timeout(FORMATION_TIMEOUT))?
+}
+
+/// Brings the adaptor from any state to running as coordinator. Also used
+/// by the [supervisor](crate::supervisor) after reconnecting.
+#[instrument(skip(adaptor))]
+pub(crate) async fn run_startup_sequence(
+    adaptor: &Adaptor,
+    skip_reset: bool,
+) -> Result<DeviceInfo, StartUpError> {
     if !skip_reset {
-        reset_device(&mut adaptor).await?;
+        reset_device(adaptor).await?;
     }
-    use_maximum_tx_power(&mut adaptor).await?;
-    let device_info = start_as_coordinator_if_needed(&mut adaptor).await?;
+    use_maximum_tx_power(adaptor).await?;
+    let device_info = start_as_coordinator_if_needed(adaptor).await?;
     debug!("device started as coordinator");
-    register_endpoints(&mut adaptor, default_endpoints())
+    register_endpoints(adaptor, default_endpoints())
         .await
         .map_err(StartUpError::RegisterEndpoints)?;
     debug!("needed endpoints registered on device");
-    add_to_green_power_group(&mut adaptor).await?;
+    add_to_green_power_group(adaptor).await?;
     debug!("added device to green power group");
-    Ok(Coordinator::start(device_info, adaptor))
+    Ok(device_info)
 }
 
-async fn use_maximum_tx_power(
-    adaptor: &mut Adaptor,
-) -> Result<(), StartUpError> {
+async fn use_maximum_tx_power(adaptor: &Adaptor) -> Result<(), StartUpError> {
     adaptor
         .queue_sync(commands::sys::SetTxPower { level: 20 })
         .await
@@ -45,7 +196,7 @@ async fn use_maximum_tx_power(
 }
 
 #[instrument(skip(adaptor))]
-pub async fn reset_device(adaptor: &mut Adaptor) -> Result<(), StartUpError> {
+pub async fn reset_device(adaptor: &Adaptor) -> Result<(), StartUpError> {
     let ResetInd {
         product_id,
         major_rel,
@@ -65,7 +216,7 @@ pub async fn reset_device(adaptor: &mut Adaptor) -> Result<(), StartUpError> {
 
 #[instrument(skip(adaptor))]
 pub async fn check_connection_to_adapter(
-    adaptor: &mut Adaptor,
+    adaptor: &Adaptor,
 ) -> Result<(), StartUpError> {
     let res = adaptor
         .queue_sync(commands::sys::Ping)
@@ -80,7 +231,7 @@ pub async fn check_connection_to_adapter(
 
 #[instrument(skip(adaptor))]
 async fn start_as_coordinator_if_needed(
-    adaptor: &mut Adaptor,
+    adaptor: &Adaptor,
 ) -> Result<DeviceInfo, StartUpError> {
     use commands::zdo::StartupFromAppReply;
     loop {
@@ -113,7 +264,7 @@ async fn start_as_coordinator_if_needed(
 
 #[instrument(skip(adaptor))]
 async fn add_to_green_power_group(
-    adaptor: &mut Adaptor,
+    adaptor: &Adaptor,
 ) -> Result<(), StartUpError> {
     let _ = adaptor
         .queue_sync(commands::zdo::ExtFindGroup {
@@ -128,7 +279,7 @@ async fn add_to_green_power_group(
 
 #[instrument(skip(adaptor, endpoints))]
 async fn register_endpoints(
-    adaptor: &mut Adaptor,
+    adaptor: &Adaptor,
     endpoints: impl IntoIterator<Item = Endpoint>,
 ) -> Result<(), RegisterEndpointsError> {
     // Note, `z2m` checks if the endpoint is already registered first
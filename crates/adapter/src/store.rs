@@ -0,0 +1,377 @@
+//! Remembers the devices on the network across restarts. Devices are keyed
+//! by their IEEE address, the short address may change when a device
+//! rejoins.
+//!
+//! Keep a store up to date with [`Coordinator::track_devices`].
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::SystemTime;
+use std::{fs, io};
+
+use futures::StreamExt;
+use serde::{Deserialize, Serialize};
+use tokio::task;
+use tracing::{debug, warn};
+use zstacker_znp_protocol::commands::af::IncomingMsg;
+use zstacker_znp_protocol::commands::zdo::{EndDeviceAnnceInd, IeeeAddrRsp};
+use zstacker_znp_protocol::commands::{IeeeAddr, ShortAddr};
+
+use crate::coordinator::Coordinator;
+use crate::interview::DeviceDescription;
+
+/// What is known about a device on the network
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct KnownDevice {
+    pub ieee_addr: IeeeAddr,
+    /// `None` once another device took the address, until this device
+    /// announces itself or its address is looked up
+    pub short_addr: Option<ShortAddr>,
+    /// Result of the last [`Coordinator::interview`], holds the endpoints
+    pub description: Option<DeviceDescription>,
+    /// When the device last announced itself or send a message
+    pub last_seen: Option<SystemTime>,
+    /// Link quality of the last message received from the device
+    pub lqi: Option<u8>,
+    /// As reported by the basic cluster
+    #[serde(default)]
+    pub model_id: Option<String>,
+    /// Name given to the device by the user
+    #[serde(default)]
+    pub friendly_name: Option<String>,
+}
+
+impl KnownDevice {
+    pub fn new(ieee_addr: IeeeAddr, short_addr: ShortAddr) -> Self {
+        Self {
+            ieee_addr,
+            short_addr: Some(short_addr),
+            description: None,
+            last_seen: None,
+            lqi: None,
+            model_id: None,
+            friendly_name: None,
+        }
+    }
+}
+
+/// Storage for [`KnownDevice`]s. Implement `get`, `insert`, `remove` and
+/// `devices`, the `record_*` methods build on those.
+pub trait DeviceStore: Send + 'static {
+    type Error: std::error::Error + Send + Sync + 'static;
+
+    fn get(&self, ieee_addr: IeeeAddr) -> Option<KnownDevice>;
+    fn devices(&self) -> Vec<KnownDevice>;
+    /// Replaces the device with the same IEEE address
+    fn insert(&mut self, device: KnownDevice) -> Result<(), Self::Error>;
+    fn remove(
+        &mut self,
+        ieee_addr: IeeeAddr,
+    ) -> Result<Option<KnownDevice>, Self::Error>;
+
+    fn find_by_short(&self, short_addr: ShortAddr) -> Option<KnownDevice> {
+        self.devices()
+            .into_iter()
+            .find(|device| device.short_addr == Some(short_addr))
+    }
+
+    fn find_by_name(&self, friendly_name: &str) -> Option<KnownDevice> {
+        self.devices().into_iter().find(|device| {
+            device.friendly_name.as_deref() == Some(friendly_name)
+        })
+    }
+
+    /// The device with `ieee_addr` now uses `short_addr`. Any other device
+    /// that claimed `short_addr` has since left or changed address, its
+    /// short address becomes unknown.
+    fn record_address(
+        &mut self,
+        ieee_addr: IeeeAddr,
+        short_addr: ShortAddr,
+    ) -> Result<(), Self::Error> {
+        if let Some(mut previous) = self.find_by_short(short_addr)
+            && previous.ieee_addr != ieee_addr
+        {
+            debug!("{:?} no longer uses {short_addr:?}", previous.ieee_addr);
+            previous.short_addr = None;
+            self.insert(previous)?;
+        }
+        let mut device = self
+            .get(ieee_addr)
+            .unwrap_or_else(|| KnownDevice::new(ieee_addr, short_addr));
+        if device.short_addr != Some(short_addr) {
+            debug!("{ieee_addr:?} moved to {short_addr:?}");
+            device.short_addr = Some(short_addr);
+        }
+        self.insert(device)
+    }
+
+    /// The device at `short_addr` was heard from, with link quality `lqi`
+    /// if it was a message. Does nothing for unknown devices.
+    fn record_seen(
+        &mut self,
+        short_addr: ShortAddr,
+        lqi: Option<u8>,
+        at: SystemTime,
+    ) -> Result<(), Self::Error> {
+        let Some(mut device) = self.find_by_short(short_addr) else {
+            return Ok(());
+        };
+        device.last_seen = Some(at);
+        device.lqi = lqi.or(device.lqi);
+        self.insert(device)
+    }
+
+    fn record_interview(
+        &mut self,
+        ieee_addr: IeeeAddr,
+        description: DeviceDescription,
+    ) -> Result<(), Self::Error> {
+        let mut device = self
+            .get(ieee_addr)
+            .unwrap_or_else(|| KnownDevice::new(ieee_addr, description.addr));
+        device.short_addr = Some(description.addr);
+        device.description = Some(description);
+        self.insert(device)
+    }
+}
+
+/// Forgets everything once dropped
+#[derive(Debug, Clone, Default)]
+pub struct MemoryStore {
+    devices: HashMap<IeeeAddr, KnownDevice>,
+}
+
+impl DeviceStore for MemoryStore {
+    type Error = std::convert::Infallible;
+
+    fn get(&self, ieee_addr: IeeeAddr) -> Option<KnownDevice> {
+        self.devices.get(&ieee_addr).cloned()
+    }
+
+    fn devices(&self) -> Vec<KnownDevice> {
+        self.devices.values().cloned().collect()
+    }
+
+    fn insert(&mut self, device: KnownDevice) -> Result<(), Self::Error> {
+        self.devices.insert(device.ieee_addr, device);
+        Ok(())
+    }
+
+    fn remove(
+        &mut self,
+        ieee_addr: IeeeAddr,
+    ) -> Result<Option<KnownDevice>, Self::Error> {
+        Ok(self.devices.remove(&ieee_addr))
+    }
+
+    fn find_by_short(&self, short_addr: ShortAddr) -> Option<KnownDevice> {
+        self.devices
+            .values()
+            .find(|device| device.short_addr == Some(short_addr))
+            .cloned()
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum JsonStoreError {
+    #[error("Could not read or write the device file")]
+    Io(#[source] io::Error),
+    #[error("Device file is not valid")]
+    Json(#[source] serde_json::Error),
+}
+
+/// Keeps all devices in memory and writes them to a JSON file on every
+/// change. The file is replaced atomically, a crash leaves either the old
+/// or the new version.
+///
+/// Devices are seen with every message they send, when they were last
+/// seen and their link quality are only written with the next other
+/// change, on [`JsonFileStore::flush`] or once the store is dropped.
+#[derive(Debug)]
+pub struct JsonFileStore {
+    path: PathBuf,
+    memory: MemoryStore,
+    /// Last seen times or link qualities changed since the last save
+    unsaved: bool,
+}
+
+impl JsonFileStore {
+    /// Starts empty if there is no file at `path` yet
+    pub fn open(path: impl AsRef<Path>) -> Result<Self, JsonStoreError> {
+        let path = path.as_ref().to_path_buf();
+        let devices: Vec<KnownDevice> = match fs::read(&path) {
+            Ok(bytes) => {
+                serde_json::from_slice(&bytes).map_err(JsonStoreError::Json)?
+            }
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Vec::new(),
+            Err(e) => return Err(JsonStoreError::Io(e)),
+        };
+        let devices = devices
+            .into_iter()
+            .map(|device| (device.ieee_addr, device))
+            .collect();
+        Ok(Self {
+            path,
+            memory: MemoryStore { devices },
+            unsaved: false,
+        })
+    }
+
+    /// Writes last seen times and link qualities that are not yet saved
+    pub fn flush(&mut self) -> Result<(), JsonStoreError> {
+        if self.unsaved {
+            self.save()?;
+        }
+        Ok(())
+    }
+
+    fn save(&mut self) -> Result<(), JsonStoreError> {
+        let mut devices = self.memory.devices();
+        devices.sort_by_key(|device| device.ieee_addr.0);
+        let json = serde_json::to_vec_pretty(&devices)
+            .map_err(JsonStoreError::Json)?;
+        let tmp = self.path.with_extension("tmp");
+        fs::write(&tmp, json).map_err(JsonStoreError::Io)?;
+        fs::rename(&tmp, &self.path).map_err(JsonStoreError::Io)?;
+        self.unsaved = false;
+        Ok(())
+    }
+}
+
+impl Drop for JsonFileStore {
+    fn drop(&mut self) {
+        if let Err(e) = self.flush() {
+            warn!("Could not save when devices were last seen: {e}");
+        }
+    }
+}
+
+/// Whether the devices differ in no more then when they were last seen
+fn only_seen_changed(old: &KnownDevice, new: &KnownDevice) -> bool {
+    let unseen = |device: &KnownDevice| KnownDevice {
+        last_seen: None,
+        lqi: None,
+        ..device.clone()
+    };
+    unseen(old) == unseen(new)
+}
+
+impl DeviceStore for JsonFileStore {
+    type Error = JsonStoreError;
+
+    fn get(&self, ieee_addr: IeeeAddr) -> Option<KnownDevice> {
+        self.memory.get(ieee_addr)
+    }
+
+    fn devices(&self) -> Vec<KnownDevice> {
+        self.memory.devices()
+    }
+
+    fn insert(&mut self, device: KnownDevice) -> Result<(), Self::Error> {
+        let only_seen = self
+            .memory
+            .get(device.ieee_addr)
+            .is_some_and(|old| only_seen_changed(&old, &device));
+        let Ok(()) = self.memory.insert(device);
+        if only_seen {
+            self.unsaved = true;
+            Ok(())
+        } else {
+            self.save()
+        }
+    }
+
+    fn remove(
+        &mut self,
+        ieee_addr: IeeeAddr,
+    ) -> Result<Option<KnownDevice>, Self::Error> {
+        let Ok(removed) = self.memory.remove(ieee_addr);
+        if removed.is_some() {
+            self.save()?;
+        }
+        Ok(removed)
+    }
+
+    fn find_by_short(&self, short_addr: ShortAddr) -> Option<KnownDevice> {
+        self.memory.find_by_short(short_addr)
+    }
+}
+
+enum Event {
+    Announce(EndDeviceAnnceInd),
+    Address(IeeeAddrRsp),
+    Message(IncomingMsg),
+}
+
+impl Event {
+    fn apply<S: DeviceStore>(self, store: &mut S) -> Result<(), S::Error> {
+        match self {
+            Event::Announce(ind) => {
+                store.record_address(ind.ieee_addr, ind.nwk_addr)?;
+                store.record_seen(ind.nwk_addr, None, SystemTime::now())
+            }
+            Event::Address(rsp) if rsp.status.is_success() => {
+                store.record_address(rsp.ieee_addr, rsp.nwk_addr)
+            }
+            Event::Address(_) => Ok(()),
+            Event::Message(msg) => store.record_seen(
+                msg.src_addr,
+                Some(msg.link_quality),
+                SystemTime::now(),
+            ),
+        }
+    }
+}
+
+impl Coordinator {
+    /// Keeps `store` up to date with device announcements, address lookups
+    /// and incoming messages. Interview results are not recorded
+    /// automatically, use [`DeviceStore::record_interview`].
+    ///
+    /// The store is updated on the blocking thread pool, it may write to
+    /// disk while holding the lock.
+    ///
+    /// Stops once every handle to the coordinator is dropped, or when the
+    /// returned handle is aborted.
+    pub fn track_devices<S: DeviceStore>(
+        &self,
+        store: Arc<Mutex<S>>,
+    ) -> task::JoinHandle<()> {
+        let announcements = self
+            .subscribe::<EndDeviceAnnceInd>()
+            .map(|ind| ind.map(Event::Announce));
+        let addresses = self
+            .subscribe::<IeeeAddrRsp>()
+            .map(|rsp| rsp.map(Event::Address));
+        let messages =
+            self.incoming_messages().map(|msg| msg.map(Event::Message));
+        let mut events = futures::stream::select(
+            announcements,
+            futures::stream::select(addresses, messages),
+        );
+
+        task::spawn(async move {
+            while let Some(event) = events.next().await {
+                let event = match event {
+                    Ok(event) => event,
+                    Err(e) => {
+                        warn!("Device store may miss updates: {e}");
+                        continue;
+                    }
+                };
+                let store = Arc::clone(&store);
+                let update = task::spawn_blocking(move || {
+                    let mut store = store.lock().expect("store mutex poisoned");
+                    event.apply(&mut *store)
+                });
+                match update.await {
+                    Ok(Ok(())) => (),
+                    Ok(Err(e)) => warn!("Could not update device store: {e}"),
+                    Err(e) => warn!("Updating the device store failed: {e}"),
+                }
+            }
+        })
+    }
+}
@@ -0,0 +1,155 @@
+//! Opt-in recovery from fatal io errors, such as an unplugged adaptor or a
+//! corrupted frame. Without a supervisor every request fails with
+//! [`QueueError::IoTask`](crate::coordinator::QueueError::IoTask) once the
+//! io task ended.
+//!
+//! The supervisor gets a working transport, restarts the io task and runs
+//! the startup sequence again. Subscriptions keep working across a
+//! reconnect, they only miss the notifications send while disconnected.
+
+use std::io;
+use std::sync::Weak;
+use std::time::Duration;
+
+use tokio::task;
+use tracing::{info, instrument, warn};
+
+use crate::coordinator::{Adaptor, Coordinator, Shared};
+use crate::startup::run_startup_sequence;
+use crate::transport::{BoxedTransport, Transport};
+
+#[derive(Debug, Clone)]
+pub enum ConnectionState {
+    Connected,
+    /// The io task ran into this error and ended
+    Disconnected(crate::coordinator::IoTaskError),
+    /// The supervisor is trying to get the connection back
+    Reconnecting {
+        attempt: u32,
+    },
+}
+
+#[derive(Debug, Clone)]
+pub struct SupervisorConfig {
+    /// First try talking over the transport the failed io task used, only
+    /// reopen if that fails. Works for errors such as a checksum mismatch
+    /// but not for an unplugged adaptor.
+    pub reuse_transport: bool,
+    /// Passed to the startup sequence after reconnecting
+    pub skip_reset: bool,
+    /// Delay after the first failed attempt, doubles every attempt
+    pub retry_delay: Duration,
+    pub max_retry_delay: Duration,
+}
+
+impl Default for SupervisorConfig {
+    fn default() -> Self {
+        Self {
+            reuse_transport: true,
+            skip_reset: false,
+            retry_delay: Duration::from_millis(500),
+            max_retry_delay: Duration::from_secs(30),
+        }
+    }
+}
+
+impl Coordinator {
+    /// Reconnects whenever the connection to the adaptor is lost, `reopen`
+    /// is called to get a new transport. Follow progress using
+    /// [`Coordinator::connection_state`].
+    ///
+    /// The supervisor stops once every handle to the coordinator is
+    /// dropped, or when the returned handle is aborted.
+    pub fn supervise<F, T>(
+        &self,
+        mut reopen: impl FnMut() -> F + Send + 'static,
+        config: SupervisorConfig,
+    ) -> task::JoinHandle<()>
+    where
+        F: Future<Output = io::Result<T>> + Send,
+        T: Transport,
+    {
+        let adaptor = self.adaptor().downgrade();
+        let mut states = self.adaptor().connection_state_receiver();
+        task::spawn(async move {
+            loop {
+                let disconnected = states.wait_for(|state| {
+                    matches!(state, ConnectionState::Disconnected(_))
+                });
+                if disconnected.await.is_err() {
+                    return; // Adaptor dropped
+                }
+                if reconnect(&adaptor, &mut reopen, &config).await.is_none() {
+                    return; // Adaptor dropped while reconnecting
+                }
+            }
+        })
+    }
+}
+
+/// Only holds on to the adaptor during an attempt so it is dropped with
+/// the last handle. Returns `None` if that happens before reconnecting.
+#[instrument(skip_all)]
+async fn reconnect<F, T>(
+    adaptor: &Weak<Shared>,
+    reopen: &mut impl FnMut() -> F,
+    config: &SupervisorConfig,
+) -> Option<()>
+where
+    F: Future<Output = io::Result<T>>,
+    T: Transport,
+{
+    let mut delay = config.retry_delay;
+    let mut attempt = 1;
+    loop {
+        let adaptor = Adaptor::from_shared(adaptor.upgrade()?);
+        adaptor.set_connection_state(ConnectionState::Reconnecting { attempt });
+        match reconnect_once(&adaptor, reopen, config, attempt).await {
+            Ok(()) => {
+                info!("Reconnected to the adaptor after {attempt} attempt(s)");
+                adaptor.set_connection_state(ConnectionState::Connected);
+                return Some(());
+            }
+            Err(err) => warn!("Reconnect attempt {attempt} failed: {err:?}"),
+        }
+        drop(adaptor);
+        tokio::time::sleep(delay).await;
+        delay = (delay * 2).min(config.max_retry_delay);
+        attempt += 1;
+    }
+}
+
+async fn reconnect_once<F, T>(
+    adaptor: &Adaptor,
+    reopen: &mut impl FnMut() -> F,
+    config: &SupervisorConfig,
+    attempt: u32,
+) -> Result<(), ReconnectError>
+where
+    F: Future<Output = io::Result<T>>,
+    T: Transport,
+{
+    // The previous transport must be gone before reopening, a serial port
+    // can only be opened once
+    let recovered = adaptor
+        .stop_io_task()
+        .await
+        .filter(|_| config.reuse_transport && attempt == 1);
+    let transport: BoxedTransport = match recovered {
+        Some(transport) => transport,
+        None => Box::new(reopen().await.map_err(ReconnectError::Reopen)?),
+    };
+    adaptor.restart_io_task(transport).await;
+    run_startup_sequence(adaptor, config.skip_reset)
+        .await
+        .map_err(ReconnectError::StartUp)?;
+    Ok(())
+}
+
+#[derive(Debug, thiserror::Error)]
+enum ReconnectError {
+    #[error("Could not reopen the transport")]
+    Reopen(#[source] io::Error),
+    #[error("Could not run the startup sequence")]
+    StartUp(#[source] crate::error::StartUpError),
+}
@@ -0,0 +1,283 @@
+//! Map of the mesh: which devices hear each other, how well and through
+//! which neighbor messages are routed. Exports to Graphviz DOT and to JSON
+//! shaped like the network map of zigbee2mqtt.
+
+use std::collections::VecDeque;
+use std::fmt::Write;
+
+use serde::{Serialize, Serializer};
+use tracing::{debug, instrument};
+use zstacker_znp_protocol::commands::zdo::{
+    NeighborLqi, RouterStatus, RoutingEntry,
+};
+use zstacker_znp_protocol::commands::{DeviceType, IeeeAddr, ShortAddr};
+
+use crate::coordinator::Coordinator;
+use crate::list::{Scan, crawl};
+
+/// How a neighbor relates to the device that reported it. Serialized as
+/// the number in the neighbor table, like zigbee2mqtt does.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Relationship {
+    Parent,
+    Child,
+    Sibling,
+    None,
+    PreviousChild,
+    Reserved(u8),
+}
+
+impl Relationship {
+    pub fn from_raw(raw: u8) -> Self {
+        match raw {
+            0 => Self::Parent,
+            1 => Self::Child,
+            2 => Self::Sibling,
+            3 => Self::None,
+            4 => Self::PreviousChild,
+            other => Self::Reserved(other),
+        }
+    }
+
+    pub fn to_raw(self) -> u8 {
+        match self {
+            Self::Parent => 0,
+            Self::Child => 1,
+            Self::Sibling => 2,
+            Self::None => 3,
+            Self::PreviousChild => 4,
+            Self::Reserved(raw) => raw,
+        }
+    }
+}
+
+impl Serialize for Relationship {
+    fn serialize<S: Serializer>(
+        &self,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        serializer.serialize_u8(self.to_raw())
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize)]
+pub struct NodeAddr {
+    #[serde(rename = "ieeeAddr", serialize_with = "ieee_hex")]
+    pub ieee_addr: IeeeAddr,
+    #[serde(rename = "networkAddress")]
+    pub short_addr: ShortAddr,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Node {
+    #[serde(flatten)]
+    pub addr: NodeAddr,
+    #[serde(rename = "type", serialize_with = "device_type_name")]
+    pub device_type: DeviceType,
+}
+
+/// A neighbor table entry. Both devices usually report each other so most
+/// pairs are linked twice, once in each direction.
+#[derive(Debug, Clone, Serialize)]
+pub struct Link {
+    /// The neighbor
+    pub source: NodeAddr,
+    /// The device that reported the neighbor
+    pub target: NodeAddr,
+    /// Link quality of the messages the target receives from the source
+    #[serde(rename = "linkquality")]
+    pub lqi: u8,
+    /// Hops from the coordinator to the source
+    pub depth: u8,
+    pub relationship: Relationship,
+    /// Destinations the target reaches through the source
+    pub routes: Vec<ShortAddr>,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct Topology {
+    pub nodes: Vec<Node>,
+    pub links: Vec<Link>,
+}
+
+impl Topology {
+    pub fn node(&self, short_addr: ShortAddr) -> Option<&Node> {
+        self.nodes
+            .iter()
+            .find(|node| node.addr.short_addr == short_addr)
+    }
+
+    fn insert_node(&mut self, node: Node) {
+        if self.node(node.addr.short_addr).is_none() {
+            self.nodes.push(node);
+        }
+    }
+
+    /// Adds what `target` reported about its surroundings
+    fn insert_tables(
+        &mut self,
+        target: NodeAddr,
+        neighbors: Vec<NeighborLqi>,
+        routes: &[RoutingEntry],
+    ) {
+        for neighbor in neighbors {
+            let source = node_addr(&neighbor);
+            self.insert_node(Node {
+                addr: source,
+                device_type: neighbor.device_type,
+            });
+            self.links.push(Link {
+                source,
+                target,
+                lqi: neighbor.lqi,
+                depth: neighbor.depth,
+                relationship: Relationship::from_raw(neighbor.relationship),
+                routes: routes
+                    .iter()
+                    .filter(|route| {
+                        matches!(route.status, RouterStatus::Active)
+                    })
+                    .filter(|route| route.next_hop == source.short_addr)
+                    .map(|route| route.destination_address)
+                    .collect(),
+            });
+        }
+    }
+
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self)
+            .expect("topology only contains strings and numbers")
+    }
+
+    /// Render with for example `dot -Tsvg`. Solid lines connect parents
+    /// and children, lines of links that carry routes are bold.
+    pub fn to_dot(&self) -> String {
+        let mut dot = String::from("digraph G {\n  node [shape=record];\n");
+        for node in &self.nodes {
+            let NodeAddr {
+                ieee_addr,
+                short_addr,
+            } = node.addr;
+            writeln!(
+                dot,
+                "  \"{ieee}\" [label=\"{{{ty}|{ieee}|0x{short:04x}}}\"];",
+                ieee = ieee_string(ieee_addr),
+                ty = device_type_str(node.device_type),
+                short = short_addr.0,
+            )
+            .expect("writing to a String never fails");
+        }
+        for link in &self.links {
+            let style = match link.relationship {
+                Relationship::Parent | Relationship::Child => "solid",
+                _ => "dashed",
+            };
+            let bold = if link.routes.is_empty() { "" } else { ",bold" };
+            writeln!(
+                dot,
+                "  \"{}\" -> \"{}\" [label=\"{}\", style=\"{style}{bold}\"];",
+                ieee_string(link.source.ieee_addr),
+                ieee_string(link.target.ieee_addr),
+                link.lqi,
+            )
+            .expect("writing to a String never fails");
+        }
+        dot.push_str("}\n");
+        dot
+    }
+}
+
+impl Coordinator {
+    /// Crawls the network starting at the coordinator, asking at most
+    /// `max_concurrent` routers at a time. Every router is asked for its
+    /// neighbor and routing table, end devices keep neither. Routers that
+    /// do not answer are listed as unreachable, the map shows them as
+    /// reported by their neighbors.
+    #[instrument(skip(self))]
+    pub async fn topology(&self, max_concurrent: usize) -> Scan<Topology> {
+        let (mut tables, unreachable) =
+            crawl([self.short_addr], max_concurrent, async |addr| {
+                debug!("asking {addr:?} for its neighbors");
+                let neighbors = self.lqi_table_for(addr).await?;
+                let routes = self.routing_table_for(addr).await?;
+                let routers = neighbors
+                    .iter()
+                    .filter(|neighbor| is_router(neighbor))
+                    .map(|neighbor| neighbor.network_address)
+                    .collect();
+                Ok(((neighbors, routes), routers))
+            })
+            .await;
+
+        let coordinator = NodeAddr {
+            ieee_addr: self.ieee_addr,
+            short_addr: self.short_addr,
+        };
+        let mut topology = Topology::default();
+        topology.insert_node(Node {
+            addr: coordinator,
+            device_type: DeviceType::Coordinator,
+        });
+        // Walk the tables breadth first from the coordinator so the order
+        // does not depend on which router answered first
+        let mut to_add = VecDeque::from([coordinator]);
+        while let Some(target) = to_add.pop_front() {
+            let Some((neighbors, routes)) = tables.remove(&target.short_addr)
+            else {
+                continue;
+            };
+            to_add.extend(
+                neighbors
+                    .iter()
+                    .filter(|neighbor| is_router(neighbor))
+                    .map(node_addr),
+            );
+            topology.insert_tables(target, neighbors, &routes);
+        }
+        Scan {
+            found: topology,
+            unreachable,
+        }
+    }
+}
+
+fn is_router(neighbor: &NeighborLqi) -> bool {
+    matches!(
+        neighbor.device_type,
+        DeviceType::Coordinator | DeviceType::Router
+    )
+}
+
+fn node_addr(neighbor: &NeighborLqi) -> NodeAddr {
+    NodeAddr {
+        ieee_addr: neighbor.extended_address,
+        short_addr: neighbor.network_address,
+    }
+}
+
+fn ieee_string(addr: IeeeAddr) -> String {
+    format!("0x{:016x}", addr.0)
+}
+
+fn device_type_str(device_type: DeviceType) -> &'static str {
+    match device_type {
+        DeviceType::Coordinator => "Coordinator",
+        DeviceType::Router => "Router",
+        DeviceType::EndDevice => "EndDevice",
+        DeviceType::None => "Unknown",
+    }
+}
+
+fn ieee_hex<S: Serializer>(
+    addr: &IeeeAddr,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    serializer.serialize_str(&ieee_string(*addr))
+}
+
+fn device_type_name<S: Serializer>(
+    device_type: &DeviceType,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    serializer.serialize_str(device_type_str(*device_type))
+}
@@ -0,0 +1,24 @@
+//! The connection to the adaptor. Usually a serial port but network attached
+//! coordinators (ser2net, TubesZB, SLZB-06) are reached over TCP.
+
+use std::fmt::Debug;
+
+use tokio::io::{AsyncRead, AsyncWrite};
+
+/// Anything bytes can be send to and read from the adaptor over.
+/// Implemented for all types that qualify, for example
+/// [`tokio_serial::SerialStream`], [`tokio::net::TcpStream`] and
+/// [`tokio::io::DuplexStream`].
+pub trait Transport:
+    AsyncRead + AsyncWrite + Unpin + Send + Debug + 'static
+{
+}
+
+impl<T> Transport for T where
+    T: AsyncRead + AsyncWrite + Unpin + Send + Debug + 'static
+{
+}
+
+/// What the [`Adaptor`](crate::coordinator::Adaptor) stores, so it does not
+/// need to be generic over the transport.
+pub type BoxedTransport = Box<dyn Transport>;
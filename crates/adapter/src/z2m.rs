@@ -0,0 +1,283 @@
+//! Reads the devices zigbee2mqtt paired from its data directory, so they
+//! can be addressed without interviewing them again.
+//!
+//! zigbee2mqtt keeps the devices in `database.db`, one JSON object per
+//! line, and their friendly names in `configuration.yaml`.
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+use std::{fs, io};
+
+use serde::Deserialize;
+use zstacker_znp_protocol::commands::af::ClusterId;
+use zstacker_znp_protocol::commands::zdo::SimpleDescriptor;
+use zstacker_znp_protocol::commands::{IeeeAddr, ShortAddr};
+
+use crate::interview::{DeviceDescription, PowerSource};
+use crate::nvram::items::LogicalDeviceType;
+use crate::store::KnownDevice;
+
+pub const DATABASE: &str = "database.db";
+pub const CONFIGURATION: &str = "configuration.yaml";
+
+#[derive(Debug, thiserror::Error)]
+pub enum ImportError {
+    #[error("Could not read {path:?}")]
+    Io {
+        path: PathBuf,
+        #[source]
+        source: io::Error,
+    },
+    #[error("Line {line} of the database is not a valid device")]
+    Database {
+        line: usize,
+        #[source]
+        source: serde_json::Error,
+    },
+    #[error("Could not parse {path:?}")]
+    Configuration {
+        path: PathBuf,
+        #[source]
+        source: serde_yaml_ng::Error,
+    },
+    #[error("Not an IEEE address: {0:?}, expected 0x followed by 16 digits")]
+    IeeeAddr(String),
+}
+
+/// Types of the `database.db` lines that describe a device other then
+/// the coordinator. The database also holds the coordinator and groups.
+const DEVICE_TYPES: [&str; 4] =
+    ["Router", "EndDevice", "Unknown", "GreenPower"];
+
+/// Any line of `database.db`, to tell devices apart from other entries
+#[derive(Debug, Deserialize)]
+struct DbEntry {
+    #[serde(rename = "type")]
+    ty: String,
+}
+
+/// A device line of `database.db`, as written by zigbee-herdsman
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct DbDevice {
+    #[serde(rename = "type")]
+    ty: String,
+    ieee_addr: String,
+    nwk_addr: u16,
+    manuf_id: Option<u16>,
+    model_id: Option<String>,
+    power_source: Option<String>,
+    #[serde(default)]
+    ep_list: Vec<u8>,
+    #[serde(default)]
+    endpoints: HashMap<String, DbEndpoint>,
+    #[serde(default)]
+    interview_completed: bool,
+    /// Milliseconds since the unix epoch
+    last_seen: Option<u64>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct DbEndpoint {
+    ep_id: u8,
+    prof_id: Option<u16>,
+    dev_id: Option<u16>,
+    #[serde(default)]
+    in_cluster_list: Vec<u16>,
+    #[serde(default)]
+    out_cluster_list: Vec<u16>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum DevicesSection {
+    Inline(HashMap<String, DeviceOptions>),
+    /// Name of a file next to `configuration.yaml` with the devices
+    File(String),
+}
+
+#[derive(Debug, Deserialize)]
+struct DeviceOptions {
+    friendly_name: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Configuration {
+    devices: Option<DevicesSection>,
+}
+
+/// Reads both files from the zigbee2mqtt data directory. Devices without a
+/// friendly name in the configuration get none.
+pub fn import(
+    data_dir: impl AsRef<Path>,
+) -> Result<Vec<KnownDevice>, ImportError> {
+    let data_dir = data_dir.as_ref();
+    let mut devices = read_database(data_dir.join(DATABASE))?;
+    let names = read_friendly_names(data_dir.join(CONFIGURATION))?;
+    for device in &mut devices {
+        device.friendly_name = names.get(&device.ieee_addr).cloned();
+    }
+    Ok(devices)
+}
+
+/// Every device in `database.db` except the coordinator, groups are
+/// skipped. Only devices zigbee2mqtt finished interviewing get a
+/// description.
+pub fn read_database(
+    path: impl AsRef<Path>,
+) -> Result<Vec<KnownDevice>, ImportError> {
+    let contents = read(path.as_ref())?;
+    let mut devices = Vec::new();
+    for (i, line) in contents.lines().enumerate() {
+        if line.trim().is_empty() {
+            continue;
+        }
+        let invalid = |source| ImportError::Database {
+            line: i + 1,
+            source,
+        };
+        let entry: DbEntry = serde_json::from_str(line).map_err(invalid)?;
+        if !DEVICE_TYPES.contains(&entry.ty.as_str()) {
+            continue;
+        }
+        let device: DbDevice = serde_json::from_str(line).map_err(invalid)?;
+        devices.push(device.into_known()?);
+    }
+    Ok(devices)
+}
+
+/// The friendly names in `configuration.yaml`, also when the devices are
+/// kept in a separate file
+pub fn read_friendly_names(
+    path: impl AsRef<Path>,
+) -> Result<HashMap<IeeeAddr, String>, ImportError> {
+    let path = path.as_ref();
+    let configuration: Configuration = parse_yaml(path)?;
+    let devices = match configuration.devices {
+        None => return Ok(HashMap::new()),
+        Some(DevicesSection::Inline(devices)) => devices,
+        Some(DevicesSection::File(file)) => {
+            let dir = path.parent().unwrap_or(Path::new(""));
+            parse_yaml(&dir.join(file))?
+        }
+    };
+    devices
+        .into_iter()
+        .filter_map(|(ieee, options)| Some((ieee, options.friendly_name?)))
+        .map(|(ieee, name)| Ok((parse_ieee(&ieee)?, name)))
+        .collect()
+}
+
+impl DbDevice {
+    fn into_known(self) -> Result<KnownDevice, ImportError> {
+        let ieee_addr = parse_ieee(&self.ieee_addr)?;
+        let short_addr = ShortAddr(self.nwk_addr);
+        let logical_type = match self.ty.as_str() {
+            "Router" => Some(LogicalDeviceType::Router),
+            "EndDevice" => Some(LogicalDeviceType::EndDevice),
+            _ => None,
+        };
+        let power_source = power_source(self.power_source.as_deref());
+
+        let description = logical_type
+            .filter(|_| self.interview_completed)
+            .map(|logical_type| DeviceDescription {
+                addr: short_addr,
+                manufacturer_code: self.manuf_id.unwrap_or(0),
+                logical_type,
+                // zigbee2mqtt does not store the capabilities the device
+                // announced with
+                mac_capabilities: None,
+                power_source,
+                endpoints: endpoints(&self.ep_list, self.endpoints),
+            });
+
+        Ok(KnownDevice {
+            ieee_addr,
+            short_addr: Some(short_addr),
+            description,
+            last_seen: self
+                .last_seen
+                .map(|ms| SystemTime::UNIX_EPOCH + Duration::from_millis(ms)),
+            lqi: None,
+            model_id: self.model_id,
+            friendly_name: None,
+        })
+    }
+}
+
+/// zigbee-herdsman stores the power source attribute of the basic cluster
+/// by name
+fn power_source(name: Option<&str>) -> PowerSource {
+    match name {
+        Some(name)
+            if name.starts_with("Mains")
+                || name.starts_with("Emergency mains")
+                || name == "DC Source" =>
+        {
+            PowerSource::Mains
+        }
+        Some("Battery") => PowerSource::DisposableBattery,
+        _ => PowerSource::Unknown,
+    }
+}
+
+/// In the order of the endpoint list, which is the order the device
+/// reported them in
+fn endpoints(
+    ep_list: &[u8],
+    mut endpoints: HashMap<String, DbEndpoint>,
+) -> Vec<SimpleDescriptor> {
+    let mut ordered: Vec<_> = ep_list
+        .iter()
+        .filter_map(|ep| endpoints.remove(&ep.to_string()))
+        .collect();
+    let mut rest: Vec<_> = endpoints.into_values().collect();
+    rest.sort_by_key(|endpoint| endpoint.ep_id);
+    ordered.extend(rest);
+
+    ordered
+        .into_iter()
+        .map(|endpoint| SimpleDescriptor {
+            endpoint: endpoint.ep_id,
+            profile_id: endpoint.prof_id.unwrap_or(0),
+            device_id: endpoint.dev_id.unwrap_or(0),
+            device_version: 0,
+            in_clusters: clusters(endpoint.in_cluster_list),
+            out_clusters: clusters(endpoint.out_cluster_list),
+        })
+        .collect()
+}
+
+fn clusters(ids: Vec<u16>) -> Vec<ClusterId> {
+    ids.into_iter().map(ClusterId).collect()
+}
+
+/// zigbee2mqtt writes IEEE addresses as `0x` followed by 16 hex digits
+fn parse_ieee(s: &str) -> Result<IeeeAddr, ImportError> {
+    s.strip_prefix("0x")
+        .filter(|digits| digits.len() == 16)
+        .and_then(|digits| u64::from_str_radix(digits, 16).ok())
+        .map(IeeeAddr)
+        .ok_or_else(|| ImportError::IeeeAddr(s.to_string()))
+}
+
+fn read(path: &Path) -> Result<String, ImportError> {
+    fs::read_to_string(path).map_err(|source| ImportError::Io {
+        path: path.to_path_buf(),
+        source,
+    })
+}
+
+fn parse_yaml<T: serde::de::DeserializeOwned>(
+    path: &Path,
+) -> Result<T, ImportError> {
+    serde_yaml_ng::from_str(&read(path)?).map_err(|source| {
+        ImportError::Configuration {
+            path: path.to_path_buf(),
+            source,
+        }
+    })
+}
@@ -0,0 +1,32 @@
+use futures_concurrency::future::Race;
+use tokio::io::DuplexStream;
+use zstacker_test_support::mock_adaptor;
+use zstacker_znp::af::Message;
+use zstacker_znp::coordinator::Adaptor;
+use zstacker_znp::start_coordinator;
+use zstacker_znp_protocol::commands::ShortAddr;
+use zstacker_znp_protocol::commands::af::ClusterId;
+
+async fn run_test(serial: DuplexStream) {
+    let adaptor = Adaptor::start(serial);
+    let coordinator =
+        start_coordinator(adaptor, Vec::new(), false).await.unwrap();
+    for _ in 0..2 {
+        coordinator
+            .send_af(Message {
+                dst_addr: ShortAddr(2),
+                dst_endpoint: 1,
+                src_endpoint: 1,
+                cluster_id: ClusterId(6),
+                data: vec![0x01, 0x00, 0x02],
+            })
+            .await
+            .unwrap();
+    }
+}
+
+#[tokio::test]
+async fn send_af_awaits_data_confirm() {
+    let (b, a) = tokio::io::duplex(4096);
+    (mock_adaptor(a), run_test(b)).race().await;
+}
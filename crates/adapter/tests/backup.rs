@@ -0,0 +1,84 @@
+use futures_concurrency::future::Race;
+use tokio::io::DuplexStream;
+use zstacker_test_support::mock_adaptor;
+use zstacker_znp::backup::{Backup, RestoreError};
+use zstacker_znp::coordinator::Adaptor;
+use zstacker_znp::nvram::ids;
+use zstacker_znp::start_coordinator;
+
+/// As written by zigbee2mqtt, the link key is derived from the seed
+const BACKUP: &str = r#"{
+  "metadata": {
+    "format": "zigpy/open-coordinator-backup",
+    "version": 1,
+    "source": "zigbee-herdsman@0.13.65",
+    "internal": { "date": "2024-01-01T00:00:00.000Z", "znpVersion": 1 }
+  },
+  "stack_specific": {
+    "zstack": { "tclk_seed": "000102030405060708090a0b0c0d0e0f" }
+  },
+  "coordinator_ieee": "00124b00aabbccdd",
+  "pan_id": "1a62",
+  "extended_pan_id": "dddddddddddddddd",
+  "nwk_update_id": 2,
+  "security_level": 5,
+  "channel": 15,
+  "channel_mask": [15, 20],
+  "network_key": {
+    "key": "01030507090b0d0f00020406080a0c0d",
+    "sequence_number": 0,
+    "frame_counter": 4242
+  },
+  "devices": [
+    {
+      "nwk_address": "a1b2",
+      "ieee_address": "00124b0001020304",
+      "is_child": true,
+      "link_key": {
+        "key": "0707070707431b0a0f0f0f0f0f4b1302",
+        "tx_counter": 12,
+        "rx_counter": 34
+      }
+    },
+    {
+      "nwk_address": "0e0f",
+      "ieee_address": "00158d0001020304",
+      "is_child": false
+    }
+  ]
+}"#;
+
+async fn run_test(serial: DuplexStream) {
+    let adaptor = Adaptor::start(serial);
+    let coordinator =
+        start_coordinator(adaptor, Vec::new(), false).await.unwrap();
+
+    // The NIB of a network formed on a CC26xx
+    coordinator
+        .write_nvram_item(ids::NIB, &[0; 116])
+        .await
+        .unwrap();
+
+    let mut invalid = Backup::from_json(BACKUP).unwrap();
+    invalid.channel_mask.push(40);
+    assert!(matches!(
+        coordinator.restore_backup(&invalid).await,
+        Err(RestoreError::InvalidChannel(40))
+    ));
+
+    let backup = Backup::from_json(BACKUP).unwrap();
+    coordinator.restore_backup(&backup).await.unwrap();
+
+    let mut expected = backup.clone();
+    expected.metadata = coordinator.create_backup().await.unwrap().metadata;
+    expected.network_key.frame_counter += 10_000;
+    let restored = coordinator.create_backup().await.unwrap();
+    assert_eq!(restored, expected);
+    assert_eq!(Backup::from_json(&restored.to_json()).unwrap(), restored);
+}
+
+#[tokio::test]
+async fn restored_backup_reads_back() {
+    let (b, a) = tokio::io::duplex(4096);
+    (mock_adaptor(a), run_test(b)).race().await;
+}
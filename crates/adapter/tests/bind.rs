@@ -0,0 +1,48 @@
+use futures_concurrency::future::Race;
+use tokio::io::DuplexStream;
+use zstacker_test_support::mock_adaptor;
+use zstacker_znp::coordinator::Adaptor;
+use zstacker_znp::start_coordinator;
+use zstacker_znp_protocol::commands::af::ClusterId;
+use zstacker_znp_protocol::commands::zdo::BindDestination;
+use zstacker_znp_protocol::commands::{IeeeAddr, ShortAddr};
+
+/// The mock places this device at short address 0x0304
+const SWITCH: IeeeAddr = IeeeAddr(0x00124b0001020304);
+const LIGHT: IeeeAddr = IeeeAddr(0x00124b0005060708);
+
+async fn run_test(serial: DuplexStream) {
+    let adaptor = Adaptor::start(serial);
+    let coordinator =
+        start_coordinator(adaptor, Vec::new(), false).await.unwrap();
+
+    let to_light = BindDestination::Device {
+        ieee_addr: LIGHT,
+        endpoint: 1,
+    };
+    coordinator
+        .bind(SWITCH, 1, ClusterId(0x0008), to_light)
+        .await
+        .unwrap();
+    coordinator
+        .bind(SWITCH, 1, ClusterId(0x0006), BindDestination::Group(1))
+        .await
+        .unwrap();
+    coordinator
+        .unbind(SWITCH, 1, ClusterId(0x0006), BindDestination::Group(1))
+        .await
+        .unwrap();
+
+    let table = coordinator.binding_table(ShortAddr(0x0304)).await.unwrap();
+    assert_eq!(table.len(), 3, "should request every page");
+    assert!(table.iter().all(|entry| entry.src == SWITCH));
+    assert_eq!(table[1].cluster_id, ClusterId(0x0008));
+    assert_eq!(table[1].dst, to_light);
+    assert_eq!(table[2].dst, BindDestination::Group(2));
+}
+
+#[tokio::test]
+async fn bind_unbind_and_list() {
+    let (b, a) = tokio::io::duplex(4096);
+    (mock_adaptor(a), run_test(b)).race().await;
+}
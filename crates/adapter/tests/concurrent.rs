@@ -0,0 +1,50 @@
+use futures_concurrency::future::{Join, Race};
+use tokio::io::DuplexStream;
+use zstacker_test_support::mock_adaptor;
+use zstacker_znp::af::Message;
+use zstacker_znp::coordinator::{Adaptor, CoordinatorHandle};
+use zstacker_znp::start_coordinator;
+use zstacker_znp_protocol::commands::ShortAddr;
+use zstacker_znp_protocol::commands::af::ClusterId;
+
+async fn poll(coordinator: CoordinatorHandle, addr: u16) {
+    for _ in 0..3 {
+        coordinator
+            .send_af(Message {
+                dst_addr: ShortAddr(addr),
+                dst_endpoint: 1,
+                src_endpoint: 1,
+                cluster_id: ClusterId(6),
+                data: vec![0x01, 0x00, 0x02],
+            })
+            .await
+            .unwrap();
+    }
+}
+
+async fn command(coordinator: CoordinatorHandle) {
+    let device = coordinator.device(ShortAddr(4), 1);
+    for _ in 0..3 {
+        device.on_off().toggle().await.unwrap();
+    }
+}
+
+async fn run_test(serial: DuplexStream) {
+    let adaptor = Adaptor::start(serial);
+    let coordinator =
+        start_coordinator(adaptor, Vec::new(), false).await.unwrap();
+    let tasks = [
+        tokio::spawn(poll(coordinator.clone(), 2)),
+        tokio::spawn(poll(coordinator.clone(), 3)),
+        tokio::spawn(command(coordinator.clone())),
+    ];
+    for res in tasks.join().await {
+        res.unwrap();
+    }
+}
+
+#[tokio::test]
+async fn handles_issue_requests_concurrently() {
+    let (b, a) = tokio::io::duplex(4096);
+    (mock_adaptor(a), run_test(b)).race().await;
+}
@@ -0,0 +1,21 @@
+use futures_concurrency::future::Race;
+use tokio::io::DuplexStream;
+use zstacker_test_support::mock_adaptor;
+use zstacker_znp::coordinator::Adaptor;
+use zstacker_znp::start_coordinator;
+use zstacker_znp_protocol::commands::ShortAddr;
+
+async fn run_test(serial: DuplexStream) {
+    let adaptor = Adaptor::start(serial);
+    let coordinator =
+        start_coordinator(adaptor, Vec::new(), false).await.unwrap();
+    let device = coordinator.device(ShortAddr(2), 1);
+    device.on_off().toggle().await.unwrap();
+    device.level_control().stop().await.unwrap();
+}
+
+#[tokio::test]
+async fn cluster_command_awaits_default_response() {
+    let (b, a) = tokio::io::duplex(4096);
+    (mock_adaptor(a), run_test(b)).race().await;
+}
@@ -0,0 +1,70 @@
+use futures_concurrency::future::Race;
+use tokio::io::DuplexStream;
+use zstacker_test_support::mock_adaptor;
+use zstacker_znp::coordinator::Adaptor;
+use zstacker_znp::error::FormError;
+use zstacker_znp::nvram::items::{
+    ChanList, ExtendedPanId, LogicalDeviceType, LogicalType, PanId, PreCfgKey,
+    PreCfgKeysEnable, ZnpHasConfiguredZStack3,
+};
+use zstacker_znp::{NetworkConfig, form_network};
+use zstacker_znp_protocol::commands::Key;
+
+async fn run_test(serial: DuplexStream) {
+    let adaptor = Adaptor::start(serial);
+    let config = NetworkConfig {
+        channel_mask: 1 << 15 | 1 << 20,
+        pan_id: 0x1a62,
+        extended_pan_id: 0xdddd_dddd_dddd_dddd,
+        network_key: Key([3; 16]),
+    };
+    let coordinator = form_network(adaptor, config.clone()).await.unwrap();
+
+    assert_eq!(
+        coordinator.read::<LogicalType>().await.unwrap(),
+        LogicalDeviceType::Coordinator
+    );
+    assert_eq!(coordinator.read::<PanId>().await.unwrap(), config.pan_id);
+    assert_eq!(
+        coordinator.read::<ExtendedPanId>().await.unwrap(),
+        config.extended_pan_id
+    );
+    assert_eq!(
+        coordinator.read::<ChanList>().await.unwrap(),
+        config.channel_mask
+    );
+    assert_eq!(
+        coordinator.read::<PreCfgKey>().await.unwrap(),
+        config.network_key
+    );
+    assert!(coordinator.read::<PreCfgKeysEnable>().await.unwrap());
+    assert_eq!(
+        coordinator.read::<ZnpHasConfiguredZStack3>().await.unwrap(),
+        0x55
+    );
+}
+
+#[tokio::test]
+async fn forms_network_with_config() {
+    let (b, a) = tokio::io::duplex(4096);
+    (mock_adaptor(a), run_test(b)).race().await;
+}
+
+#[tokio::test]
+async fn rejects_channels_zigbee_does_not_use() {
+    for channel_mask in [0, 1 << 10, 1 << 15 | 1 << 27] {
+        // Rejected before anything is send to the adaptor
+        let (b, _a) = tokio::io::duplex(4096);
+        let config = NetworkConfig {
+            channel_mask,
+            pan_id: 0x1a62,
+            extended_pan_id: 0xdddd_dddd_dddd_dddd,
+            network_key: Key([3; 16]),
+        };
+        let res = form_network(Adaptor::start(b), config).await;
+        assert!(matches!(
+            res,
+            Err(FormError::InvalidChannelMask(mask)) if mask == channel_mask
+        ));
+    }
+}
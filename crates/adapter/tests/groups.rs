@@ -0,0 +1,55 @@
+use futures_concurrency::future::Race;
+use tokio::io::DuplexStream;
+use zstacker_test_support::mock_adaptor;
+use zstacker_zcl::clusters::on_off;
+use zstacker_znp::coordinator::Adaptor;
+use zstacker_znp::groups::GroupError;
+use zstacker_znp::start_coordinator;
+use zstacker_znp_protocol::commands::ShortAddr;
+
+const ENDPOINT: u8 = 1;
+
+async fn run_test(serial: DuplexStream) {
+    let adaptor = Adaptor::start(serial);
+    let coordinator =
+        start_coordinator(adaptor, Vec::new(), false).await.unwrap();
+
+    coordinator
+        .add_group(ENDPOINT, 1, "living room")
+        .await
+        .unwrap();
+    coordinator.add_group(ENDPOINT, 2, "kitchen").await.unwrap();
+    coordinator.add_group(242, 3, "").await.unwrap();
+    assert!(matches!(
+        coordinator.add_group(ENDPOINT, 1, "living room").await,
+        Err(GroupError::Failed)
+    ));
+    assert!(matches!(
+        coordinator
+            .add_group(ENDPOINT, 4, "a name that does not fit")
+            .await,
+        Err(GroupError::NameTooLong(24))
+    ));
+    assert_eq!(coordinator.groups(ENDPOINT).await.unwrap(), [1, 2]);
+    assert_eq!(coordinator.group_count().await.unwrap(), 3);
+
+    coordinator.remove_group(ENDPOINT, 1).await.unwrap();
+    assert_eq!(coordinator.groups(ENDPOINT).await.unwrap(), [2]);
+    coordinator.remove_all_groups(ENDPOINT).await.unwrap();
+    assert!(coordinator.groups(ENDPOINT).await.unwrap().is_empty());
+    assert_eq!(coordinator.group_count().await.unwrap(), 1);
+
+    coordinator.group_command(2, &on_off::Toggle).await.unwrap();
+
+    let groups = coordinator.device(ShortAddr(2), 1).groups();
+    groups.add(5, "hallway").await.unwrap();
+    groups.remove(5).await.unwrap();
+    groups.remove_all().await.unwrap();
+    assert_eq!(groups.membership().await.unwrap(), [1, 2]);
+}
+
+#[tokio::test]
+async fn coordinator_and_device_groups() {
+    let (b, a) = tokio::io::duplex(4096);
+    (mock_adaptor(a), run_test(b)).race().await;
+}
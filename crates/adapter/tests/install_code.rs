@@ -0,0 +1,28 @@
+use futures_concurrency::future::Race;
+use tokio::io::DuplexStream;
+use zstacker_test_support::mock_adaptor;
+use zstacker_znp::coordinator::Adaptor;
+use zstacker_znp::install_code::InstallCode;
+use zstacker_znp::start_coordinator;
+
+/// As printed on the QR code of a Zigbee 3.0 device
+const QR_CODE: &str =
+    "Z:00124B0001020304$I:83FED3407A939723A5C639B26916D505C3B5%G$M:ABC";
+
+async fn run_test(serial: DuplexStream) {
+    let adaptor = Adaptor::start(serial);
+    let coordinator =
+        start_coordinator(adaptor, Vec::new(), false).await.unwrap();
+
+    let (ieee_addr, code) = InstallCode::from_qr_code(QR_CODE).unwrap();
+    coordinator
+        .add_install_code(ieee_addr, &code)
+        .await
+        .unwrap();
+}
+
+#[tokio::test]
+async fn install_code_is_registered() {
+    let (b, a) = tokio::io::duplex(4096);
+    (mock_adaptor(a), run_test(b)).race().await;
+}
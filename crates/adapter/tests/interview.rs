@@ -0,0 +1,38 @@
+use futures_concurrency::future::Race;
+use tokio::io::DuplexStream;
+use zstacker_test_support::mock_adaptor;
+use zstacker_znp::coordinator::Adaptor;
+use zstacker_znp::interview::PowerSource;
+use zstacker_znp::nvram::items::LogicalDeviceType;
+use zstacker_znp::start_coordinator;
+use zstacker_znp_protocol::commands::ShortAddr;
+use zstacker_znp_protocol::commands::af::ClusterId;
+
+async fn run_test(serial: DuplexStream) {
+    let adaptor = Adaptor::start(serial);
+    let coordinator =
+        start_coordinator(adaptor, Vec::new(), false).await.unwrap();
+
+    let description = coordinator.interview(ShortAddr(0xa1b2)).await.unwrap();
+    assert_eq!(description.addr, ShortAddr(0xa1b2));
+    assert_eq!(description.manufacturer_code, 0x115f);
+    assert_eq!(description.logical_type, LogicalDeviceType::Router);
+    assert_eq!(description.power_source, PowerSource::Mains);
+
+    let endpoints: Vec<_> = description
+        .endpoints
+        .iter()
+        .map(|descriptor| descriptor.endpoint)
+        .collect();
+    assert_eq!(endpoints, [1, 242]);
+    let light = &description.endpoints[0];
+    assert_eq!(light.profile_id, 0x0104);
+    assert_eq!(light.in_clusters, [ClusterId(0x0000), ClusterId(0x0006)]);
+    assert_eq!(light.out_clusters, [ClusterId(0x0019)]);
+}
+
+#[tokio::test]
+async fn interview_collects_descriptors() {
+    let (b, a) = tokio::io::duplex(4096);
+    (mock_adaptor(a), run_test(b)).race().await;
+}
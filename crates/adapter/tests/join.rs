@@ -0,0 +1,42 @@
+use std::time::Duration;
+
+use futures::StreamExt;
+use futures_concurrency::future::Race;
+use tokio::io::DuplexStream;
+use zstacker_test_support::mock_adaptor;
+use zstacker_znp::coordinator::Adaptor;
+use zstacker_znp::join::DeviceJoined;
+use zstacker_znp::start_coordinator;
+use zstacker_znp_protocol::commands::{IeeeAddr, ShortAddr};
+
+async fn run_test(serial: DuplexStream) {
+    let adaptor = Adaptor::start(serial);
+    let coordinator =
+        start_coordinator(adaptor, Vec::new(), false).await.unwrap();
+
+    let mut joined = coordinator.joined_devices();
+    coordinator
+        .permit_join(Duration::from_secs(600), None)
+        .await
+        .unwrap();
+    assert_eq!(
+        joined.next().await.unwrap().unwrap(),
+        DeviceJoined {
+            ieee_addr: IeeeAddr(0x00124b0001020304),
+            short_addr: ShortAddr(0xa1b2),
+            parent: Some(ShortAddr(0x0e0f)),
+            capabilities: 0x80,
+        }
+    );
+
+    coordinator
+        .permit_join(Duration::ZERO, Some(ShortAddr(0x0e0f)))
+        .await
+        .unwrap();
+}
+
+#[tokio::test]
+async fn device_joins_while_permitted() {
+    let (b, a) = tokio::io::duplex(4096);
+    (mock_adaptor(a), run_test(b)).race().await;
+}
@@ -0,0 +1,39 @@
+use futures_concurrency::future::Race;
+use tokio::io::DuplexStream;
+use zstacker_test_support::mock_adaptor;
+use zstacker_znp::coordinator::Adaptor;
+use zstacker_znp::leave::{Removal, RemoveDeviceError};
+use zstacker_znp::start_coordinator;
+use zstacker_znp_protocol::commands::IeeeAddr;
+
+/// The mock knows this device
+const ON_NETWORK: IeeeAddr = IeeeAddr(0x00124b0001020304);
+/// The mock can not find this device
+const DEAD: IeeeAddr = IeeeAddr(0x00158d0000000001);
+
+async fn run_test(serial: DuplexStream) {
+    let adaptor = Adaptor::start(serial);
+    let coordinator =
+        start_coordinator(adaptor, Vec::new(), false).await.unwrap();
+
+    let removal = coordinator
+        .remove_device(ON_NETWORK, false, true)
+        .await
+        .unwrap();
+    assert_eq!(removal, Removal::Left);
+
+    let err = coordinator
+        .remove_device(DEAD, true, false)
+        .await
+        .unwrap_err();
+    assert!(matches!(err, RemoveDeviceError::Unreachable));
+
+    let removal = coordinator.remove_device(DEAD, false, false).await.unwrap();
+    assert_eq!(removal, Removal::Forced);
+}
+
+#[tokio::test]
+async fn leaves_or_is_forced_out() {
+    let (b, a) = tokio::io::duplex(4096);
+    (mock_adaptor(a), run_test(b)).race().await;
+}
@@ -1,5 +1,5 @@
 use futures_concurrency::future::Race;
-use tokio_serial::SerialStream;
+use tokio::io::DuplexStream;
 use tracing::level_filters::LevelFilter;
 use tracing_subscriber::layer::SubscriberExt;
 use tracing_subscriber::util::SubscriberInitExt;
@@ -8,9 +8,9 @@ use zstacker_test_support::mock_adaptor;
 use zstacker_znp::coordinator::Adaptor;
 use zstacker_znp::start_coordinator;
 
-async fn run_test(serial: SerialStream) {
+async fn run_test(serial: DuplexStream) {
     let adaptor = Adaptor::start(serial);
-    let mut coordinator = start_coordinator(adaptor, Vec::new(), false).await.unwrap();
+    let coordinator = start_coordinator(adaptor, Vec::new(), false).await.unwrap();
     coordinator.list_addresses_on_network().await.unwrap();
 }
 
@@ -25,7 +25,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         .with(fmt::layer())
         .try_init()?;
 
-    let (b, a) = SerialStream::pair().unwrap();
+    let (b, a) = tokio::io::duplex(4096);
     (mock_adaptor(a), run_test(b)).race().await;
 
     Ok(())
@@ -0,0 +1,77 @@
+use futures_concurrency::future::Race;
+use tokio::io::DuplexStream;
+use zstacker_test_support::mock_adaptor;
+use zstacker_znp::coordinator::Adaptor;
+use zstacker_znp::nvram::items::{ChanList, NwkActiveKeyInfo};
+use zstacker_znp::nvram::types::NwkKeyDesc;
+use zstacker_znp::nvram::{ReadError, ids};
+use zstacker_znp::start_coordinator;
+use zstacker_znp_protocol::commands::Key;
+use zstacker_znp_protocol::commands::sys::{ExNvId, NvId, NvSysId};
+
+async fn run_test(serial: DuplexStream) {
+    let adaptor = Adaptor::start(serial);
+    let coordinator =
+        start_coordinator(adaptor, Vec::new(), false).await.unwrap();
+
+    // Longer then one chunk
+    let value: Vec<u8> = (0..=255).chain(0..=255).collect();
+    let id = NvId(0x0f00);
+    coordinator.write_nvram_item(id, &value).await.unwrap();
+    assert_eq!(coordinator.read_nvram_item(id).await.unwrap(), value);
+
+    // A different length recreates the item
+    coordinator.write_nvram_item(id, &[1, 2, 3]).await.unwrap();
+    assert_eq!(coordinator.read_nvram_item(id).await.unwrap(), [1, 2, 3]);
+
+    coordinator.delete_nvram_item(id).await.unwrap();
+    assert!(matches!(
+        coordinator.read_nvram_item(id).await,
+        Err(ReadError::DoesNotExist(_))
+    ));
+
+    coordinator.write::<ChanList>(&(1 << 15)).await.unwrap();
+    assert_eq!(coordinator.read::<ChanList>().await.unwrap(), 1 << 15);
+    let key = NwkKeyDesc {
+        key_seq_num: 1,
+        key: Key([7; 16]),
+    };
+    coordinator.write::<NwkActiveKeyInfo>(&key).await.unwrap();
+    assert_eq!(
+        coordinator
+            .read_nvram_item(ids::NWK_ACTIVE_KEY_INFO)
+            .await
+            .unwrap(),
+        [[1].as_slice(), &[7; 16]].concat()
+    );
+    let read = coordinator.read::<NwkActiveKeyInfo>().await.unwrap();
+    assert_eq!(read.key, key.key);
+
+    // A CC2531 stores the NIB without padding
+    let mut nib = vec![0; 110];
+    nib[22] = 15;
+    nib[33..35].copy_from_slice(&0x1a62u16.to_le_bytes());
+    coordinator.write_nvram_item(ids::NIB, &nib).await.unwrap();
+    let nib = coordinator.read_nib().await.unwrap();
+    assert_eq!(nib.nwk_logical_channel, 15);
+    assert_eq!(nib.nwk_pan_id, 0x1a62);
+
+    let id = ExNvId {
+        sys_id: NvSysId::ZStack,
+        item_id: 0x0004,
+        sub_id: 3,
+    };
+    coordinator.write_ex_nvram_item(id, &value).await.unwrap();
+    assert_eq!(coordinator.read_ex_nvram_item(id).await.unwrap(), value);
+    coordinator.delete_ex_nvram_item(id).await.unwrap();
+    assert!(matches!(
+        coordinator.read_ex_nvram_item(id).await,
+        Err(ReadError::ExDoesNotExist(_))
+    ));
+}
+
+#[tokio::test]
+async fn write_read_and_delete_items() {
+    let (b, a) = tokio::io::duplex(4096);
+    (mock_adaptor(a), run_test(b)).race().await;
+}
@@ -0,0 +1,60 @@
+use std::time::Duration;
+
+use futures::StreamExt;
+use futures_concurrency::future::Race;
+use tokio::io::DuplexStream;
+use zstacker_test_support::mock_adaptor;
+use zstacker_znp::coordinator::Adaptor;
+use zstacker_znp::start_coordinator;
+use zstacker_znp_protocol::commands::{IeeeAddr, ShortAddr};
+
+/// The device the mock announces when joining is permitted
+const IEEE_ADDR: IeeeAddr = IeeeAddr(0x00124b0001020304);
+/// The mock answers lookups with the last two bytes of the IEEE address
+const LOOKED_UP: ShortAddr = ShortAddr(0x0304);
+const ANNOUNCED: ShortAddr = ShortAddr(0xa1b2);
+
+async fn run_test(serial: DuplexStream) {
+    let adaptor = Adaptor::start(serial);
+    let coordinator =
+        start_coordinator(adaptor, Vec::new(), false).await.unwrap();
+
+    assert_eq!(
+        coordinator
+            .resolve_short(coordinator.ieee_addr)
+            .await
+            .unwrap(),
+        coordinator.short_addr
+    );
+    assert_eq!(
+        coordinator.resolve_ieee(LOOKED_UP).await.unwrap(),
+        IEEE_ADDR
+    );
+    assert_eq!(
+        coordinator.resolve_short(IEEE_ADDR).await.unwrap(),
+        LOOKED_UP
+    );
+
+    // The device rejoins and announces its new address
+    let mut joined = coordinator.joined_devices();
+    coordinator
+        .permit_join(Duration::from_secs(60), None)
+        .await
+        .unwrap();
+    joined.next().await.unwrap().unwrap();
+
+    assert_eq!(
+        coordinator.resolve_short(IEEE_ADDR).await.unwrap(),
+        ANNOUNCED
+    );
+    assert_eq!(
+        coordinator.resolve_ieee(ANNOUNCED).await.unwrap(),
+        IEEE_ADDR
+    );
+}
+
+#[tokio::test]
+async fn cache_follows_announcements() {
+    let (b, a) = tokio::io::duplex(4096);
+    (mock_adaptor(a), run_test(b)).race().await;
+}
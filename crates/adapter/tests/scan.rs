@@ -0,0 +1,47 @@
+use futures_concurrency::future::Race;
+use tokio::io::DuplexStream;
+use zstacker_test_support::{MockNetwork, mock_network};
+use zstacker_znp::coordinator::{Adaptor, QueueError};
+use zstacker_znp::start_coordinator;
+use zstacker_znp_protocol::commands::ShortAddr;
+
+const ROUTER: ShortAddr = ShortAddr(2);
+const OFFLINE_ROUTER: ShortAddr = ShortAddr(5);
+const END_DEVICE: ShortAddr = ShortAddr(0x0304);
+
+async fn run_test(serial: DuplexStream) {
+    let adaptor = Adaptor::start(serial);
+    let coordinator =
+        start_coordinator(adaptor, Vec::new(), false).await.unwrap();
+
+    let addresses = coordinator.scan_addresses_on_network(2).await;
+    assert_eq!(
+        addresses.found,
+        [ROUTER, END_DEVICE, OFFLINE_ROUTER].into_iter().collect()
+    );
+    assert_eq!(addresses.unreachable.len(), 1);
+    assert_eq!(addresses.unreachable[0].device, OFFLINE_ROUTER);
+    assert!(matches!(
+        addresses.unreachable[0].cause,
+        QueueError::TimedOut { .. }
+    ));
+
+    let lqi = coordinator.scan_lqi_table(2).await;
+    assert_eq!(lqi.found.len(), 2);
+    assert_eq!(lqi.found[&ROUTER].len(), 3);
+    assert!(lqi.found[&END_DEVICE].is_empty());
+    assert_eq!(lqi.unreachable.len(), 1, "should not ask twice");
+    assert_eq!(lqi.unreachable[0].device, OFFLINE_ROUTER);
+
+    // Without tolerance the offline router ends the scan
+    assert!(coordinator.list_addresses_on_network().await.is_err());
+}
+
+#[tokio::test(start_paused = true)]
+async fn continues_past_offline_router() {
+    let network = MockNetwork {
+        offline_router: true,
+    };
+    let (b, a) = tokio::io::duplex(4096);
+    (mock_network(a, network), run_test(b)).race().await;
+}
@@ -0,0 +1,116 @@
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, SystemTime};
+
+use futures_concurrency::future::Race;
+use tokio::io::DuplexStream;
+use zstacker_test_support::mock_adaptor;
+use zstacker_znp::af::Message;
+use zstacker_znp::coordinator::Adaptor;
+use zstacker_znp::start_coordinator;
+use zstacker_znp::store::{
+    DeviceStore, JsonFileStore, KnownDevice, MemoryStore,
+};
+use zstacker_znp_protocol::commands::af::ClusterId;
+use zstacker_znp_protocol::commands::{IeeeAddr, ShortAddr};
+
+/// Joined by the mock once joining is permitted
+const IEEE_ADDR: IeeeAddr = IeeeAddr(0x00124b0001020304);
+const SHORT_ADDR: ShortAddr = ShortAddr(0xa1b2);
+
+async fn wait_for(
+    store: &Mutex<MemoryStore>,
+    done: impl Fn(&KnownDevice) -> bool,
+) -> KnownDevice {
+    loop {
+        let device = store.lock().unwrap().get(IEEE_ADDR);
+        match device {
+            Some(device) if done(&device) => return device,
+            _ => tokio::time::sleep(Duration::from_millis(10)).await,
+        }
+    }
+}
+
+async fn run_test(serial: DuplexStream) {
+    let adaptor = Adaptor::start(serial);
+    let coordinator =
+        start_coordinator(adaptor, Vec::new(), false).await.unwrap();
+
+    let store = Arc::new(Mutex::new(MemoryStore::default()));
+    let tracking = coordinator.track_devices(Arc::clone(&store));
+
+    coordinator
+        .permit_join(Duration::from_secs(60), None)
+        .await
+        .unwrap();
+    let device = wait_for(&store, |device| device.last_seen.is_some()).await;
+    assert_eq!(device.short_addr, Some(SHORT_ADDR));
+    assert_eq!(device.lqi, None);
+
+    // The mock answers with a default response
+    coordinator
+        .send_af(Message {
+            dst_addr: SHORT_ADDR,
+            dst_endpoint: 1,
+            src_endpoint: 1,
+            cluster_id: ClusterId(6),
+            data: vec![0x01, 0x00, 0x02],
+        })
+        .await
+        .unwrap();
+    let device = wait_for(&store, |device| device.lqi.is_some()).await;
+    assert_eq!(device.lqi, Some(255));
+
+    tracking.abort();
+}
+
+#[tokio::test]
+async fn store_tracks_joined_device() {
+    let (b, a) = tokio::io::duplex(4096);
+    (mock_adaptor(a), run_test(b)).race().await;
+}
+
+#[test]
+fn json_store_survives_reopening() {
+    let path = std::env::temp_dir()
+        .join(format!("zstacker-devices-{}.json", std::process::id()));
+    let _ = std::fs::remove_file(&path);
+
+    let mut store = JsonFileStore::open(&path).unwrap();
+    store.record_address(IEEE_ADDR, SHORT_ADDR).unwrap();
+    store.record_address(IeeeAddr(2), ShortAddr(2)).unwrap();
+    store.remove(IeeeAddr(2)).unwrap();
+    // The device rejoined with a new address
+    store.record_address(IEEE_ADDR, ShortAddr(0x1234)).unwrap();
+
+    let reopened = JsonFileStore::open(&path).unwrap();
+    assert_eq!(reopened.devices(), store.devices());
+    assert_eq!(
+        reopened.find_by_short(ShortAddr(0x1234)),
+        Some(KnownDevice::new(IEEE_ADDR, ShortAddr(0x1234)))
+    );
+
+    // Being seen alone is not written right away
+    store
+        .record_seen(ShortAddr(0x1234), Some(80), SystemTime::now())
+        .unwrap();
+    let reopened = JsonFileStore::open(&path).unwrap();
+    assert_eq!(reopened.get(IEEE_ADDR).unwrap().lqi, None);
+    store.flush().unwrap();
+    let reopened = JsonFileStore::open(&path).unwrap();
+    assert_eq!(reopened.get(IEEE_ADDR).unwrap().lqi, Some(80));
+    std::fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn device_that_lost_its_address_is_kept() {
+    let mut store = MemoryStore::default();
+    store.record_address(IEEE_ADDR, SHORT_ADDR).unwrap();
+    store.record_address(IeeeAddr(2), SHORT_ADDR).unwrap();
+
+    assert_eq!(store.devices().len(), 2);
+    assert_eq!(store.get(IEEE_ADDR).unwrap().short_addr, None);
+    assert_eq!(
+        store.find_by_short(SHORT_ADDR).unwrap().ieee_addr,
+        IeeeAddr(2)
+    );
+}
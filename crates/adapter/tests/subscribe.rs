@@ -0,0 +1,36 @@
+use std::time::Duration;
+
+use futures::StreamExt;
+use tokio::io::AsyncWriteExt;
+use zstacker_znp::coordinator::Adaptor;
+use zstacker_znp_protocol::commands::zdo::StateChangeInd;
+use zstacker_znp_protocol::commands::{AsyncNotify, DeviceState, to_frame};
+use zstacker_znp_protocol::data_format;
+
+#[tokio::test]
+async fn unsolicited_notification_reaches_subscriber() {
+    let (mut device, host) = tokio::io::duplex(4096);
+    let adaptor = Adaptor::start(host);
+    let mut state_changes = adaptor.subscribe::<StateChangeInd>();
+
+    let notification = StateChangeInd {
+        state: DeviceState::StartedAsZBCoordinator,
+    };
+    let frame = to_frame(
+        data_format::to_vec(&notification).unwrap(),
+        StateChangeInd::META,
+    )
+    .unwrap();
+    device.write_all(&frame).await.unwrap();
+
+    let received =
+        tokio::time::timeout(Duration::from_secs(1), state_changes.next())
+            .await
+            .expect("notification should arrive")
+            .expect("stream should not end")
+            .unwrap();
+    assert!(matches!(
+        received.state,
+        DeviceState::StartedAsZBCoordinator
+    ));
+}
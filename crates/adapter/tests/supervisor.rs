@@ -0,0 +1,182 @@
+use std::io;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+use std::time::Duration;
+
+use futures::StreamExt;
+use tokio::io::{AsyncRead, AsyncWrite, DuplexStream, ReadBuf};
+use tokio::sync::mpsc;
+use zstacker_test_support::mock_adaptor;
+use zstacker_znp::af::Message;
+use zstacker_znp::coordinator::Adaptor;
+use zstacker_znp::start_coordinator;
+use zstacker_znp::supervisor::{ConnectionState, SupervisorConfig};
+use zstacker_znp_protocol::commands::ShortAddr;
+use zstacker_znp_protocol::commands::af::ClusterId;
+use zstacker_znp_protocol::commands::sys::ResetInd;
+
+#[tokio::test]
+async fn reconnects_after_losing_the_adaptor() {
+    let (b, a) = tokio::io::duplex(4096);
+    let unplugged = tokio::spawn(mock_adaptor(a));
+    let coordinator = start_coordinator(Adaptor::start(b), Vec::new(), false)
+        .await
+        .unwrap();
+
+    let mut resets = coordinator.subscribe::<ResetInd>();
+    let mut states = coordinator.connection_state();
+    assert!(matches!(
+        states.next().await,
+        Some(ConnectionState::Connected)
+    ));
+
+    coordinator.supervise(
+        || async {
+            let (b, a) = tokio::io::duplex(4096);
+            tokio::spawn(mock_adaptor(a));
+            Ok(b)
+        },
+        SupervisorConfig::default(),
+    );
+    unplugged.abort();
+
+    let reconnected = async {
+        while let Some(state) = states.next().await {
+            if matches!(state, ConnectionState::Connected) {
+                return;
+            }
+        }
+        panic!("connection state stream ended");
+    };
+    tokio::time::timeout(Duration::from_secs(5), reconnected)
+        .await
+        .unwrap();
+
+    // The startup sequence resets the adaptor again
+    resets.next().await.unwrap().unwrap();
+    coordinator
+        .send_af(Message {
+            dst_addr: ShortAddr(2),
+            dst_endpoint: 1,
+            src_endpoint: 1,
+            cluster_id: ClusterId(6),
+            data: vec![0x01, 0x00, 0x02],
+        })
+        .await
+        .unwrap();
+}
+
+#[tokio::test(start_paused = true)]
+async fn stops_once_the_coordinator_is_dropped() {
+    let (b, a) = tokio::io::duplex(4096);
+    let unplugged = tokio::spawn(mock_adaptor(a));
+    let coordinator = start_coordinator(Adaptor::start(b), Vec::new(), false)
+        .await
+        .unwrap();
+
+    let mut states = coordinator.connection_state();
+    let supervisor = coordinator.supervise(
+        || async { Err::<DuplexStream, _>(io::ErrorKind::NotFound.into()) },
+        SupervisorConfig::default(),
+    );
+    unplugged.abort();
+    while let Some(state) = states.next().await {
+        if matches!(state, ConnectionState::Reconnecting { attempt: 3 }) {
+            break;
+        }
+    }
+
+    drop(coordinator);
+    tokio::time::timeout(Duration::from_secs(60), supervisor)
+        .await
+        .expect("supervisor should stop retrying")
+        .unwrap();
+}
+
+/// Transport that lets the test see whether it still exists
+#[derive(Debug)]
+struct Tracked {
+    inner: DuplexStream,
+    _alive: Arc<()>,
+}
+
+impl AsyncRead for Tracked {
+    fn poll_read(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut ReadBuf<'_>,
+    ) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.inner).poll_read(cx, buf)
+    }
+}
+
+impl AsyncWrite for Tracked {
+    fn poll_write(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        Pin::new(&mut self.inner).poll_write(cx, buf)
+    }
+
+    fn poll_flush(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.inner).poll_flush(cx)
+    }
+
+    fn poll_shutdown(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.inner).poll_shutdown(cx)
+    }
+}
+
+/// A serial port can not be opened while the previous handle to it is
+/// still around, whether the io task holds on to it or not.
+async fn previous_transport_is_dropped_before_reopen(reuse_transport: bool) {
+    let (b, a) = tokio::io::duplex(4096);
+    let unplugged = tokio::spawn(mock_adaptor(a));
+    let alive = Arc::new(());
+    let transport = Tracked {
+        inner: b,
+        _alive: Arc::clone(&alive),
+    };
+    let coordinator =
+        start_coordinator(Adaptor::start(transport), Vec::new(), false)
+            .await
+            .unwrap();
+
+    let previous = Arc::downgrade(&alive);
+    drop(alive);
+    let (reopened_tx, mut reopened) = mpsc::unbounded_channel();
+    let supervisor = coordinator.supervise(
+        move || {
+            let _ = reopened_tx.send(previous.strong_count());
+            async { Err::<DuplexStream, _>(io::ErrorKind::NotFound.into()) }
+        },
+        SupervisorConfig {
+            reuse_transport,
+            ..SupervisorConfig::default()
+        },
+    );
+    unplugged.abort();
+
+    for _ in 0..2 {
+        assert_eq!(reopened.recv().await, Some(0));
+    }
+    supervisor.abort();
+}
+
+#[tokio::test(start_paused = true)]
+async fn reopens_after_dropping_the_failed_transport() {
+    previous_transport_is_dropped_before_reopen(false).await;
+}
+
+#[tokio::test(start_paused = true)]
+async fn reopens_after_dropping_the_reused_transport() {
+    previous_transport_is_dropped_before_reopen(true).await;
+}
@@ -0,0 +1,21 @@
+use futures_concurrency::future::Race;
+use tokio::net::TcpListener;
+use zstacker_test_support::mock_adaptor;
+use zstacker_znp::coordinator::Adaptor;
+use zstacker_znp::start_coordinator;
+
+#[tokio::test]
+async fn start_coordinator_over_tcp() {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+
+    let network_attached_adaptor = async {
+        let (stream, _) = listener.accept().await.unwrap();
+        mock_adaptor(stream).await;
+    };
+    let run_test = async {
+        let adaptor = Adaptor::connect_tcp(addr).await.unwrap();
+        start_coordinator(adaptor, Vec::new(), false).await.unwrap();
+    };
+    (network_attached_adaptor, run_test).race().await;
+}
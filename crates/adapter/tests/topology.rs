@@ -0,0 +1,95 @@
+use futures_concurrency::future::Race;
+use tokio::io::DuplexStream;
+use zstacker_test_support::{MockNetwork, mock_adaptor, mock_network};
+use zstacker_znp::coordinator::Adaptor;
+use zstacker_znp::start_coordinator;
+use zstacker_znp::topology::Relationship;
+use zstacker_znp_protocol::commands::{DeviceType, ShortAddr};
+
+const ROUTER: ShortAddr = ShortAddr(2);
+const END_DEVICE: ShortAddr = ShortAddr(0x0304);
+const OFFLINE_ROUTER: ShortAddr = ShortAddr(5);
+
+async fn run_test(serial: DuplexStream) {
+    let adaptor = Adaptor::start(serial);
+    let coordinator =
+        start_coordinator(adaptor, Vec::new(), false).await.unwrap();
+
+    let scan = coordinator.topology(2).await;
+    assert!(scan.unreachable.is_empty());
+    let topology = scan.found;
+    assert_eq!(topology.nodes.len(), 3);
+    assert!(matches!(
+        topology.node(END_DEVICE).unwrap().device_type,
+        DeviceType::EndDevice
+    ));
+
+    // The coordinator and the router report each other
+    assert_eq!(topology.links.len(), 3);
+    let to_coordinator = topology
+        .links
+        .iter()
+        .find(|link| link.target.short_addr == coordinator.short_addr)
+        .unwrap();
+    assert_eq!(to_coordinator.source.short_addr, ROUTER);
+    assert_eq!(to_coordinator.relationship, Relationship::Child);
+    assert_eq!(to_coordinator.lqi, 200);
+    assert_eq!(to_coordinator.routes, [ROUTER, END_DEVICE]);
+    let to_end_device = topology
+        .links
+        .iter()
+        .find(|link| link.source.short_addr == END_DEVICE)
+        .unwrap();
+    assert_eq!(to_end_device.target.short_addr, ROUTER);
+    assert_eq!(to_end_device.depth, 2);
+
+    let json: serde_json::Value =
+        serde_json::from_str(&topology.to_json()).unwrap();
+    assert_eq!(json["nodes"][0]["ieeeAddr"], "0x000000000000002a");
+    assert_eq!(json["nodes"][0]["type"], "Coordinator");
+    assert_eq!(json["links"][0]["linkquality"], 200);
+    assert_eq!(json["links"][0]["source"]["networkAddress"], 2);
+    assert_eq!(json["links"][0]["relationship"], 1);
+
+    let dot = topology.to_dot();
+    assert!(dot.starts_with("digraph G {"));
+    assert!(dot.contains(
+        "\"0x00124b0001020304\" -> \"0x00124b0001020002\" \
+        [label=\"120\", style=\"solid,bold\"];"
+    ));
+}
+
+#[tokio::test]
+async fn crawl_and_export() {
+    let (b, a) = tokio::io::duplex(4096);
+    (mock_adaptor(a), run_test(b)).race().await;
+}
+
+async fn run_offline_test(serial: DuplexStream) {
+    let adaptor = Adaptor::start(serial);
+    let coordinator =
+        start_coordinator(adaptor, Vec::new(), false).await.unwrap();
+
+    let scan = coordinator.topology(2).await;
+    assert_eq!(scan.unreachable.len(), 1);
+    assert_eq!(scan.unreachable[0].device, OFFLINE_ROUTER);
+    // Known from the neighbor table of the router next to it
+    let topology = scan.found;
+    assert!(topology.node(OFFLINE_ROUTER).is_some());
+    assert_eq!(topology.links.len(), 4);
+    assert!(
+        topology
+            .links
+            .iter()
+            .all(|link| link.target.short_addr != OFFLINE_ROUTER)
+    );
+}
+
+#[tokio::test(start_paused = true)]
+async fn crawl_continues_past_offline_router() {
+    let network = MockNetwork {
+        offline_router: true,
+    };
+    let (b, a) = tokio::io::duplex(4096);
+    (mock_network(a, network), run_offline_test(b)).race().await;
+}
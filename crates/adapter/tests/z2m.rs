@@ -0,0 +1,92 @@
+#![cfg(feature = "z2m")]
+
+use std::fs;
+use std::path::PathBuf;
+
+use zstacker_znp::interview::PowerSource;
+use zstacker_znp::nvram::items::LogicalDeviceType;
+use zstacker_znp::store::{DeviceStore, MemoryStore};
+use zstacker_znp::z2m;
+use zstacker_znp_protocol::commands::af::ClusterId;
+use zstacker_znp_protocol::commands::{IeeeAddr, ShortAddr};
+
+/// Shortened from a zigbee2mqtt 1.x install
+const DATABASE: &str = r#"{"id":1,"type":"Coordinator","ieeeAddr":"0x00124b0024c2a1b3","nwkAddr":0,"manufId":0,"epList":[1,242],"endpoints":{"1":{"profId":260,"epId":1,"devId":5,"inClusterList":[],"outClusterList":[],"clusters":{},"binds":[],"configuredReportings":[],"meta":{}}},"interviewCompleted":false,"meta":{}}
+{"id":2,"type":"Router","ieeeAddr":"0x00158d0001020304","nwkAddr":12345,"manufId":4447,"manufName":"LUMI","powerSource":"Mains (single phase)","modelId":"lumi.plug","epList":[1,2],"endpoints":{"2":{"profId":260,"epId":2,"devId":81,"inClusterList":[12],"outClusterList":[],"clusters":{},"binds":[],"configuredReportings":[],"meta":{}},"1":{"profId":260,"epId":1,"devId":81,"inClusterList":[0,4,3,6,16,5,10,1,2],"outClusterList":[25,10],"clusters":{},"binds":[],"configuredReportings":[],"meta":{}}},"appVersion":31,"interviewCompleted":true,"meta":{},"lastSeen":1700000000000}
+{"id":3,"type":"EndDevice","ieeeAddr":"0x00158d0005060708","nwkAddr":4660,"manufId":4151,"manufName":"LUMI","powerSource":"Battery","modelId":"lumi.sensor_magnet.aq2","epList":[1],"endpoints":{"1":{"profId":260,"epId":1,"devId":24321,"inClusterList":[0,3,65535,6],"outClusterList":[0,4,65535],"clusters":{},"binds":[],"configuredReportings":[],"meta":{}}},"interviewCompleted":false,"meta":{}}
+{"id":4,"type":"Group","groupID":1,"members":[{"deviceID":2,"endpointID":1}],"meta":{}}
+"#;
+
+const CONFIGURATION: &str = r#"homeassistant: false
+mqtt:
+  base_topic: zigbee2mqtt
+  server: mqtt://localhost
+serial:
+  port: /dev/ttyUSB0
+devices:
+  '0x00158d0001020304':
+    friendly_name: kitchen/plug
+    retain: false
+  '0x00158d0005060708':
+    friendly_name: front_door
+"#;
+
+fn data_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir()
+        .join(format!("zstacker-z2m-{name}-{}", std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+#[test]
+fn imports_paired_devices() {
+    let dir = data_dir("inline");
+    fs::write(dir.join(z2m::DATABASE), DATABASE).unwrap();
+    fs::write(dir.join(z2m::CONFIGURATION), CONFIGURATION).unwrap();
+
+    let mut store = MemoryStore::default();
+    for device in z2m::import(&dir).unwrap() {
+        store.insert(device).unwrap();
+    }
+    assert_eq!(store.devices().len(), 2);
+
+    let plug = store.find_by_name("kitchen/plug").unwrap();
+    assert_eq!(plug.ieee_addr, IeeeAddr(0x00158d0001020304));
+    assert_eq!(plug.short_addr, Some(ShortAddr(12345)));
+    assert_eq!(plug.model_id.as_deref(), Some("lumi.plug"));
+    let description = plug.description.unwrap();
+    assert_eq!(description.manufacturer_code, 4447);
+    assert_eq!(description.logical_type, LogicalDeviceType::Router);
+    assert_eq!(description.power_source, PowerSource::Mains);
+    assert_eq!(description.mac_capabilities, None);
+    let endpoints: Vec<_> =
+        description.endpoints.iter().map(|ep| ep.endpoint).collect();
+    assert_eq!(endpoints, [1, 2]);
+    assert_eq!(description.endpoints[1].in_clusters, [ClusterId(12)]);
+
+    // Not interviewed completely, zigbee2mqtt may have missed endpoints
+    let sensor = store.find_by_short(ShortAddr(4660)).unwrap();
+    assert_eq!(sensor.friendly_name.as_deref(), Some("front_door"));
+    assert!(sensor.description.is_none());
+
+    fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn reads_names_from_separate_devices_file() {
+    let dir = data_dir("file");
+    let (main, devices) = CONFIGURATION.split_once("devices:\n").unwrap();
+    fs::write(
+        dir.join(z2m::CONFIGURATION),
+        format!("{main}devices: devices.yaml\n"),
+    )
+    .unwrap();
+    let devices = devices.replace("\n  ", "\n").replacen("  ", "", 1);
+    fs::write(dir.join("devices.yaml"), devices).unwrap();
+
+    let names = z2m::read_friendly_names(dir.join(z2m::CONFIGURATION)).unwrap();
+    assert_eq!(names[&IeeeAddr(0x00158d0005060708)], "front_door");
+    assert_eq!(names.len(), 2);
+
+    fs::remove_dir_all(dir).unwrap();
+}
@@ -0,0 +1,94 @@
+//! Group membership of the endpoints on the mock adaptor, starts out empty
+
+use zstacker_znp_protocol::commands::zdo::{
+    ExtCountAllGroupsReply, ExtFindAllGroupsEndpointReply,
+};
+use zstacker_znp_protocol::commands::{
+    BasicStatus, CommandType, SubSystem, to_frame,
+};
+use zstacker_znp_protocol::data_format;
+use zstacker_znp_protocol::framing::CommandMeta;
+
+/// Zdo subsystem ids of the commands handled here
+mod id {
+    pub const EXT_REMOVE_GROUP: u8 = 71;
+    pub const EXT_REMOVE_ALL_GROUP: u8 = 72;
+    pub const EXT_FIND_ALL_GROUPS_ENDPOINT: u8 = 73;
+    pub const EXT_ADD_GROUP: u8 = 75;
+    pub const EXT_COUNT_ALL_GROUPS: u8 = 76;
+}
+
+#[derive(Debug, Default)]
+pub(crate) struct Groups {
+    /// Endpoint and group id
+    members: Vec<(u8, u16)>,
+}
+
+impl Groups {
+    /// The reply to the request if it is a group request
+    pub(crate) fn handle(
+        &mut self,
+        meta: &CommandMeta,
+        data: &[u8],
+    ) -> Option<Vec<u8>> {
+        if meta.ty != CommandType::SREQ || meta.sub_system != SubSystem::Zdo {
+            return None;
+        }
+        let reply_meta = CommandMeta {
+            ty: CommandType::SRSP,
+            ..meta.clone()
+        };
+        let reply = match meta.id {
+            id::EXT_ADD_GROUP => {
+                let member = (data[0], u16_at(data, 1));
+                let added = !self.members.contains(&member);
+                if added {
+                    self.members.push(member);
+                }
+                status(added)
+            }
+            id::EXT_REMOVE_GROUP => {
+                let member = (data[0], u16_at(data, 1));
+                let before = self.members.len();
+                self.members.retain(|m| *m != member);
+                status(self.members.len() != before)
+            }
+            id::EXT_REMOVE_ALL_GROUP => {
+                self.members.retain(|(endpoint, _)| *endpoint != data[0]);
+                status(true)
+            }
+            id::EXT_FIND_ALL_GROUPS_ENDPOINT => {
+                data_format::to_vec(&ExtFindAllGroupsEndpointReply {
+                    group_list: self
+                        .members
+                        .iter()
+                        .filter(|(endpoint, _)| *endpoint == data[0])
+                        .map(|(_, group_id)| *group_id)
+                        .collect(),
+                })
+                .unwrap()
+            }
+            id::EXT_COUNT_ALL_GROUPS => {
+                data_format::to_vec(&ExtCountAllGroupsReply {
+                    count: self.members.len() as u8,
+                })
+                .unwrap()
+            }
+            _ => return None,
+        };
+        Some(to_frame(reply, reply_meta).unwrap())
+    }
+}
+
+fn u16_at(data: &[u8], idx: usize) -> u16 {
+    u16::from_le_bytes([data[idx], data[idx + 1]])
+}
+
+fn status(ok: bool) -> Vec<u8> {
+    let status = if ok {
+        BasicStatus::Ok
+    } else {
+        BasicStatus::Err
+    };
+    data_format::to_vec(&status).unwrap()
+}
@@ -1,14 +1,35 @@
 use std::time::Duration;
 
-use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt};
 use tokio::time::sleep;
-use tokio_serial::SerialStream;
 use zstacker_znp_protocol::commands::START_OF_FRAME;
 use zstacker_znp_protocol::framing::CommandMeta;
 
+mod groups;
+mod nvram;
 pub mod responses;
 
-pub async fn mock_adaptor(mut serial: SerialStream) {
+/// Variations on the network the mock plays, see [`responses::lqi`] for
+/// the devices that are always there.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct MockNetwork {
+    /// Adds a router at 5 next to the router at 2. It is offline, requests
+    /// to it are accepted but never answered.
+    pub offline_router: bool,
+}
+
+/// Plays the part of the adaptor on the other end of `serial`
+pub async fn mock_adaptor(serial: impl AsyncRead + AsyncWrite + Unpin) {
+    mock_network(serial, MockNetwork::default()).await
+}
+
+/// Like [`mock_adaptor`] with a variation on the network
+pub async fn mock_network(
+    mut serial: impl AsyncRead + AsyncWrite + Unpin,
+    network: MockNetwork,
+) {
+    let mut nvram = nvram::Nvram::default();
+    let mut groups = groups::Groups::default();
     loop {
         let mut buf = [0u8; 4];
         serial.read_exact(&mut buf).await.unwrap();
@@ -19,6 +40,15 @@ pub async fn mock_adaptor(mut serial: SerialStream) {
         let mut data = vec![0u8; data_length as usize + 1];
         serial.read_exact(&mut data).await.unwrap();
 
+        if let Some(reply) = nvram.handle(&meta, &data) {
+            serial.write_all(&reply).await.unwrap();
+            continue;
+        }
+        if let Some(reply) = groups.handle(&meta, &data) {
+            serial.write_all(&reply).await.unwrap();
+            continue;
+        }
+
         match meta {
             responses::RESET => {
                 serial.write_all(&responses::reset()).await.unwrap();
@@ -29,6 +59,160 @@ pub async fn mock_adaptor(mut serial: SerialStream) {
             responses::SYS_VERSION => {
                 serial.write_all(&responses::sys_version()).await.unwrap();
             }
+            responses::SET_TX_POWER => {
+                serial.write_all(&responses::set_tx_power()).await.unwrap();
+            }
+            responses::NWK_ADDR_REQ => {
+                let ieee_addr = u64::from_le_bytes(
+                    data[0..8].try_into().expect("data should be longer the 8"),
+                );
+                serial
+                    .write_all(&responses::descriptor_status(meta))
+                    .await
+                    .unwrap();
+                serial
+                    .write_all(&responses::nwk_addr(ieee_addr))
+                    .await
+                    .unwrap();
+            }
+            responses::IEEE_ADDR_REQ => {
+                let short_addr = u16::from_le_bytes([data[0], data[1]]);
+                serial
+                    .write_all(&responses::descriptor_status(meta))
+                    .await
+                    .unwrap();
+                serial
+                    .write_all(&responses::ieee_addr(short_addr))
+                    .await
+                    .unwrap();
+            }
+            responses::BIND_REQ | responses::UNBIND_REQ => {
+                let dst_addr = u16::from_le_bytes([data[0], data[1]]);
+                let reply = responses::bind(&meta, dst_addr);
+                serial
+                    .write_all(&responses::descriptor_status(meta))
+                    .await
+                    .unwrap();
+                serial.write_all(&reply).await.unwrap();
+            }
+            responses::MGMT_BIND_REQ => {
+                let (dst_addr, start_index) =
+                    (u16::from_le_bytes([data[0], data[1]]), data[2]);
+                serial
+                    .write_all(&responses::descriptor_status(meta))
+                    .await
+                    .unwrap();
+                serial
+                    .write_all(&responses::binding_table(dst_addr, start_index))
+                    .await
+                    .unwrap();
+            }
+            responses::MGMT_LEAVE_REQ => {
+                let dst_addr = u16::from_le_bytes([data[0], data[1]]);
+                serial
+                    .write_all(&responses::descriptor_status(meta))
+                    .await
+                    .unwrap();
+                serial
+                    .write_all(&responses::mgmt_leave(dst_addr))
+                    .await
+                    .unwrap();
+            }
+            responses::SEC_DEVICE_REMOVE => {
+                serial
+                    .write_all(&responses::sec_device_remove())
+                    .await
+                    .unwrap();
+            }
+            responses::NODE_DESC_REQ
+            | responses::POWER_DESC_REQ
+            | responses::ACTIVE_EP_REQ
+            | responses::SIMPLE_DESC_REQ => {
+                let addr = u16::from_le_bytes([data[0], data[1]]);
+                let reply = match meta {
+                    responses::NODE_DESC_REQ => responses::node_desc(addr),
+                    responses::POWER_DESC_REQ => responses::power_desc(addr),
+                    responses::ACTIVE_EP_REQ => responses::active_ep(addr),
+                    _ => responses::simple_desc(addr, data[4]),
+                };
+                serial
+                    .write_all(&responses::descriptor_status(meta))
+                    .await
+                    .unwrap();
+                serial.write_all(&reply).await.unwrap();
+            }
+            responses::PERMIT_JOIN_REQ => {
+                let (addr_mode, duration) = (data[0], data[3]);
+                let dst_addr = u16::from_le_bytes([data[1], data[2]]);
+                serial
+                    .write_all(&responses::permit_join_status())
+                    .await
+                    .unwrap();
+                serial
+                    .write_all(&responses::permit_join_ind(duration))
+                    .await
+                    .unwrap();
+                serial
+                    .write_all(&responses::permit_join(addr_mode, dst_addr))
+                    .await
+                    .unwrap();
+                if duration > 0 {
+                    for notification in responses::device_joined() {
+                        serial.write_all(&notification).await.unwrap();
+                    }
+                }
+            }
+            responses::ADD_INSTALL_CODE => {
+                serial
+                    .write_all(&responses::add_install_code())
+                    .await
+                    .unwrap();
+            }
+            responses::BDB_SET_CHANNEL => {
+                serial
+                    .write_all(&responses::bdb_set_channel())
+                    .await
+                    .unwrap();
+            }
+            responses::BDB_START_COMMISSIONING => {
+                serial
+                    .write_all(&responses::bdb_start_commissioning())
+                    .await
+                    .unwrap();
+                for notification in
+                    responses::bdb_commissioning_notifications(data[0])
+                {
+                    serial.write_all(&notification).await.unwrap();
+                }
+            }
+            responses::AF_REGISTER => {
+                serial.write_all(&responses::af_register()).await.unwrap();
+            }
+            responses::AF_DATA_REQUEST => {
+                let (src_endpoint, trans_id) = (data[3], data[6]);
+                serial
+                    .write_all(&responses::data_request_status())
+                    .await
+                    .unwrap();
+                serial
+                    .write_all(&responses::data_confirm(src_endpoint, trans_id))
+                    .await
+                    .unwrap();
+                if let Some(reply) = responses::zcl_response(&data) {
+                    serial.write_all(&reply).await.unwrap();
+                }
+            }
+            responses::AF_DATA_REQUEST_EXT => {
+                let (src_endpoint, trans_id) = (data[12], data[15]);
+                serial
+                    .write_all(&responses::descriptor_status(meta))
+                    .await
+                    .unwrap();
+                serial
+                    .write_all(&responses::data_confirm(src_endpoint, trans_id))
+                    .await
+                    .unwrap();
+            }
             responses::EXT_FIND_GROUP => {
                 serial.write_all(&responses::find_group()).await.unwrap();
             }
@@ -37,16 +221,38 @@ pub async fn mock_adaptor(mut serial: SerialStream) {
                     data[0..2].try_into().expect("data should be longer the 2"),
                 );
                 serial.write_all(&responses::lqi_status()).await.unwrap();
+                if network.offline_router
+                    && dst_addr == responses::OFFLINE_ROUTER
+                {
+                    continue;
+                }
                 sleep(Duration::from_millis(300)).await;
-                serial.write_all(&responses::lqi(dst_addr)).await.unwrap();
+                serial
+                    .write_all(&responses::lqi(
+                        dst_addr,
+                        network.offline_router,
+                    ))
+                    .await
+                    .unwrap();
             }
             responses::RTG_REQ => {
                 let dst_addr = u16::from_le_bytes(
                     data[0..2].try_into().expect("data should be longer the 2"),
                 );
                 serial.write_all(&responses::rtg_status()).await.unwrap();
+                if network.offline_router
+                    && dst_addr == responses::OFFLINE_ROUTER
+                {
+                    continue;
+                }
                 sleep(Duration::from_millis(300)).await;
-                serial.write_all(&responses::routing_table(dst_addr)).await.unwrap();
+                serial
+                    .write_all(&responses::routing_table(
+                        dst_addr,
+                        network.offline_router,
+                    ))
+                    .await
+                    .unwrap();
             }
             CommandMeta { .. } => {
                 panic!("mock can not handle command type: {meta:?}")
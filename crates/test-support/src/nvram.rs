@@ -0,0 +1,143 @@
+//! Non volatile memory of the mock adaptor, starts out empty
+
+use std::collections::HashMap;
+
+use zstacker_znp_protocol::commands::sys::{
+    NvLengthReply, NvReadReply, NvStatus, OsalNvReadExtReply,
+};
+use zstacker_znp_protocol::commands::{
+    BasicStatus, CommandType, SubSystem, to_frame,
+};
+use zstacker_znp_protocol::data_format;
+use zstacker_znp_protocol::framing::CommandMeta;
+
+/// Sys subsystem ids of the commands handled here
+mod id {
+    pub const ITEM_INIT: u8 = 7;
+    pub const DELETE: u8 = 18;
+    pub const LENGTH: u8 = 19;
+    pub const READ_EXT: u8 = 28;
+    pub const WRITE_EXT: u8 = 29;
+    pub const EX_CREATE: u8 = 48;
+    pub const EX_DELETE: u8 = 49;
+    pub const EX_LENGTH: u8 = 50;
+    pub const EX_READ: u8 = 51;
+    pub const EX_WRITE: u8 = 52;
+}
+
+#[derive(Debug, Default)]
+pub(crate) struct Nvram {
+    items: HashMap<u16, Vec<u8>>,
+    /// Keyed by sys id, item id and sub id as send
+    ex_items: HashMap<[u8; 5], Vec<u8>>,
+}
+
+impl Nvram {
+    /// The reply to the request if it is an nvram request
+    pub(crate) fn handle(
+        &mut self,
+        meta: &CommandMeta,
+        data: &[u8],
+    ) -> Option<Vec<u8>> {
+        if meta.ty != CommandType::SREQ || meta.sub_system != SubSystem::Sys {
+            return None;
+        }
+        let reply_meta = CommandMeta {
+            ty: CommandType::SRSP,
+            ..meta.clone()
+        };
+        let reply = match meta.id {
+            id::ITEM_INIT => {
+                let len = u16_at(data, 2);
+                let created = !self.items.contains_key(&u16_at(data, 0));
+                self.items
+                    .entry(u16_at(data, 0))
+                    .or_insert_with(|| vec![0; len.into()]);
+                status(if created {
+                    NvStatus::ItemUninit
+                } else {
+                    NvStatus::Success
+                })
+            }
+            id::DELETE => status(match self.items.remove(&u16_at(data, 0)) {
+                Some(_) => NvStatus::Success,
+                None => NvStatus::ItemUninit,
+            }),
+            id::LENGTH => {
+                let len = self.items.get(&u16_at(data, 0)).map_or(0, Vec::len);
+                data_format::to_vec(&(len as u16)).unwrap()
+            }
+            id::READ_EXT => {
+                let item = &self.items[&u16_at(data, 0)];
+                let offset = u16_at(data, 2).into();
+                let end = item.len().min(offset + 240);
+                data_format::to_vec(&OsalNvReadExtReply {
+                    status: BasicStatus::Ok,
+                    bytes: item[offset..end].to_vec(),
+                })
+                .unwrap()
+            }
+            id::WRITE_EXT => {
+                let item = self.items.get_mut(&u16_at(data, 0))?;
+                let offset: usize = u16_at(data, 2).into();
+                let len: usize = u16_at(data, 4).into();
+                status(write(item, offset, &data[6..6 + len]))
+            }
+            id::EX_CREATE => {
+                let len = u32::from_le_bytes(data[5..9].try_into().unwrap());
+                self.ex_items.insert(ex_key(data), vec![0; len as usize]);
+                status(NvStatus::Success)
+            }
+            id::EX_DELETE => {
+                status(match self.ex_items.remove(&ex_key(data)) {
+                    Some(_) => NvStatus::Success,
+                    None => NvStatus::ItemUninit,
+                })
+            }
+            id::EX_LENGTH => {
+                let len = self.ex_items.get(&ex_key(data)).map_or(0, Vec::len);
+                data_format::to_vec(&NvLengthReply { len: len as u32 }).unwrap()
+            }
+            id::EX_READ => {
+                let item = &self.ex_items[&ex_key(data)];
+                let offset: usize = u16_at(data, 5).into();
+                let end = item.len().min(offset + usize::from(data[7]));
+                data_format::to_vec(&NvReadReply {
+                    status: NvStatus::Success,
+                    value: item[offset..end].to_vec(),
+                })
+                .unwrap()
+            }
+            id::EX_WRITE => {
+                let item = self.ex_items.get_mut(&ex_key(data))?;
+                let offset: usize = u16_at(data, 5).into();
+                let len: usize = data[7].into();
+                status(write(item, offset, &data[8..8 + len]))
+            }
+            _ => return None,
+        };
+        Some(to_frame(reply, reply_meta).unwrap())
+    }
+}
+
+fn u16_at(data: &[u8], idx: usize) -> u16 {
+    u16::from_le_bytes([data[idx], data[idx + 1]])
+}
+
+fn ex_key(data: &[u8]) -> [u8; 5] {
+    data[0..5].try_into().unwrap()
+}
+
+fn write(item: &mut [u8], offset: usize, value: &[u8]) -> NvStatus {
+    match item.get_mut(offset..offset + value.len()) {
+        Some(target) => {
+            target.copy_from_slice(value);
+            NvStatus::Success
+        }
+        None => NvStatus::BadItemLength,
+    }
+}
+
+fn status(status: NvStatus) -> Vec<u8> {
+    data_format::to_vec(&status).unwrap()
+}
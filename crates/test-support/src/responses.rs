@@ -23,6 +23,66 @@ pub(crate) const SYS_VERSION: CommandMeta = CommandMeta {
     id: 2,
 };
 
+pub(crate) const SET_TX_POWER: CommandMeta = CommandMeta {
+    ty: CommandType::SREQ,
+    sub_system: SubSystem::Sys,
+    id: 20,
+};
+
+pub(crate) const AF_REGISTER: CommandMeta = CommandMeta {
+    ty: CommandType::SREQ,
+    sub_system: SubSystem::Af,
+    id: 0,
+};
+
+pub(crate) const AF_DATA_REQUEST: CommandMeta = CommandMeta {
+    ty: CommandType::SREQ,
+    sub_system: SubSystem::Af,
+    id: 1,
+};
+
+pub(crate) const NWK_ADDR_REQ: CommandMeta = CommandMeta {
+    ty: CommandType::SREQ,
+    sub_system: SubSystem::Zdo,
+    id: 0,
+};
+
+pub(crate) const IEEE_ADDR_REQ: CommandMeta = CommandMeta {
+    ty: CommandType::SREQ,
+    sub_system: SubSystem::Zdo,
+    id: 1,
+};
+
+pub(crate) const NODE_DESC_REQ: CommandMeta = CommandMeta {
+    ty: CommandType::SREQ,
+    sub_system: SubSystem::Zdo,
+    id: 2,
+};
+
+pub(crate) const POWER_DESC_REQ: CommandMeta = CommandMeta {
+    ty: CommandType::SREQ,
+    sub_system: SubSystem::Zdo,
+    id: 3,
+};
+
+pub(crate) const SIMPLE_DESC_REQ: CommandMeta = CommandMeta {
+    ty: CommandType::SREQ,
+    sub_system: SubSystem::Zdo,
+    id: 4,
+};
+
+pub(crate) const ACTIVE_EP_REQ: CommandMeta = CommandMeta {
+    ty: CommandType::SREQ,
+    sub_system: SubSystem::Zdo,
+    id: 5,
+};
+
+pub(crate) const AF_DATA_REQUEST_EXT: CommandMeta = CommandMeta {
+    ty: CommandType::SREQ,
+    sub_system: SubSystem::Af,
+    id: 2,
+};
+
 pub(crate) const EXT_FIND_GROUP: CommandMeta = CommandMeta {
     ty: CommandType::SREQ,
     sub_system: SubSystem::Zdo,
@@ -41,6 +101,60 @@ pub(crate) const RTG_REQ: CommandMeta = CommandMeta {
     id: 50,
 };
 
+pub(crate) const PERMIT_JOIN_REQ: CommandMeta = CommandMeta {
+    ty: CommandType::SREQ,
+    sub_system: SubSystem::Zdo,
+    id: 54,
+};
+
+pub(crate) const BIND_REQ: CommandMeta = CommandMeta {
+    ty: CommandType::SREQ,
+    sub_system: SubSystem::Zdo,
+    id: 33,
+};
+
+pub(crate) const UNBIND_REQ: CommandMeta = CommandMeta {
+    ty: CommandType::SREQ,
+    sub_system: SubSystem::Zdo,
+    id: 34,
+};
+
+pub(crate) const MGMT_BIND_REQ: CommandMeta = CommandMeta {
+    ty: CommandType::SREQ,
+    sub_system: SubSystem::Zdo,
+    id: 51,
+};
+
+pub(crate) const MGMT_LEAVE_REQ: CommandMeta = CommandMeta {
+    ty: CommandType::SREQ,
+    sub_system: SubSystem::Zdo,
+    id: 52,
+};
+
+pub(crate) const SEC_DEVICE_REMOVE: CommandMeta = CommandMeta {
+    ty: CommandType::SREQ,
+    sub_system: SubSystem::Zdo,
+    id: 68,
+};
+
+pub(crate) const ADD_INSTALL_CODE: CommandMeta = CommandMeta {
+    ty: CommandType::SREQ,
+    sub_system: SubSystem::AppConfig,
+    id: 4,
+};
+
+pub(crate) const BDB_START_COMMISSIONING: CommandMeta = CommandMeta {
+    ty: CommandType::SREQ,
+    sub_system: SubSystem::AppConfig,
+    id: 5,
+};
+
+pub(crate) const BDB_SET_CHANNEL: CommandMeta = CommandMeta {
+    ty: CommandType::SREQ,
+    sub_system: SubSystem::AppConfig,
+    id: 8,
+};
+
 pub(crate) fn reset() -> Vec<u8> {
     use zstacker_znp_protocol::commands::sys::{ResetInd, ResetReason};
     pub(crate) const RESPONSE: ResetInd = ResetInd {
@@ -69,6 +183,125 @@ pub(crate) fn sys_version() -> Vec<u8> {
         .unwrap()
 }
 
+pub(crate) fn set_tx_power() -> Vec<u8> {
+    use zstacker_znp_protocol::commands::BasicStatus;
+    use zstacker_znp_protocol::commands::sys::SetTxPowerReply;
+    to_frame(
+        data_format::to_vec(&BasicStatus::Ok).unwrap(),
+        SetTxPowerReply::META,
+    )
+    .unwrap()
+}
+
+pub(crate) fn af_register() -> Vec<u8> {
+    use zstacker_znp_protocol::commands::BasicStatus;
+    use zstacker_znp_protocol::commands::af::RegisterReply;
+    to_frame(
+        data_format::to_vec(&BasicStatus::Ok).unwrap(),
+        RegisterReply::META,
+    )
+    .unwrap()
+}
+
+pub(crate) fn data_request_status() -> Vec<u8> {
+    use zstacker_znp_protocol::commands::BasicStatus;
+    use zstacker_znp_protocol::commands::af::DataRequest;
+    to_frame(
+        data_format::to_vec(&BasicStatus::Ok).unwrap(),
+        DataRequest::status_reply_meta().unwrap(),
+    )
+    .unwrap()
+}
+
+pub(crate) fn data_confirm(endpoint: u8, trans_id: u8) -> Vec<u8> {
+    use zstacker_znp_protocol::commands::Status;
+    use zstacker_znp_protocol::commands::af::{ConfirmStatus, DataConfirm};
+    to_frame(
+        data_format::to_vec(&DataConfirm {
+            status: ConfirmStatus::Known(Status::ZSuccess),
+            endpoint,
+            trans_id,
+        })
+        .unwrap(),
+        DataConfirm::META,
+    )
+    .unwrap()
+}
+
+/// If the data request carried a cluster specific ZCL command the device
+/// answers with a successful default response, or with the matching
+/// response for genGroups commands that have one.
+pub(crate) fn zcl_response(request: &[u8]) -> Option<Vec<u8>> {
+    use zstacker_znp_protocol::commands::AsyncNotify;
+    use zstacker_znp_protocol::commands::af::{ClusterId, IncomingMsg};
+    let [
+        dst_lo,
+        dst_hi,
+        dst_endpoint,
+        src_endpoint,
+        cluster_lo,
+        cluster_hi,
+        ..,
+    ] = *request
+    else {
+        return None;
+    };
+    let [frame_control, seq, command_id, ..] = *request.get(10..)? else {
+        return None;
+    };
+    if frame_control & 0b11 != 0b01 {
+        return None;
+    }
+    let cluster_id = u16::from_le_bytes([cluster_lo, cluster_hi]);
+    let data = match groups_response(cluster_id, command_id, &request[13..]) {
+        // server to client, disable default response, cluster specific
+        Some(payload) => [vec![0x19, seq, command_id], payload].concat(),
+        // server to client, disable default response, global default
+        // response with status success
+        None => vec![0x18, seq, 0x0b, command_id, 0x00],
+    };
+    let msg = IncomingMsg {
+        group_id: 0,
+        cluster_id: ClusterId(cluster_id),
+        src_addr: ShortAddr(u16::from_le_bytes([dst_lo, dst_hi])),
+        src_endpoint: dst_endpoint,
+        dst_endpoint: src_endpoint,
+        was_broadcast: false,
+        link_quality: 255,
+        security_use: false,
+        timestamp: 0,
+        trans_seq_number: 0,
+        data,
+    };
+    Some(
+        to_frame(data_format::to_vec(&msg).unwrap(), IncomingMsg::META)
+            .unwrap(),
+    )
+}
+
+/// Every device is a member of groups 1 and 2 and accepts being added to
+/// or removed from any group. `payload` follows the ZCL header.
+fn groups_response(
+    cluster_id: u16,
+    command_id: u8,
+    payload: &[u8],
+) -> Option<Vec<u8>> {
+    const GENERIC_GROUPS: u16 = 0x0004;
+    if cluster_id != GENERIC_GROUPS {
+        return None;
+    }
+    let group_id = payload.get(..2);
+    match command_id {
+        // add group and remove group: status success and the group id
+        0x00 | 0x03 => Some([&[0x00], group_id?].concat()),
+        // view group: status success, the group id and an empty name
+        0x01 => Some([&[0x00], group_id?, &[0x00]].concat()),
+        // get group membership: room for more, two groups
+        0x02 => Some(vec![0xfe, 2, 0x01, 0x00, 0x02, 0x00]),
+        _ => None,
+    }
+}
+
 pub(crate) fn device_info() -> Vec<u8> {
     use zstacker_znp_protocol::commands::util::DeviceInfo;
     use zstacker_znp_protocol::commands::{
@@ -110,28 +343,55 @@ pub(crate) fn lqi_status() -> Vec<u8> {
     .unwrap()
 }
 
-pub(crate) fn lqi(src_addr: u16) -> Vec<u8> {
+/// Short address of the router added by [`crate::MockNetwork`]
+pub(crate) const OFFLINE_ROUTER: u16 = 5;
+
+/// The mock network is the coordinator at 43, a router at 2 that is its
+/// child and an end device at 0x0304 that is the child of the router.
+/// Other devices have no neighbors.
+pub(crate) fn lqi(src_addr: u16, offline_router: bool) -> Vec<u8> {
     use zstacker_znp_protocol::commands::zdo::{MgmtLqiRsp, NeighborLqi};
-    use zstacker_znp_protocol::commands::{BasicStatus, IeeeAddr};
+    use zstacker_znp_protocol::commands::{BasicStatus, DeviceType, IeeeAddr};
+    let neighbor = |addr: u16, device_type, relationship, depth, lqi| {
+        let ieee_addr = if addr == 43 {
+            42
+        } else {
+            MOCK_IEEE_PREFIX | u64::from(addr)
+        };
+        NeighborLqi {
+            extended_pan_id: 0xdddd_dddd_dddd_dddd,
+            extended_address: IeeeAddr(ieee_addr),
+            network_address: ShortAddr(addr),
+            device_type,
+            rx_on_when_idle: u8::from(!matches!(
+                device_type,
+                DeviceType::EndDevice
+            )),
+            relationship,
+            permit_joining: false,
+            depth,
+            lqi,
+        }
+    };
+    let mut neighbors = match src_addr {
+        43 => vec![neighbor(2, DeviceType::Router, 1, 1, 200)],
+        2 => vec![
+            neighbor(43, DeviceType::Coordinator, 0, 0, 180),
+            neighbor(0x0304, DeviceType::EndDevice, 1, 2, 120),
+        ],
+        _ => Vec::new(),
+    };
+    if offline_router && src_addr == 2 {
+        neighbors.push(neighbor(OFFLINE_ROUTER, DeviceType::Router, 2, 1, 60));
+    }
     to_frame(
         data_format::to_vec(&MgmtLqiRsp {
             srcaddr: ShortAddr(src_addr),
             status: BasicStatus::Ok,
             neighbor_lqis: PartialList::from_vec(
                 0,
-                1,
-                vec![NeighborLqi {
-                    extended_pan_id: 0,
-                    extended_address: IeeeAddr(2),
-                    network_address: ShortAddr(2),
-                    device_type:
-                        zstacker_znp_protocol::commands::DeviceType::Router,
-                    rx_on_when_idle: 1,
-                    relationship: 1,
-                    permit_joining: true,
-                    depth: 1,
-                    lqi: 16,
-                }],
+                neighbors.len() as u8,
+                neighbors,
             ),
         })
         .unwrap(),
@@ -150,27 +410,394 @@ pub(crate) fn rtg_status() -> Vec<u8> {
     .unwrap()
 }
 
-pub(crate) fn routing_table(src_addr: u16) -> Vec<u8> {
+/// Routes of the network described at [`lqi`]
+pub(crate) fn routing_table(src_addr: u16, offline_router: bool) -> Vec<u8> {
     use zstacker_znp_protocol::commands::BasicStatus;
     use zstacker_znp_protocol::commands::zdo::{
         MgmtRtgRsp, RouterStatus, RoutingEntry,
     };
+    let route = |destination, next_hop| RoutingEntry {
+        destination_address: ShortAddr(destination),
+        status: RouterStatus::Active,
+        next_hop: ShortAddr(next_hop),
+    };
+    let mut routes = match src_addr {
+        43 => vec![route(2, 2), route(0x0304, 2)],
+        2 => vec![route(0x0304, 0x0304)],
+        _ => Vec::new(),
+    };
+    if offline_router && src_addr == 2 {
+        routes.push(route(OFFLINE_ROUTER, OFFLINE_ROUTER));
+    }
     to_frame(
         data_format::to_vec(&MgmtRtgRsp {
             src_addr: ShortAddr(src_addr),
             status: BasicStatus::Ok,
-            routing_table: PartialList::from_vec(
-                0,
-                1,
-                vec![RoutingEntry {
-                    destination_address: ShortAddr(42),
-                    status: RouterStatus::Active,
-                    next_hop: ShortAddr(42),
-                }],
-            ),
+            routing_table: PartialList::from_vec(0, routes.len() as u8, routes),
         })
         .unwrap(),
         MgmtRtgRsp::META,
     )
     .unwrap()
 }
+
+pub(crate) fn bdb_set_channel() -> Vec<u8> {
+    use zstacker_znp_protocol::commands::BasicStatus;
+    use zstacker_znp_protocol::commands::appconfig::BdbSetChannelReply;
+    to_frame(
+        data_format::to_vec(&BasicStatus::Ok).unwrap(),
+        BdbSetChannelReply::META,
+    )
+    .unwrap()
+}
+
+pub(crate) fn add_install_code() -> Vec<u8> {
+    use zstacker_znp_protocol::commands::BasicStatus;
+    use zstacker_znp_protocol::commands::appconfig::AddInstallCodeReply;
+    to_frame(
+        data_format::to_vec(&BasicStatus::Ok).unwrap(),
+        AddInstallCodeReply::META,
+    )
+    .unwrap()
+}
+
+pub(crate) fn bdb_start_commissioning() -> Vec<u8> {
+    use zstacker_znp_protocol::commands::BasicStatus;
+    use zstacker_znp_protocol::commands::appconfig::BdbStartCommissioningReply;
+    to_frame(
+        data_format::to_vec(&BasicStatus::Ok).unwrap(),
+        BdbStartCommissioningReply::META,
+    )
+    .unwrap()
+}
+
+/// Formation first reports it is in progress then that it succeeded
+pub(crate) fn bdb_commissioning_notifications(mode: u8) -> Vec<Vec<u8>> {
+    use zstacker_znp_protocol::commands::AsyncNotify;
+    use zstacker_znp_protocol::commands::appconfig::{
+        BdbCommissioningNotification, BdbCommissioningStatus,
+    };
+    [
+        BdbCommissioningStatus::InProgress,
+        BdbCommissioningStatus::Success,
+    ]
+    .into_iter()
+    .map(|status| {
+        let notification = BdbCommissioningNotification {
+            status,
+            mode,
+            remaining_modes: 0,
+        };
+        to_frame(
+            data_format::to_vec(&notification).unwrap(),
+            BdbCommissioningNotification::META,
+        )
+        .unwrap()
+    })
+    .collect()
+}
+
+pub(crate) fn permit_join_status() -> Vec<u8> {
+    use zstacker_znp_protocol::commands::BasicStatus;
+    use zstacker_znp_protocol::commands::zdo::MgmtPermitJoinReq;
+    to_frame(
+        data_format::to_vec(&BasicStatus::Ok).unwrap(),
+        MgmtPermitJoinReq::status_reply_meta().unwrap(),
+    )
+    .unwrap()
+}
+
+/// The coordinator answers broadcasts itself
+pub(crate) fn permit_join(addr_mode: u8, dst_addr: u16) -> Vec<u8> {
+    use zstacker_znp_protocol::commands::Status;
+    use zstacker_znp_protocol::commands::zdo::MgmtPermitJoinRsp;
+    let src_addr = if addr_mode == 0x0F { 0 } else { dst_addr };
+    to_frame(
+        data_format::to_vec(&MgmtPermitJoinRsp {
+            src_addr: ShortAddr(src_addr),
+            status: Status::ZSuccess,
+        })
+        .unwrap(),
+        MgmtPermitJoinRsp::META,
+    )
+    .unwrap()
+}
+
+pub(crate) fn permit_join_ind(duration: u8) -> Vec<u8> {
+    use zstacker_znp_protocol::commands::AsyncNotify;
+    use zstacker_znp_protocol::commands::zdo::PermitJoinInd;
+    to_frame(
+        data_format::to_vec(&PermitJoinInd { duration }).unwrap(),
+        PermitJoinInd::META,
+    )
+    .unwrap()
+}
+
+/// A device joins through router `0x0e0f` and then announces itself
+pub(crate) fn device_joined() -> Vec<Vec<u8>> {
+    use zstacker_znp_protocol::commands::zdo::{
+        EndDeviceAnnceInd, TcDeviceInd,
+    };
+    use zstacker_znp_protocol::commands::{AsyncNotify, IeeeAddr};
+    const IEEE_ADDR: IeeeAddr = IeeeAddr(0x00124b0001020304);
+    const NWK_ADDR: ShortAddr = ShortAddr(0xa1b2);
+    let trust_center = TcDeviceInd {
+        nwk_addr: NWK_ADDR,
+        ext_addr: IEEE_ADDR,
+        parent_addr: ShortAddr(0x0e0f),
+    };
+    let announce = EndDeviceAnnceInd {
+        src_addr: NWK_ADDR,
+        nwk_addr: NWK_ADDR,
+        ieee_addr: IEEE_ADDR,
+        capabilities: 0x80,
+    };
+    vec![
+        to_frame(
+            data_format::to_vec(&trust_center).unwrap(),
+            TcDeviceInd::META,
+        )
+        .unwrap(),
+        to_frame(
+            data_format::to_vec(&announce).unwrap(),
+            EndDeviceAnnceInd::META,
+        )
+        .unwrap(),
+    ]
+}
+
+/// Devices have IEEE addresses 0x00124b000102xxxx, where xxxx is their
+/// short address
+const MOCK_IEEE_PREFIX: u64 = 0x00124b0001020000;
+
+/// Devices outside of [`MOCK_IEEE_PREFIX`] are not on the network
+pub(crate) fn nwk_addr(ieee_addr: u64) -> Vec<u8> {
+    use zstacker_znp_protocol::commands::zdo::NwkAddrRsp;
+    use zstacker_znp_protocol::commands::{IeeeAddr, Status};
+    let status = if ieee_addr & !0xffff == MOCK_IEEE_PREFIX {
+        Status::ZSuccess
+    } else {
+        Status::ZdoDeviceNotFound
+    };
+    to_frame(
+        data_format::to_vec(&NwkAddrRsp {
+            status,
+            ieee_addr: IeeeAddr(ieee_addr),
+            nwk_addr: ShortAddr(ieee_addr as u16),
+            start_index: 0,
+            assoc_dev_list: Vec::new(),
+        })
+        .unwrap(),
+        NwkAddrRsp::META,
+    )
+    .unwrap()
+}
+
+pub(crate) fn ieee_addr(short_addr: u16) -> Vec<u8> {
+    use zstacker_znp_protocol::commands::zdo::IeeeAddrRsp;
+    use zstacker_znp_protocol::commands::{IeeeAddr, Status};
+    to_frame(
+        data_format::to_vec(&IeeeAddrRsp {
+            status: Status::ZSuccess,
+            ieee_addr: IeeeAddr(MOCK_IEEE_PREFIX | u64::from(short_addr)),
+            nwk_addr: ShortAddr(short_addr),
+            start_index: 0,
+            assoc_dev_list: Vec::new(),
+        })
+        .unwrap(),
+        IeeeAddrRsp::META,
+    )
+    .unwrap()
+}
+
+pub(crate) fn bind(request: &CommandMeta, dst_addr: u16) -> Vec<u8> {
+    use zstacker_znp_protocol::commands::Status;
+    use zstacker_znp_protocol::commands::zdo::{BindRsp, UnbindRsp};
+    let meta = if *request == BIND_REQ {
+        BindRsp::META
+    } else {
+        UnbindRsp::META
+    };
+    to_frame(
+        data_format::to_vec(&BindRsp {
+            src_addr: ShortAddr(dst_addr),
+            status: Status::ZSuccess,
+        })
+        .unwrap(),
+        meta,
+    )
+    .unwrap()
+}
+
+/// Every device has the same three bindings, of which two are returned at
+/// a time.
+pub(crate) fn binding_table(dst_addr: u16, start_index: u8) -> Vec<u8> {
+    use zstacker_znp_protocol::commands::af::ClusterId;
+    use zstacker_znp_protocol::commands::zdo::{
+        BindDestination, BindTable, MgmtBindRsp,
+    };
+    use zstacker_znp_protocol::commands::{IeeeAddr, Status};
+    let src = IeeeAddr(MOCK_IEEE_PREFIX | u64::from(dst_addr));
+    let table = [
+        (ClusterId(0x0006), BindDestination::Group(0x0001)),
+        (
+            ClusterId(0x0008),
+            BindDestination::Device {
+                ieee_addr: IeeeAddr(0x00124b0005060708),
+                endpoint: 1,
+            },
+        ),
+        (ClusterId(0x0300), BindDestination::Group(0x0002)),
+    ]
+    .map(|(cluster_id, dst)| BindTable {
+        src,
+        src_endpoint: 1,
+        cluster_id,
+        dst,
+    });
+    let page = table.iter().skip(start_index.into()).take(2).cloned();
+    to_frame(
+        data_format::to_vec(&MgmtBindRsp {
+            src_addr: ShortAddr(dst_addr),
+            status: Status::ZSuccess,
+            binding_table: PartialList::from_vec(
+                start_index,
+                table.len() as u8,
+                page.collect(),
+            ),
+        })
+        .unwrap(),
+        MgmtBindRsp::META,
+    )
+    .unwrap()
+}
+
+pub(crate) fn mgmt_leave(dst_addr: u16) -> Vec<u8> {
+    use zstacker_znp_protocol::commands::Status;
+    use zstacker_znp_protocol::commands::zdo::MgmtLeaveRsp;
+    to_frame(
+        data_format::to_vec(&MgmtLeaveRsp {
+            src_addr: ShortAddr(dst_addr),
+            status: Status::ZSuccess,
+        })
+        .unwrap(),
+        MgmtLeaveRsp::META,
+    )
+    .unwrap()
+}
+
+pub(crate) fn sec_device_remove() -> Vec<u8> {
+    use zstacker_znp_protocol::commands::BasicStatus;
+    use zstacker_znp_protocol::commands::zdo::SecDeviceRemoveReply;
+    to_frame(
+        data_format::to_vec(&BasicStatus::Ok).unwrap(),
+        SecDeviceRemoveReply::META,
+    )
+    .unwrap()
+}
+
+/// Status reply to any of the ZDO descriptor requests
+pub(crate) fn descriptor_status(request: CommandMeta) -> Vec<u8> {
+    use zstacker_znp_protocol::commands::BasicStatus;
+    let meta = CommandMeta {
+        ty: CommandType::SRSP,
+        ..request
+    };
+    to_frame(data_format::to_vec(&BasicStatus::Ok).unwrap(), meta).unwrap()
+}
+
+/// Every device is a mains powered router
+pub(crate) fn node_desc(addr: u16) -> Vec<u8> {
+    use zstacker_znp_protocol::commands::Status;
+    use zstacker_znp_protocol::commands::zdo::NodeDescRsp;
+    to_frame(
+        data_format::to_vec(&NodeDescRsp {
+            src_addr: ShortAddr(addr),
+            status: Status::ZSuccess,
+            nwk_addr: ShortAddr(addr),
+            logical_type_flags: 0x01,
+            aps_flags_freq_band: 0x40,
+            mac_capabilities: 0x8e,
+            manufacturer_code: 0x115f,
+            max_buffer_size: 0x7f,
+            max_in_transfer_size: 100,
+            server_mask: 0,
+            max_out_transfer_size: 100,
+            descriptor_capabilities: 0,
+        })
+        .unwrap(),
+        NodeDescRsp::META,
+    )
+    .unwrap()
+}
+
+pub(crate) fn power_desc(addr: u16) -> Vec<u8> {
+    use zstacker_znp_protocol::commands::Status;
+    use zstacker_znp_protocol::commands::zdo::PowerDescRsp;
+    to_frame(
+        data_format::to_vec(&PowerDescRsp {
+            src_addr: ShortAddr(addr),
+            status: Status::ZSuccess,
+            nwk_addr: ShortAddr(addr),
+            current_mode_available_sources: 0x10,
+            current_source_level: 0xc1,
+        })
+        .unwrap(),
+        PowerDescRsp::META,
+    )
+    .unwrap()
+}
+
+pub(crate) fn active_ep(addr: u16) -> Vec<u8> {
+    use zstacker_znp_protocol::commands::Status;
+    use zstacker_znp_protocol::commands::zdo::ActiveEpRsp;
+    to_frame(
+        data_format::to_vec(&ActiveEpRsp {
+            src_addr: ShortAddr(addr),
+            status: Status::ZSuccess,
+            nwk_addr: ShortAddr(addr),
+            active_endpoints: vec![1, 242],
+        })
+        .unwrap(),
+        ActiveEpRsp::META,
+    )
+    .unwrap()
+}
+
+/// Endpoint 1 is an on/off light, 242 the green power proxy
+pub(crate) fn simple_desc(addr: u16, endpoint: u8) -> Vec<u8> {
+    use zstacker_znp_protocol::commands::Status;
+    use zstacker_znp_protocol::commands::af::ClusterId;
+    use zstacker_znp_protocol::commands::zdo::{
+        SimpleDescRsp, SimpleDescriptor,
+    };
+    let descriptor = match endpoint {
+        1 => SimpleDescriptor {
+            endpoint,
+            profile_id: 0x0104,
+            device_id: 0x0100,
+            device_version: 1,
+            in_clusters: vec![ClusterId(0x0000), ClusterId(0x0006)],
+            out_clusters: vec![ClusterId(0x0019)],
+        },
+        _ => SimpleDescriptor {
+            endpoint,
+            profile_id: 0xa1e0,
+            device_id: 0x0061,
+            device_version: 0,
+            in_clusters: Vec::new(),
+            out_clusters: vec![ClusterId(0x0021)],
+        },
+    };
+    to_frame(
+        data_format::to_vec(&SimpleDescRsp {
+            src_addr: ShortAddr(addr),
+            status: Status::ZSuccess,
+            nwk_addr: ShortAddr(addr),
+            descriptor: Some(descriptor),
+        })
+        .unwrap(),
+        SimpleDescRsp::META,
+    )
+    .unwrap()
+}
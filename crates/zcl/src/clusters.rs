@@ -0,0 +1,77 @@
+//! Typed attributes and commands of the common home automation clusters.
+//!
+//! Each module has the cluster `ID`, an [`Attribute`](crate::Attribute)
+//! implementing type per attribute and a struct per cluster specific command.
+//! Names follow the ZCL specification, the zigbee-herdsman name of the
+//! cluster is mentioned in the module documentation.
+
+/// Defines a type implementing [`Attribute`](crate::Attribute). The data type
+/// and [`AttributeValue`](crate::AttributeValue) variant share a name.
+macro_rules! attribute {
+    ($(#[$doc:meta])* $name:ident = $id:literal: $ty:ident => $value:ty) => {
+        $(#[$doc])*
+        #[derive(Debug, Clone, Copy)]
+        pub struct $name;
+
+        impl $crate::Attribute for $name {
+            const CLUSTER: $crate::ClusterId = ID;
+            const ID: $crate::AttributeId = $id;
+            const DATA_TYPE: $crate::DataType = $crate::DataType::$ty;
+            type Value = $value;
+
+            fn from_value(value: $crate::AttributeValue) -> Option<$value> {
+                match value {
+                    $crate::AttributeValue::$ty(v) => Some(v),
+                    _ => None,
+                }
+            }
+
+            fn to_value(value: $value) -> $crate::AttributeValue {
+                $crate::AttributeValue::$ty(value)
+            }
+        }
+    };
+}
+
+/// Implements [`ClusterCommand`](crate::ClusterCommand) for a command of the
+/// cluster in the current module.
+macro_rules! cluster_command {
+    ($ty:ty, $id:literal, $direction:ident) => {
+        impl $crate::Command for $ty {
+            const ID: u8 = $id;
+            const FRAME_TYPE: $crate::FrameType =
+                $crate::FrameType::ClusterSpecific;
+            const DIRECTION: $crate::Direction = $crate::Direction::$direction;
+        }
+
+        impl $crate::ClusterCommand for $ty {
+            const CLUSTER: $crate::ClusterId = ID;
+        }
+    };
+}
+
+pub mod basic;
+pub mod color_control;
+pub mod groups;
+pub mod ias_zone;
+pub mod level_control;
+pub mod on_off;
+pub mod ota;
+pub mod power_configuration;
+pub mod relative_humidity;
+pub mod temperature_measurement;
+
+/// `genTime`, the coordinator serves the time to devices that ask
+pub mod time {
+    pub const ID: crate::ClusterId = 0x000a;
+}
+
+/// `ssIasAce`, ancillary control equipment such as keypads
+pub mod ias_ace {
+    pub const ID: crate::ClusterId = 0x0501;
+}
+
+/// `ssIasWd`, warning devices such as sirens
+pub mod ias_wd {
+    pub const ID: crate::ClusterId = 0x0502;
+}
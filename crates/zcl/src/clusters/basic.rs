@@ -0,0 +1,52 @@
+//! `genBasic`: identifies the device and its manufacturer.
+
+use serde::{Deserialize, Serialize};
+
+pub const ID: crate::ClusterId = 0x0000;
+
+attribute!(ZclVersion = 0x0000: Uint8 => u8);
+attribute!(ApplicationVersion = 0x0001: Uint8 => u8);
+attribute!(StackVersion = 0x0002: Uint8 => u8);
+attribute!(HwVersion = 0x0003: Uint8 => u8);
+attribute!(ManufacturerName = 0x0004: CharStr => String);
+attribute!(ModelIdentifier = 0x0005: CharStr => String);
+attribute!(DateCode = 0x0006: CharStr => String);
+attribute!(
+    /// See [`PowerSupply`] for the meaning of the value
+    PowerSource = 0x0007: Enum8 => u8
+);
+attribute!(SwBuildId = 0x4000: CharStr => String);
+
+/// Value of [`PowerSource`] with the battery backup bit (0x80) masked off
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PowerSupply {
+    Unknown,
+    MainsSinglePhase,
+    MainsThreePhase,
+    Battery,
+    DcSource,
+    EmergencyMainsConstantlyPowered,
+    EmergencyMainsAndTransferSwitch,
+    Reserved(u8),
+}
+
+impl PowerSupply {
+    pub const BATTERY_BACKUP: u8 = 0x80;
+
+    pub fn from_raw(raw: u8) -> Self {
+        match raw & !Self::BATTERY_BACKUP {
+            0x00 => Self::Unknown,
+            0x01 => Self::MainsSinglePhase,
+            0x02 => Self::MainsThreePhase,
+            0x03 => Self::Battery,
+            0x04 => Self::DcSource,
+            0x05 => Self::EmergencyMainsConstantlyPowered,
+            0x06 => Self::EmergencyMainsAndTransferSwitch,
+            other => Self::Reserved(other),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ResetToFactoryDefaults;
+cluster_command!(ResetToFactoryDefaults, 0x00, ClientToServer);
@@ -0,0 +1,84 @@
+//! `lightingColorCtrl`: color and color temperature of lights.
+
+use serde::{Deserialize, Serialize};
+use serde_repr::{Deserialize_repr, Serialize_repr};
+
+pub const ID: crate::ClusterId = 0x0300;
+
+attribute!(CurrentHue = 0x0000: Uint8 => u8);
+attribute!(CurrentSaturation = 0x0001: Uint8 => u8);
+attribute!(RemainingTime = 0x0002: Uint16 => u16);
+attribute!(
+    /// CIE 1931 x coordinate times 65536
+    CurrentX = 0x0003: Uint16 => u16
+);
+attribute!(
+    /// CIE 1931 y coordinate times 65536
+    CurrentY = 0x0004: Uint16 => u16
+);
+attribute!(
+    /// Reciprocal mega kelvin (1_000_000 / kelvin)
+    ColorTemperatureMireds = 0x0007: Uint16 => u16
+);
+attribute!(
+    /// 0x00 hue and saturation, 0x01 x and y, 0x02 color temperature
+    ColorMode = 0x0008: Enum8 => u8
+);
+attribute!(EnhancedCurrentHue = 0x4000: Uint16 => u16);
+attribute!(ColorCapabilities = 0x400a: Bitmap16 => u16);
+attribute!(ColorTempPhysicalMinMireds = 0x400b: Uint16 => u16);
+attribute!(ColorTempPhysicalMaxMireds = 0x400c: Uint16 => u16);
+
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, Serialize_repr, Deserialize_repr,
+)]
+#[repr(u8)]
+pub enum HueDirection {
+    ShortestDistance = 0x00,
+    LongestDistance = 0x01,
+    Up = 0x02,
+    Down = 0x03,
+}
+
+/// Transition times are in tenths of a second
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MoveToHue {
+    pub hue: u8,
+    pub direction: HueDirection,
+    pub transition_time: u16,
+}
+cluster_command!(MoveToHue, 0x00, ClientToServer);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MoveToSaturation {
+    pub saturation: u8,
+    pub transition_time: u16,
+}
+cluster_command!(MoveToSaturation, 0x03, ClientToServer);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MoveToHueAndSaturation {
+    pub hue: u8,
+    pub saturation: u8,
+    pub transition_time: u16,
+}
+cluster_command!(MoveToHueAndSaturation, 0x06, ClientToServer);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MoveToColor {
+    pub color_x: u16,
+    pub color_y: u16,
+    pub transition_time: u16,
+}
+cluster_command!(MoveToColor, 0x07, ClientToServer);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MoveToColorTemperature {
+    pub color_temperature_mireds: u16,
+    pub transition_time: u16,
+}
+cluster_command!(MoveToColorTemperature, 0x0a, ClientToServer);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StopMoveStep;
+cluster_command!(StopMoveStep, 0x47, ClientToServer);
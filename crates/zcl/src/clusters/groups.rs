@@ -0,0 +1,88 @@
+//! `genGroups`: the groups an endpoint is a member of. Messages send to a
+//! group id reach every member.
+
+use serde::{Deserialize, Serialize};
+
+use crate::Status;
+
+pub const ID: crate::ClusterId = 0x0004;
+
+attribute!(
+    /// Bit 7 is set if the device stores group names
+    NameSupport = 0x0000: Bitmap8 => u8
+);
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AddGroup {
+    pub group_id: u16,
+    /// Ignored by devices that do not support group names
+    pub group_name: String,
+}
+cluster_command!(AddGroup, 0x00, ClientToServer);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AddGroupResponse {
+    pub status: Status,
+    pub group_id: u16,
+}
+cluster_command!(AddGroupResponse, 0x00, ServerToClient);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ViewGroup {
+    pub group_id: u16,
+}
+cluster_command!(ViewGroup, 0x01, ClientToServer);
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ViewGroupResponse {
+    pub status: Status,
+    pub group_id: u16,
+    pub group_name: String,
+}
+cluster_command!(ViewGroupResponse, 0x01, ServerToClient);
+
+/// Asks which of the groups in `group_list` the endpoint is a member of,
+/// an empty list asks for all of them.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GetGroupMembership {
+    pub group_count: u8,
+    pub group_list: Vec<u16>,
+}
+cluster_command!(GetGroupMembership, 0x02, ClientToServer);
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GetGroupMembershipResponse {
+    /// How many more groups the device can join, 0xff if unknown and 0xfe
+    /// if it can join at least one more
+    pub capacity: u8,
+    pub group_count: u8,
+    pub group_list: Vec<u16>,
+}
+cluster_command!(GetGroupMembershipResponse, 0x02, ServerToClient);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RemoveGroup {
+    pub group_id: u16,
+}
+cluster_command!(RemoveGroup, 0x03, ClientToServer);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RemoveGroupResponse {
+    pub status: Status,
+    pub group_id: u16,
+}
+cluster_command!(RemoveGroupResponse, 0x03, ServerToClient);
+
+/// Leave every group, answered with a default response
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RemoveAllGroups;
+cluster_command!(RemoveAllGroups, 0x04, ClientToServer);
+
+/// Join the group only if the device is currently identifying itself.
+/// There is no response.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AddGroupIfIdentifying {
+    pub group_id: u16,
+    pub group_name: String,
+}
+cluster_command!(AddGroupIfIdentifying, 0x05, ClientToServer);
@@ -0,0 +1,77 @@
+//! `ssIasZone`: alarm sensors such as contact, motion and water leak
+//! sensors. A zone has to enroll with the coordinator before it sends
+//! status changes.
+
+use serde::{Deserialize, Serialize};
+use serde_repr::{Deserialize_repr, Serialize_repr};
+
+pub const ID: crate::ClusterId = 0x0500;
+
+attribute!(
+    /// 0x00 not enrolled, 0x01 enrolled
+    ZoneState = 0x0000: Enum8 => u8
+);
+attribute!(
+    /// For example 0x000d motion sensor, 0x0015 contact switch
+    ZoneType = 0x0001: Enum16 => u16
+);
+attribute!(
+    /// See [`zone_status`] for the meaning of the bits
+    ZoneStatus = 0x0002: Bitmap16 => u16
+);
+attribute!(
+    /// Where the zone sends its notifications, write the coordinators
+    /// address here before enrolling.
+    IasCieAddress = 0x0010: IeeeAddr => u64
+);
+attribute!(ZoneId = 0x0011: Uint8 => u8);
+
+/// Bits of the [`ZoneStatus`] attribute
+pub mod zone_status {
+    pub const ALARM1: u16 = 1 << 0;
+    pub const ALARM2: u16 = 1 << 1;
+    pub const TAMPER: u16 = 1 << 2;
+    pub const BATTERY_LOW: u16 = 1 << 3;
+    pub const SUPERVISION_REPORTS: u16 = 1 << 4;
+    pub const RESTORE_REPORTS: u16 = 1 << 5;
+    pub const TROUBLE: u16 = 1 << 6;
+    pub const AC_MAINS_FAULT: u16 = 1 << 7;
+    pub const TEST: u16 = 1 << 8;
+    pub const BATTERY_DEFECT: u16 = 1 << 9;
+}
+
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, Serialize_repr, Deserialize_repr,
+)]
+#[repr(u8)]
+pub enum EnrollResponseCode {
+    Success = 0x00,
+    NotSupported = 0x01,
+    NoEnrollPermit = 0x02,
+    TooManyZones = 0x03,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ZoneEnrollResponse {
+    pub response: EnrollResponseCode,
+    pub zone_id: u8,
+}
+cluster_command!(ZoneEnrollResponse, 0x00, ClientToServer);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ZoneStatusChangeNotification {
+    /// See [`zone_status`]
+    pub zone_status: u16,
+    pub extended_status: u8,
+    pub zone_id: u8,
+    /// Quarter seconds between the change and sending this
+    pub delay: u16,
+}
+cluster_command!(ZoneStatusChangeNotification, 0x00, ServerToClient);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ZoneEnrollRequest {
+    pub zone_type: u16,
+    pub manufacturer_code: u16,
+}
+cluster_command!(ZoneEnrollRequest, 0x01, ServerToClient);
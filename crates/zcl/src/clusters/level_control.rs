@@ -0,0 +1,83 @@
+//! `genLevelCtrl`: brightness of lights, position of blinds etc.
+
+use serde::{Deserialize, Serialize};
+use serde_repr::{Deserialize_repr, Serialize_repr};
+
+pub const ID: crate::ClusterId = 0x0008;
+
+attribute!(CurrentLevel = 0x0000: Uint8 => u8);
+attribute!(
+    /// Tenths of a second until the current transition ends
+    RemainingTime = 0x0001: Uint16 => u16
+);
+attribute!(
+    /// Tenths of a second used by the on/off cluster commands
+    OnOffTransitionTime = 0x0010: Uint16 => u16
+);
+attribute!(OnLevel = 0x0011: Uint8 => u8);
+attribute!(StartUpCurrentLevel = 0x4000: Uint8 => u8);
+
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, Serialize_repr, Deserialize_repr,
+)]
+#[repr(u8)]
+pub enum MoveMode {
+    Up = 0x00,
+    Down = 0x01,
+}
+
+/// Transition times are in tenths of a second, 0xffff means use the
+/// [`OnOffTransitionTime`] attribute.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MoveToLevel {
+    pub level: u8,
+    pub transition_time: u16,
+}
+cluster_command!(MoveToLevel, 0x00, ClientToServer);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Move {
+    pub mode: MoveMode,
+    /// Units per second, 0xff means as fast as possible
+    pub rate: u8,
+}
+cluster_command!(Move, 0x01, ClientToServer);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Step {
+    pub mode: MoveMode,
+    pub step_size: u8,
+    pub transition_time: u16,
+}
+cluster_command!(Step, 0x02, ClientToServer);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Stop;
+cluster_command!(Stop, 0x03, ClientToServer);
+
+/// Like [`MoveToLevel`] but also switches the device on or off
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MoveToLevelWithOnOff {
+    pub level: u8,
+    pub transition_time: u16,
+}
+cluster_command!(MoveToLevelWithOnOff, 0x04, ClientToServer);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MoveWithOnOff {
+    pub mode: MoveMode,
+    pub rate: u8,
+}
+cluster_command!(MoveWithOnOff, 0x05, ClientToServer);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StepWithOnOff {
+    pub mode: MoveMode,
+    pub step_size: u8,
+    pub transition_time: u16,
+}
+cluster_command!(StepWithOnOff, 0x06, ClientToServer);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StopWithOnOff;
+cluster_command!(StopWithOnOff, 0x07, ClientToServer);
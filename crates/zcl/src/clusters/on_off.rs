@@ -0,0 +1,55 @@
+//! `genOnOff`: switch a device on or off.
+
+use serde::{Deserialize, Serialize};
+
+pub const ID: crate::ClusterId = 0x0006;
+
+attribute!(OnOff = 0x0000: Bool => bool);
+attribute!(GlobalSceneControl = 0x4000: Bool => bool);
+attribute!(
+    /// Tenths of a second the device stays on after [`OnWithTimedOff`]
+    OnTime = 0x4001: Uint16 => u16
+);
+attribute!(
+    /// Tenths of a second the device ignores on commands after switching off
+    OffWaitTime = 0x4002: Uint16 => u16
+);
+attribute!(
+    /// 0x00 off, 0x01 on, 0x02 toggle, 0xff previous state
+    StartUpOnOff = 0x4003: Enum8 => u8
+);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Off;
+cluster_command!(Off, 0x00, ClientToServer);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct On;
+cluster_command!(On, 0x01, ClientToServer);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Toggle;
+cluster_command!(Toggle, 0x02, ClientToServer);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct OffWithEffect {
+    /// 0x00 delayed all off, 0x01 dying light
+    pub effect_id: u8,
+    pub effect_variant: u8,
+}
+cluster_command!(OffWithEffect, 0x40, ClientToServer);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct OnWithRecallGlobalScene;
+cluster_command!(OnWithRecallGlobalScene, 0x41, ClientToServer);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct OnWithTimedOff {
+    /// Bit 0: only accept when on
+    pub on_off_control: u8,
+    /// Tenths of a second
+    pub on_time: u16,
+    /// Tenths of a second
+    pub off_wait_time: u16,
+}
+cluster_command!(OnWithTimedOff, 0x42, ClientToServer);
@@ -0,0 +1,269 @@
+//! `genOta`: over the air firmware upgrades. The device is the client, it
+//! asks the server (the coordinator) for new images.
+
+use serde::de::{SeqAccess, Visitor};
+use serde::ser::SerializeTuple;
+use serde::{Deserialize, Serialize};
+
+use crate::Status;
+use crate::data_type::next;
+
+pub const ID: crate::ClusterId = 0x0019;
+
+attribute!(UpgradeServerId = 0x0000: IeeeAddr => u64);
+attribute!(FileOffset = 0x0001: Uint32 => u32);
+attribute!(CurrentFileVersion = 0x0002: Uint32 => u32);
+attribute!(DownloadedFileVersion = 0x0004: Uint32 => u32);
+attribute!(
+    /// 0x00 normal, 0x01 download in progress, 0x02 download complete,
+    /// 0x03 waiting to upgrade
+    ImageUpgradeStatus = 0x0006: Enum8 => u8
+);
+attribute!(ManufacturerId = 0x0007: Uint16 => u16);
+attribute!(ImageTypeId = 0x0008: Uint16 => u16);
+
+/// Identifies an image, used by most commands in this cluster
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ImageId {
+    pub manufacturer_code: u16,
+    pub image_type: u16,
+    pub file_version: u32,
+}
+
+/// Tells devices a new image is available. This version only carries the
+/// jitter, asking all devices to query.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ImageNotify {
+    /// 0x00: only the query jitter follows
+    pub payload_type: u8,
+    /// 1-100, devices draw a random number and only query if it is lower
+    pub query_jitter: u8,
+}
+cluster_command!(ImageNotify, 0x00, ServerToClient);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct QueryNextImageRequest {
+    /// Bit 0: a hardware version follows, not supported here
+    pub field_control: u8,
+    pub current: ImageId,
+}
+cluster_command!(QueryNextImageRequest, 0x01, ClientToServer);
+
+/// Only carries the image if status is success
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct QueryNextImageResponse {
+    pub status: Status,
+    pub image: Option<(ImageId, u32)>,
+}
+cluster_command!(QueryNextImageResponse, 0x02, ServerToClient);
+
+impl Serialize for QueryNextImageResponse {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        let mut s = serializer.serialize_tuple(3)?;
+        s.serialize_element(&self.status)?;
+        if let Some((image, size)) = &self.image {
+            s.serialize_element(image)?;
+            s.serialize_element(size)?;
+        }
+        s.end()
+    }
+}
+
+struct QueryNextImageResponseVisitor;
+
+impl<'de> Visitor<'de> for QueryNextImageResponseVisitor {
+    type Value = QueryNextImageResponse;
+
+    fn expecting(
+        &self,
+        formatter: &mut std::fmt::Formatter,
+    ) -> std::fmt::Result {
+        formatter.write_str("status, if success followed by image and size")
+    }
+
+    fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+    where
+        A: SeqAccess<'de>,
+    {
+        let status: Status = next(&mut seq)?;
+        let image = if status.is_success() {
+            Some((next(&mut seq)?, next(&mut seq)?))
+        } else {
+            None
+        };
+        Ok(QueryNextImageResponse { status, image })
+    }
+}
+
+impl<'de> Deserialize<'de> for QueryNextImageResponse {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        deserializer.deserialize_tuple(3, QueryNextImageResponseVisitor)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ImageBlockRequest {
+    /// Bit 0: requester address follows, bit 1: block delay follows. Neither
+    /// is supported here.
+    pub field_control: u8,
+    pub image: ImageId,
+    pub file_offset: u32,
+    pub max_data_size: u8,
+}
+cluster_command!(ImageBlockRequest, 0x03, ClientToServer);
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ImageBlockResponse {
+    Success {
+        image: ImageId,
+        file_offset: u32,
+        data: Vec<u8>,
+    },
+    WaitForData {
+        current_time: u32,
+        request_time: u32,
+        minimum_block_period: u16,
+    },
+    /// Any other status, for example abort
+    Other(Status),
+}
+cluster_command!(ImageBlockResponse, 0x05, ServerToClient);
+
+impl Serialize for ImageBlockResponse {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        let mut s = serializer.serialize_tuple(5)?;
+        match self {
+            ImageBlockResponse::Success {
+                image,
+                file_offset,
+                data,
+            } => {
+                s.serialize_element(&Status::Success)?;
+                s.serialize_element(image)?;
+                s.serialize_element(file_offset)?;
+                let len = u8::try_from(data.len()).map_err(|_| {
+                    serde::ser::Error::custom("block longer then 255 bytes")
+                })?;
+                s.serialize_element(&len)?;
+                for byte in data {
+                    s.serialize_element(byte)?;
+                }
+            }
+            ImageBlockResponse::WaitForData {
+                current_time,
+                request_time,
+                minimum_block_period,
+            } => {
+                s.serialize_element(&Status::WaitForData)?;
+                s.serialize_element(current_time)?;
+                s.serialize_element(request_time)?;
+                s.serialize_element(minimum_block_period)?;
+            }
+            ImageBlockResponse::Other(status) => s.serialize_element(status)?,
+        }
+        s.end()
+    }
+}
+
+struct ImageBlockResponseVisitor;
+
+impl<'de> Visitor<'de> for ImageBlockResponseVisitor {
+    type Value = ImageBlockResponse;
+
+    fn expecting(
+        &self,
+        formatter: &mut std::fmt::Formatter,
+    ) -> std::fmt::Result {
+        formatter.write_str("status followed by fields depending on it")
+    }
+
+    fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+    where
+        A: SeqAccess<'de>,
+    {
+        Ok(match next(&mut seq)? {
+            Status::Success => {
+                let image = next(&mut seq)?;
+                let file_offset = next(&mut seq)?;
+                let len: u8 = next(&mut seq)?;
+                let data = (0..len)
+                    .map(|_| next(&mut seq))
+                    .collect::<Result<_, _>>()?;
+                ImageBlockResponse::Success {
+                    image,
+                    file_offset,
+                    data,
+                }
+            }
+            Status::WaitForData => ImageBlockResponse::WaitForData {
+                current_time: next(&mut seq)?,
+                request_time: next(&mut seq)?,
+                minimum_block_period: next(&mut seq)?,
+            },
+            other => ImageBlockResponse::Other(other),
+        })
+    }
+}
+
+impl<'de> Deserialize<'de> for ImageBlockResponse {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        deserializer.deserialize_tuple(usize::MAX, ImageBlockResponseVisitor)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct UpgradeEndRequest {
+    pub status: Status,
+    pub image: ImageId,
+}
+cluster_command!(UpgradeEndRequest, 0x06, ClientToServer);
+
+/// Times are UTC seconds since 2000, a current time of zero means upgrade
+/// relative to now.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct UpgradeEndResponse {
+    pub image: ImageId,
+    pub current_time: u32,
+    pub upgrade_time: u32,
+}
+cluster_command!(UpgradeEndResponse, 0x07, ServerToClient);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data_format::{from_bytes, to_vec};
+
+    #[test]
+    fn image_block_response_roundtrip() {
+        let response = ImageBlockResponse::Success {
+            image: ImageId {
+                manufacturer_code: 0x117c,
+                image_type: 1,
+                file_version: 2,
+            },
+            file_offset: 64,
+            data: vec![0xde, 0xad],
+        };
+        let bytes = to_vec(&response).unwrap();
+        assert_eq!(bytes.len(), 1 + 8 + 4 + 1 + 2);
+        assert_eq!(from_bytes::<ImageBlockResponse>(&bytes).unwrap(), response);
+
+        let no_image = QueryNextImageResponse {
+            status: Status::NoImageAvailable,
+            image: None,
+        };
+        assert_eq!(to_vec(&no_image).unwrap(), [0x98]);
+    }
+}
@@ -0,0 +1,25 @@
+//! `genPowerCfg`: mains and battery information.
+
+pub const ID: crate::ClusterId = 0x0001;
+
+attribute!(
+    /// Tenths of a volt
+    MainsVoltage = 0x0000: Uint16 => u16
+);
+attribute!(MainsFrequency = 0x0001: Uint8 => u8);
+attribute!(
+    /// Tenths of a volt, 0xff if unknown
+    BatteryVoltage = 0x0020: Uint8 => u8
+);
+attribute!(
+    /// Half percent steps, so 200 is full, 0xff if unknown
+    BatteryPercentageRemaining = 0x0021: Uint8 => u8
+);
+attribute!(BatterySize = 0x0031: Enum8 => u8);
+attribute!(BatteryQuantity = 0x0033: Uint8 => u8);
+attribute!(BatteryAlarmState = 0x003e: Bitmap32 => u32);
+
+/// Converts [`BatteryPercentageRemaining`] to a percentage
+pub fn to_percent(remaining: u8) -> Option<f32> {
+    (remaining != u8::MAX).then(|| f32::from(remaining) / 2.0)
+}
@@ -0,0 +1,19 @@
+//! `msRelativeHumidity`: humidity sensors.
+
+pub const ID: crate::ClusterId = 0x0405;
+
+attribute!(
+    /// Hundredths of a percent, 0xffff if unknown
+    MeasuredValue = 0x0000: Uint16 => u16
+);
+attribute!(MinMeasuredValue = 0x0001: Uint16 => u16);
+attribute!(MaxMeasuredValue = 0x0002: Uint16 => u16);
+attribute!(Tolerance = 0x0003: Uint16 => u16);
+
+/// Value of [`MeasuredValue`] when the humidity is not known
+pub const UNKNOWN: u16 = u16::MAX;
+
+/// Converts [`MeasuredValue`] to a percentage
+pub fn to_percent(measured: u16) -> Option<f32> {
+    (measured != UNKNOWN).then(|| f32::from(measured) / 100.0)
+}
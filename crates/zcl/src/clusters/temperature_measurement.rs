@@ -0,0 +1,19 @@
+//! `msTemperatureMeasurement`: temperature sensors.
+
+pub const ID: crate::ClusterId = 0x0402;
+
+attribute!(
+    /// Hundredths of a degree Celsius, 0x8000 if unknown
+    MeasuredValue = 0x0000: Int16 => i16
+);
+attribute!(MinMeasuredValue = 0x0001: Int16 => i16);
+attribute!(MaxMeasuredValue = 0x0002: Int16 => i16);
+attribute!(Tolerance = 0x0003: Uint16 => u16);
+
+/// Value of [`MeasuredValue`] when the temperature is not known
+pub const UNKNOWN: i16 = i16::MIN;
+
+/// Converts [`MeasuredValue`] to degrees Celsius
+pub fn to_celsius(measured: i16) -> Option<f32> {
+    (measured != UNKNOWN).then(|| f32::from(measured) / 100.0)
+}
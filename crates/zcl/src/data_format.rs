@@ -0,0 +1,21 @@
+//! This is a `serde` implementation for the data format used in `ZCL`
+//! payloads.
+//!
+//! It differs from the `znp` format in a few ways:
+//!  - Sequences (`Vec`) have no length prefix. They are always the last
+//!    field of a payload and run until the end of the input.
+//!  - Tuples and tuple structs have no length prefix either. When deserializing
+//!    they end early if the input runs out, this is how optional trailing
+//!    fields are handled.
+//!  - Strings and byte slices are prefixed with an 8 bit length, just like
+//!    the ZCL character and octet string types.
+//!  - All integers (including signed) and floats are little endian.
+//!
+
+mod de;
+mod error;
+mod ser;
+
+pub use de::{Deserializer, from_bytes};
+pub use error::{Error, Result};
+pub use ser::{Serializer, to_vec};
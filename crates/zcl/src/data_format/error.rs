@@ -0,0 +1,36 @@
+use std::fmt::Display;
+use std::num::TryFromIntError;
+
+use serde::{de, ser};
+
+pub type Result<T> = std::result::Result<T, Error>;
+
+#[derive(Debug, Clone, thiserror::Error)]
+pub enum Error {
+    /// Created by data structures through the `ser::Error` and `de::Error`
+    /// traits.
+    #[error("{0}")]
+    Message(String),
+    #[error("unexpected end of input")]
+    Eof,
+    #[error("expected boolean got: 0b{0:0b}")]
+    ExpectedBoolean(u8),
+    #[error("string is not valid utf8")]
+    InvalidUtf8,
+    #[error("length does not fit in the length prefix")]
+    LenDoesNotFit(#[source] TryFromIntError),
+    #[error("the format does not support: {0}")]
+    Unsupported(&'static str),
+}
+
+impl ser::Error for Error {
+    fn custom<T: Display>(msg: T) -> Self {
+        Error::Message(msg.to_string())
+    }
+}
+
+impl de::Error for Error {
+    fn custom<T: Display>(msg: T) -> Self {
+        Error::Message(msg.to_string())
+    }
+}
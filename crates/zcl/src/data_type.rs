@@ -0,0 +1,763 @@
+//! The data types attributes can have and their values.
+
+use serde::de::{self, SeqAccess, Visitor};
+use serde::ser::{self, SerializeTuple};
+use serde::{Deserialize, Serialize};
+use serde_repr::{Deserialize_repr, Serialize_repr};
+
+/// Type identifiers from table 2-10 of the ZCL specification
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize_repr, Deserialize_repr,
+)]
+#[repr(u8)]
+pub enum DataType {
+    NoData = 0x00,
+    Data8 = 0x08,
+    Data16 = 0x09,
+    Data24 = 0x0a,
+    Data32 = 0x0b,
+    Data40 = 0x0c,
+    Data48 = 0x0d,
+    Data56 = 0x0e,
+    Data64 = 0x0f,
+    Bool = 0x10,
+    Bitmap8 = 0x18,
+    Bitmap16 = 0x19,
+    Bitmap24 = 0x1a,
+    Bitmap32 = 0x1b,
+    Bitmap40 = 0x1c,
+    Bitmap48 = 0x1d,
+    Bitmap56 = 0x1e,
+    Bitmap64 = 0x1f,
+    Uint8 = 0x20,
+    Uint16 = 0x21,
+    Uint24 = 0x22,
+    Uint32 = 0x23,
+    Uint40 = 0x24,
+    Uint48 = 0x25,
+    Uint56 = 0x26,
+    Uint64 = 0x27,
+    Int8 = 0x28,
+    Int16 = 0x29,
+    Int24 = 0x2a,
+    Int32 = 0x2b,
+    Int40 = 0x2c,
+    Int48 = 0x2d,
+    Int56 = 0x2e,
+    Int64 = 0x2f,
+    Enum8 = 0x30,
+    Enum16 = 0x31,
+    SemiFloat = 0x38,
+    Single = 0x39,
+    Double = 0x3a,
+    OctetStr = 0x41,
+    CharStr = 0x42,
+    LongOctetStr = 0x43,
+    LongCharStr = 0x44,
+    Array = 0x48,
+    Struct = 0x4c,
+    Set = 0x50,
+    Bag = 0x51,
+    TimeOfDay = 0xe0,
+    Date = 0xe1,
+    UtcTime = 0xe2,
+    ClusterId = 0xe8,
+    AttributeId = 0xe9,
+    BacOid = 0xea,
+    IeeeAddr = 0xf0,
+    SecurityKey = 0xf1,
+    Unknown = 0xff,
+}
+
+impl DataType {
+    /// Analog types have a reportable change when configuring reporting,
+    /// discrete types do not.
+    pub fn is_analog(self) -> bool {
+        use DataType as D;
+        matches!(
+            self,
+            D::Uint8
+                | D::Uint16
+                | D::Uint24
+                | D::Uint32
+                | D::Uint40
+                | D::Uint48
+                | D::Uint56
+                | D::Uint64
+                | D::Int8
+                | D::Int16
+                | D::Int24
+                | D::Int32
+                | D::Int40
+                | D::Int48
+                | D::Int56
+                | D::Int64
+                | D::SemiFloat
+                | D::Single
+                | D::Double
+                | D::TimeOfDay
+                | D::Date
+                | D::UtcTime
+        )
+    }
+
+    /// Number of bytes for fixed length types, `None` for strings and
+    /// collections.
+    pub fn fixed_len(self) -> Option<usize> {
+        use DataType as D;
+        Some(match self {
+            D::NoData | D::Unknown => 0,
+            D::Data8 | D::Bool | D::Bitmap8 | D::Uint8 | D::Int8 | D::Enum8 => {
+                1
+            }
+            D::Data16
+            | D::Bitmap16
+            | D::Uint16
+            | D::Int16
+            | D::Enum16
+            | D::SemiFloat
+            | D::ClusterId
+            | D::AttributeId => 2,
+            D::Data24 | D::Bitmap24 | D::Uint24 | D::Int24 => 3,
+            D::Data32
+            | D::Bitmap32
+            | D::Uint32
+            | D::Int32
+            | D::Single
+            | D::TimeOfDay
+            | D::Date
+            | D::UtcTime
+            | D::BacOid => 4,
+            D::Data40 | D::Bitmap40 | D::Uint40 | D::Int40 => 5,
+            D::Data48 | D::Bitmap48 | D::Uint48 | D::Int48 => 6,
+            D::Data56 | D::Bitmap56 | D::Uint56 | D::Int56 => 7,
+            D::Data64
+            | D::Bitmap64
+            | D::Uint64
+            | D::Int64
+            | D::Double
+            | D::IeeeAddr => 8,
+            D::SecurityKey => 16,
+            D::OctetStr
+            | D::CharStr
+            | D::LongOctetStr
+            | D::LongCharStr
+            | D::Array
+            | D::Struct
+            | D::Set
+            | D::Bag => return None,
+        })
+    }
+}
+
+/// The value of an attribute together with its type.
+///
+/// Fixed length types that do not map onto a rust integer (24, 40, 48 and 56
+/// bit) are stored in the next larger integer. Serializing writes the type
+/// identifier followed by the value, as used in attribute records.
+#[derive(Debug, Clone, PartialEq)]
+pub enum AttributeValue {
+    NoData,
+    Data8(u8),
+    Data16(u16),
+    Data24(u32),
+    Data32(u32),
+    Data40(u64),
+    Data48(u64),
+    Data56(u64),
+    Data64(u64),
+    Bool(bool),
+    Bitmap8(u8),
+    Bitmap16(u16),
+    Bitmap24(u32),
+    Bitmap32(u32),
+    Bitmap40(u64),
+    Bitmap48(u64),
+    Bitmap56(u64),
+    Bitmap64(u64),
+    Uint8(u8),
+    Uint16(u16),
+    Uint24(u32),
+    Uint32(u32),
+    Uint40(u64),
+    Uint48(u64),
+    Uint56(u64),
+    Uint64(u64),
+    Int8(i8),
+    Int16(i16),
+    Int24(i32),
+    Int32(i32),
+    Int40(i64),
+    Int48(i64),
+    Int56(i64),
+    Int64(i64),
+    Enum8(u8),
+    Enum16(u16),
+    /// Half precision float, kept as its raw bits
+    SemiFloat(u16),
+    Single(f32),
+    Double(f64),
+    OctetStr(Vec<u8>),
+    CharStr(String),
+    LongOctetStr(Vec<u8>),
+    LongCharStr(String),
+    /// Elements all share the given type
+    Array(DataType, Vec<AttributeValue>),
+    Struct(Vec<AttributeValue>),
+    Set(DataType, Vec<AttributeValue>),
+    Bag(DataType, Vec<AttributeValue>),
+    TimeOfDay {
+        hours: u8,
+        minutes: u8,
+        seconds: u8,
+        hundredths: u8,
+    },
+    Date {
+        /// Years since 1900
+        year: u8,
+        month: u8,
+        day: u8,
+        week_day: u8,
+    },
+    /// Seconds since 2000-01-01 00:00 UTC
+    UtcTime(u32),
+    ClusterId(u16),
+    AttributeId(u16),
+    BacOid(u32),
+    IeeeAddr(u64),
+    SecurityKey([u8; 16]),
+    Unknown,
+}
+
+impl AttributeValue {
+    pub fn data_type(&self) -> DataType {
+        use AttributeValue as V;
+        use DataType as D;
+        match self {
+            V::NoData => D::NoData,
+            V::Data8(_) => D::Data8,
+            V::Data16(_) => D::Data16,
+            V::Data24(_) => D::Data24,
+            V::Data32(_) => D::Data32,
+            V::Data40(_) => D::Data40,
+            V::Data48(_) => D::Data48,
+            V::Data56(_) => D::Data56,
+            V::Data64(_) => D::Data64,
+            V::Bool(_) => D::Bool,
+            V::Bitmap8(_) => D::Bitmap8,
+            V::Bitmap16(_) => D::Bitmap16,
+            V::Bitmap24(_) => D::Bitmap24,
+            V::Bitmap32(_) => D::Bitmap32,
+            V::Bitmap40(_) => D::Bitmap40,
+            V::Bitmap48(_) => D::Bitmap48,
+            V::Bitmap56(_) => D::Bitmap56,
+            V::Bitmap64(_) => D::Bitmap64,
+            V::Uint8(_) => D::Uint8,
+            V::Uint16(_) => D::Uint16,
+            V::Uint24(_) => D::Uint24,
+            V::Uint32(_) => D::Uint32,
+            V::Uint40(_) => D::Uint40,
+            V::Uint48(_) => D::Uint48,
+            V::Uint56(_) => D::Uint56,
+            V::Uint64(_) => D::Uint64,
+            V::Int8(_) => D::Int8,
+            V::Int16(_) => D::Int16,
+            V::Int24(_) => D::Int24,
+            V::Int32(_) => D::Int32,
+            V::Int40(_) => D::Int40,
+            V::Int48(_) => D::Int48,
+            V::Int56(_) => D::Int56,
+            V::Int64(_) => D::Int64,
+            V::Enum8(_) => D::Enum8,
+            V::Enum16(_) => D::Enum16,
+            V::SemiFloat(_) => D::SemiFloat,
+            V::Single(_) => D::Single,
+            V::Double(_) => D::Double,
+            V::OctetStr(_) => D::OctetStr,
+            V::CharStr(_) => D::CharStr,
+            V::LongOctetStr(_) => D::LongOctetStr,
+            V::LongCharStr(_) => D::LongCharStr,
+            V::Array(..) => D::Array,
+            V::Struct(_) => D::Struct,
+            V::Set(..) => D::Set,
+            V::Bag(..) => D::Bag,
+            V::TimeOfDay { .. } => D::TimeOfDay,
+            V::Date { .. } => D::Date,
+            V::UtcTime(_) => D::UtcTime,
+            V::ClusterId(_) => D::ClusterId,
+            V::AttributeId(_) => D::AttributeId,
+            V::BacOid(_) => D::BacOid,
+            V::IeeeAddr(_) => D::IeeeAddr,
+            V::SecurityKey(_) => D::SecurityKey,
+            V::Unknown => D::Unknown,
+        }
+    }
+
+    /// Any unsigned integer like value (data, bitmap, uint, enum) widened
+    /// to an u64.
+    pub fn as_u64(&self) -> Option<u64> {
+        use AttributeValue as V;
+        Some(match *self {
+            V::Data8(v) | V::Bitmap8(v) | V::Uint8(v) | V::Enum8(v) => v.into(),
+            V::Data16(v) | V::Bitmap16(v) | V::Uint16(v) | V::Enum16(v) => {
+                v.into()
+            }
+            V::Data24(v)
+            | V::Data32(v)
+            | V::Bitmap24(v)
+            | V::Bitmap32(v)
+            | V::Uint24(v)
+            | V::Uint32(v) => v.into(),
+            V::Data40(v)
+            | V::Data48(v)
+            | V::Data56(v)
+            | V::Data64(v)
+            | V::Bitmap40(v)
+            | V::Bitmap48(v)
+            | V::Bitmap56(v)
+            | V::Bitmap64(v)
+            | V::Uint40(v)
+            | V::Uint48(v)
+            | V::Uint56(v)
+            | V::Uint64(v) => v,
+            _ => return None,
+        })
+    }
+
+    /// Any signed integer widened to an i64.
+    pub fn as_i64(&self) -> Option<i64> {
+        use AttributeValue as V;
+        Some(match *self {
+            V::Int8(v) => v.into(),
+            V::Int16(v) => v.into(),
+            V::Int24(v) | V::Int32(v) => v.into(),
+            V::Int40(v) | V::Int48(v) | V::Int56(v) | V::Int64(v) => v,
+            _ => return None,
+        })
+    }
+
+    pub fn as_bool(&self) -> Option<bool> {
+        match *self {
+            AttributeValue::Bool(v) => Some(v),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            AttributeValue::CharStr(s) | AttributeValue::LongCharStr(s) => {
+                Some(s)
+            }
+            _ => None,
+        }
+    }
+
+    /// Serializes just the value, without the type identifier in front.
+    /// Used where the type is known from context, such as the reportable
+    /// change in a configure reporting record.
+    pub fn untagged(&self) -> Untagged<'_> {
+        Untagged(self)
+    }
+
+    fn write<S: SerializeTuple>(&self, s: &mut S) -> Result<(), S::Error> {
+        use AttributeValue as V;
+        match self {
+            V::NoData | V::Unknown => Ok(()),
+            V::Data8(v) | V::Bitmap8(v) | V::Uint8(v) | V::Enum8(v) => {
+                s.serialize_element(v)
+            }
+            V::Data16(v)
+            | V::Bitmap16(v)
+            | V::Uint16(v)
+            | V::Enum16(v)
+            | V::SemiFloat(v)
+            | V::ClusterId(v)
+            | V::AttributeId(v) => s.serialize_element(v),
+            V::Data32(v)
+            | V::Bitmap32(v)
+            | V::Uint32(v)
+            | V::UtcTime(v)
+            | V::BacOid(v) => s.serialize_element(v),
+            V::Data64(v) | V::Bitmap64(v) | V::Uint64(v) | V::IeeeAddr(v) => {
+                s.serialize_element(v)
+            }
+            V::Data24(v) | V::Bitmap24(v) | V::Uint24(v) => {
+                write_bytes(s, &v.to_le_bytes()[..3])
+            }
+            V::Data40(v) | V::Bitmap40(v) | V::Uint40(v) => {
+                write_bytes(s, &v.to_le_bytes()[..5])
+            }
+            V::Data48(v) | V::Bitmap48(v) | V::Uint48(v) => {
+                write_bytes(s, &v.to_le_bytes()[..6])
+            }
+            V::Data56(v) | V::Bitmap56(v) | V::Uint56(v) => {
+                write_bytes(s, &v.to_le_bytes()[..7])
+            }
+            V::Bool(v) => s.serialize_element(v),
+            V::Int8(v) => s.serialize_element(v),
+            V::Int16(v) => s.serialize_element(v),
+            V::Int32(v) => s.serialize_element(v),
+            V::Int64(v) => s.serialize_element(v),
+            V::Int24(v) => write_bytes(s, &v.to_le_bytes()[..3]),
+            V::Int40(v) => write_bytes(s, &v.to_le_bytes()[..5]),
+            V::Int48(v) => write_bytes(s, &v.to_le_bytes()[..6]),
+            V::Int56(v) => write_bytes(s, &v.to_le_bytes()[..7]),
+            V::Single(v) => s.serialize_element(v),
+            V::Double(v) => s.serialize_element(v),
+            V::OctetStr(v) => {
+                s.serialize_element(&short_len::<S>(v.len())?)?;
+                write_bytes(s, v)
+            }
+            V::CharStr(v) => {
+                s.serialize_element(&short_len::<S>(v.len())?)?;
+                write_bytes(s, v.as_bytes())
+            }
+            V::LongOctetStr(v) => {
+                s.serialize_element(&long_len::<S>(v.len())?)?;
+                write_bytes(s, v)
+            }
+            V::LongCharStr(v) => {
+                s.serialize_element(&long_len::<S>(v.len())?)?;
+                write_bytes(s, v.as_bytes())
+            }
+            V::Array(ty, elements)
+            | V::Set(ty, elements)
+            | V::Bag(ty, elements) => {
+                s.serialize_element(ty)?;
+                s.serialize_element(&long_len::<S>(elements.len())?)?;
+                for element in elements {
+                    if element.data_type() != *ty {
+                        return Err(ser::Error::custom(
+                            "collection elements must all have the same type",
+                        ));
+                    }
+                    element.write(s)?;
+                }
+                Ok(())
+            }
+            V::Struct(elements) => {
+                s.serialize_element(&long_len::<S>(elements.len())?)?;
+                for element in elements {
+                    s.serialize_element(&element.data_type())?;
+                    element.write(s)?;
+                }
+                Ok(())
+            }
+            V::TimeOfDay {
+                hours,
+                minutes,
+                seconds,
+                hundredths,
+            } => write_bytes(s, &[*hours, *minutes, *seconds, *hundredths]),
+            V::Date {
+                year,
+                month,
+                day,
+                week_day,
+            } => write_bytes(s, &[*year, *month, *day, *week_day]),
+            V::SecurityKey(key) => write_bytes(s, key),
+        }
+    }
+
+    /// Reads a value of type `ty` that is not preceded by its type
+    /// identifier.
+    pub(crate) fn read<'de, A: SeqAccess<'de>>(
+        ty: DataType,
+        seq: &mut A,
+    ) -> Result<Self, A::Error> {
+        use AttributeValue as V;
+        use DataType as D;
+        Ok(match ty {
+            D::NoData => V::NoData,
+            D::Unknown => V::Unknown,
+            D::Data8 => V::Data8(next(seq)?),
+            D::Data16 => V::Data16(next(seq)?),
+            D::Data24 => V::Data24(read_uint(seq, 3)? as u32),
+            D::Data32 => V::Data32(next(seq)?),
+            D::Data40 => V::Data40(read_uint(seq, 5)?),
+            D::Data48 => V::Data48(read_uint(seq, 6)?),
+            D::Data56 => V::Data56(read_uint(seq, 7)?),
+            D::Data64 => V::Data64(next(seq)?),
+            D::Bool => V::Bool(next(seq)?),
+            D::Bitmap8 => V::Bitmap8(next(seq)?),
+            D::Bitmap16 => V::Bitmap16(next(seq)?),
+            D::Bitmap24 => V::Bitmap24(read_uint(seq, 3)? as u32),
+            D::Bitmap32 => V::Bitmap32(next(seq)?),
+            D::Bitmap40 => V::Bitmap40(read_uint(seq, 5)?),
+            D::Bitmap48 => V::Bitmap48(read_uint(seq, 6)?),
+            D::Bitmap56 => V::Bitmap56(read_uint(seq, 7)?),
+            D::Bitmap64 => V::Bitmap64(next(seq)?),
+            D::Uint8 => V::Uint8(next(seq)?),
+            D::Uint16 => V::Uint16(next(seq)?),
+            D::Uint24 => V::Uint24(read_uint(seq, 3)? as u32),
+            D::Uint32 => V::Uint32(next(seq)?),
+            D::Uint40 => V::Uint40(read_uint(seq, 5)?),
+            D::Uint48 => V::Uint48(read_uint(seq, 6)?),
+            D::Uint56 => V::Uint56(read_uint(seq, 7)?),
+            D::Uint64 => V::Uint64(next(seq)?),
+            D::Int8 => V::Int8(next(seq)?),
+            D::Int16 => V::Int16(next(seq)?),
+            D::Int24 => V::Int24(read_int(seq, 3)? as i32),
+            D::Int32 => V::Int32(next(seq)?),
+            D::Int40 => V::Int40(read_int(seq, 5)?),
+            D::Int48 => V::Int48(read_int(seq, 6)?),
+            D::Int56 => V::Int56(read_int(seq, 7)?),
+            D::Int64 => V::Int64(next(seq)?),
+            D::Enum8 => V::Enum8(next(seq)?),
+            D::Enum16 => V::Enum16(next(seq)?),
+            D::SemiFloat => V::SemiFloat(next(seq)?),
+            D::Single => V::Single(next(seq)?),
+            D::Double => V::Double(next(seq)?),
+            D::OctetStr => {
+                let len: u8 = next(seq)?;
+                V::OctetStr(read_bytes(seq, len.into())?)
+            }
+            D::CharStr => {
+                let len: u8 = next(seq)?;
+                V::CharStr(read_string(seq, len.into())?)
+            }
+            D::LongOctetStr => {
+                let len: u16 = next(seq)?;
+                V::LongOctetStr(read_bytes(seq, len.into())?)
+            }
+            D::LongCharStr => {
+                let len: u16 = next(seq)?;
+                V::LongCharStr(read_string(seq, len.into())?)
+            }
+            D::Array | D::Set | D::Bag => {
+                let element_ty: DataType = next(seq)?;
+                let len: u16 = next(seq)?;
+                let elements = (0..len)
+                    .map(|_| Self::read(element_ty, seq))
+                    .collect::<Result<_, _>>()?;
+                match ty {
+                    D::Array => V::Array(element_ty, elements),
+                    D::Set => V::Set(element_ty, elements),
+                    _ => V::Bag(element_ty, elements),
+                }
+            }
+            D::Struct => {
+                let len: u16 = next(seq)?;
+                let elements = (0..len)
+                    .map(|_| {
+                        let ty = next(seq)?;
+                        Self::read(ty, seq)
+                    })
+                    .collect::<Result<_, _>>()?;
+                V::Struct(elements)
+            }
+            D::TimeOfDay => {
+                let [hours, minutes, seconds, hundredths] = read_array(seq)?;
+                V::TimeOfDay {
+                    hours,
+                    minutes,
+                    seconds,
+                    hundredths,
+                }
+            }
+            D::Date => {
+                let [year, month, day, week_day] = read_array(seq)?;
+                V::Date {
+                    year,
+                    month,
+                    day,
+                    week_day,
+                }
+            }
+            D::UtcTime => V::UtcTime(next(seq)?),
+            D::ClusterId => V::ClusterId(next(seq)?),
+            D::AttributeId => V::AttributeId(next(seq)?),
+            D::BacOid => V::BacOid(next(seq)?),
+            D::IeeeAddr => V::IeeeAddr(next(seq)?),
+            D::SecurityKey => V::SecurityKey(read_array(seq)?),
+        })
+    }
+}
+
+fn short_len<S: SerializeTuple>(len: usize) -> Result<u8, S::Error> {
+    u8::try_from(len)
+        .map_err(|_| ser::Error::custom("string longer then 255 bytes"))
+}
+
+fn long_len<S: SerializeTuple>(len: usize) -> Result<u16, S::Error> {
+    u16::try_from(len)
+        .map_err(|_| ser::Error::custom("more then u16::MAX elements"))
+}
+
+fn write_bytes<S: SerializeTuple>(
+    s: &mut S,
+    bytes: &[u8],
+) -> Result<(), S::Error> {
+    for byte in bytes {
+        s.serialize_element(byte)?;
+    }
+    Ok(())
+}
+
+pub(crate) fn next<'de, T, A>(seq: &mut A) -> Result<T, A::Error>
+where
+    T: Deserialize<'de>,
+    A: SeqAccess<'de>,
+{
+    seq.next_element()?
+        .ok_or_else(|| de::Error::custom("unexpected end of input"))
+}
+
+fn read_bytes<'de, A: SeqAccess<'de>>(
+    seq: &mut A,
+    len: usize,
+) -> Result<Vec<u8>, A::Error> {
+    (0..len).map(|_| next(seq)).collect()
+}
+
+fn read_array<'de, A: SeqAccess<'de>, const N: usize>(
+    seq: &mut A,
+) -> Result<[u8; N], A::Error> {
+    let mut bytes = [0u8; N];
+    for byte in &mut bytes {
+        *byte = next(seq)?;
+    }
+    Ok(bytes)
+}
+
+fn read_string<'de, A: SeqAccess<'de>>(
+    seq: &mut A,
+    len: usize,
+) -> Result<String, A::Error> {
+    String::from_utf8(read_bytes(seq, len)?)
+        .map_err(|_| de::Error::custom("string is not valid utf8"))
+}
+
+fn read_uint<'de, A: SeqAccess<'de>>(
+    seq: &mut A,
+    len: usize,
+) -> Result<u64, A::Error> {
+    let mut bytes = [0u8; 8];
+    for byte in &mut bytes[..len] {
+        *byte = next(seq)?;
+    }
+    Ok(u64::from_le_bytes(bytes))
+}
+
+/// Sign extends the `len` byte integer
+fn read_int<'de, A: SeqAccess<'de>>(
+    seq: &mut A,
+    len: usize,
+) -> Result<i64, A::Error> {
+    let unused_bits = 64 - 8 * len as u32;
+    let raw = read_uint(seq, len)?;
+    Ok(((raw << unused_bits) as i64) >> unused_bits)
+}
+
+impl Serialize for AttributeValue {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        // The length is not known up front, the zcl format does not write
+        // it anyway.
+        let mut s = serializer.serialize_tuple(usize::MAX)?;
+        s.serialize_element(&self.data_type())?;
+        self.write(&mut s)?;
+        s.end()
+    }
+}
+
+/// See [`AttributeValue::untagged`]
+pub struct Untagged<'a>(&'a AttributeValue);
+
+impl Serialize for Untagged<'_> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        let mut s = serializer.serialize_tuple(usize::MAX)?;
+        self.0.write(&mut s)?;
+        s.end()
+    }
+}
+
+struct TaggedVisitor;
+
+impl<'de> Visitor<'de> for TaggedVisitor {
+    type Value = AttributeValue;
+
+    fn expecting(
+        &self,
+        formatter: &mut std::fmt::Formatter,
+    ) -> std::fmt::Result {
+        formatter.write_str("a data type identifier followed by a value")
+    }
+
+    fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+    where
+        A: SeqAccess<'de>,
+    {
+        let ty = next(&mut seq)?;
+        AttributeValue::read(ty, &mut seq)
+    }
+}
+
+impl<'de> Deserialize<'de> for AttributeValue {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        deserializer.deserialize_tuple(usize::MAX, TaggedVisitor)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data_format::{from_bytes, to_vec};
+
+    fn roundtrip(value: AttributeValue, expected: &[u8]) {
+        let bytes = to_vec(&value).unwrap();
+        assert_eq!(bytes, expected, "encoding {value:?}");
+        let decoded: AttributeValue = from_bytes(&bytes).unwrap();
+        assert_eq!(decoded, value);
+    }
+
+    #[test]
+    fn integers() {
+        roundtrip(AttributeValue::Uint8(7), &[0x20, 7]);
+        roundtrip(AttributeValue::Int16(-2), &[0x29, 0xfe, 0xff]);
+        roundtrip(AttributeValue::Uint24(0x010203), &[0x22, 3, 2, 1]);
+        roundtrip(AttributeValue::Int24(-1), &[0x2a, 0xff, 0xff, 0xff]);
+        roundtrip(
+            AttributeValue::Int40(-300),
+            &[0x2c, 0xd4, 0xfe, 0xff, 0xff, 0xff],
+        );
+    }
+
+    #[test]
+    fn strings() {
+        roundtrip(
+            AttributeValue::CharStr("IKEA".to_owned()),
+            &[0x42, 4, b'I', b'K', b'E', b'A'],
+        );
+        roundtrip(
+            AttributeValue::LongOctetStr(vec![1, 2]),
+            &[0x43, 2, 0, 1, 2],
+        );
+    }
+
+    #[test]
+    fn collections() {
+        roundtrip(
+            AttributeValue::Array(
+                DataType::Uint16,
+                vec![AttributeValue::Uint16(1), AttributeValue::Uint16(2)],
+            ),
+            &[0x48, 0x21, 2, 0, 1, 0, 2, 0],
+        );
+        roundtrip(
+            AttributeValue::Struct(vec![
+                AttributeValue::Bool(true),
+                AttributeValue::Enum8(3),
+            ]),
+            &[0x4c, 2, 0, 0x10, 1, 0x30, 3],
+        );
+    }
+}
@@ -0,0 +1,258 @@
+//! The ZCL frame: a header followed by the command payload.
+
+use serde::de::{SeqAccess, Visitor};
+use serde::ser::SerializeTuple;
+use serde::{Deserialize, Serialize};
+
+use crate::Command;
+use crate::data_format::{self, Deserializer};
+use crate::data_type::next;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FrameType {
+    /// Command acts across the entire profile, for example reading attributes
+    Global = 0b00,
+    /// Command is specific to the cluster the frame is send on
+    ClusterSpecific = 0b01,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Direction {
+    ClientToServer,
+    ServerToClient,
+}
+
+/// The first byte of every header, the manufacturer specific flag is
+/// derived from [`Header::manufacturer_code`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FrameControl {
+    pub frame_type: FrameType,
+    pub direction: Direction,
+    /// Ask the receiver not to send a default response when the command
+    /// succeeded.
+    pub disable_default_response: bool,
+}
+
+const FRAME_TYPE_MASK: u8 = 0b0000_0011;
+const MANUFACTURER_SPECIFIC: u8 = 0b0000_0100;
+const SERVER_TO_CLIENT: u8 = 0b0000_1000;
+const DISABLE_DEFAULT_RESPONSE: u8 = 0b0001_0000;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Header {
+    pub frame_control: FrameControl,
+    /// Set for manufacturer specific commands and attributes
+    pub manufacturer_code: Option<u16>,
+    /// Replies carry the sequence number of the request
+    pub sequence_number: u8,
+    pub command_id: u8,
+}
+
+impl Serialize for Header {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        let FrameControl {
+            frame_type,
+            direction,
+            disable_default_response,
+        } = self.frame_control;
+        let mut control = frame_type as u8;
+        if self.manufacturer_code.is_some() {
+            control |= MANUFACTURER_SPECIFIC;
+        }
+        if direction == Direction::ServerToClient {
+            control |= SERVER_TO_CLIENT;
+        }
+        if disable_default_response {
+            control |= DISABLE_DEFAULT_RESPONSE;
+        }
+
+        let mut s = serializer.serialize_tuple(5)?;
+        s.serialize_element(&control)?;
+        if let Some(code) = self.manufacturer_code {
+            s.serialize_element(&code)?;
+        }
+        s.serialize_element(&self.sequence_number)?;
+        s.serialize_element(&self.command_id)?;
+        s.end()
+    }
+}
+
+struct HeaderVisitor;
+
+impl<'de> Visitor<'de> for HeaderVisitor {
+    type Value = Header;
+
+    fn expecting(
+        &self,
+        formatter: &mut std::fmt::Formatter,
+    ) -> std::fmt::Result {
+        formatter.write_str("a zcl frame header")
+    }
+
+    fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+    where
+        A: SeqAccess<'de>,
+    {
+        let control: u8 = next(&mut seq)?;
+        let frame_type = match control & FRAME_TYPE_MASK {
+            0b00 => FrameType::Global,
+            0b01 => FrameType::ClusterSpecific,
+            other => {
+                return Err(serde::de::Error::custom(format!(
+                    "reserved frame type: {other}"
+                )));
+            }
+        };
+        let direction = if control & SERVER_TO_CLIENT == 0 {
+            Direction::ClientToServer
+        } else {
+            Direction::ServerToClient
+        };
+        let manufacturer_code = if control & MANUFACTURER_SPECIFIC == 0 {
+            None
+        } else {
+            Some(next(&mut seq)?)
+        };
+
+        Ok(Header {
+            frame_control: FrameControl {
+                frame_type,
+                direction,
+                disable_default_response: control & DISABLE_DEFAULT_RESPONSE
+                    != 0,
+            },
+            manufacturer_code,
+            sequence_number: next(&mut seq)?,
+            command_id: next(&mut seq)?,
+        })
+    }
+}
+
+impl<'de> Deserialize<'de> for Header {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        deserializer.deserialize_tuple(5, HeaderVisitor)
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum ParseError {
+    #[error(
+        "Frame does not contain the expected command, expected id: \
+        {expected}, got: {got}"
+    )]
+    WrongCommand { expected: u8, got: u8 },
+    #[error("Could not decode the command payload")]
+    Decoding(#[source] data_format::Error),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Frame {
+    pub header: Header,
+    pub payload: Vec<u8>,
+}
+
+impl Frame {
+    pub fn new<C: Command>(
+        sequence_number: u8,
+        command: &C,
+    ) -> Result<Self, data_format::Error> {
+        Ok(Self {
+            header: Header {
+                frame_control: FrameControl {
+                    frame_type: C::FRAME_TYPE,
+                    direction: C::DIRECTION,
+                    disable_default_response: false,
+                },
+                manufacturer_code: None,
+                sequence_number,
+                command_id: C::ID,
+            },
+            payload: data_format::to_vec(command)?,
+        })
+    }
+
+    /// Makes the command manufacturer specific
+    pub fn with_manufacturer_code(mut self, code: u16) -> Self {
+        self.header.manufacturer_code = Some(code);
+        self
+    }
+
+    pub fn with_direction(mut self, direction: Direction) -> Self {
+        self.header.frame_control.direction = direction;
+        self
+    }
+
+    pub fn without_default_response(mut self) -> Self {
+        self.header.frame_control.disable_default_response = true;
+        self
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, data_format::Error> {
+        let mut deserializer = Deserializer::from_bytes(bytes);
+        let header = Header::deserialize(&mut deserializer)?;
+        Ok(Self {
+            header,
+            payload: deserializer.remaining().to_vec(),
+        })
+    }
+
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = data_format::to_vec(&self.header)
+            .expect("header only contains integers");
+        bytes.extend_from_slice(&self.payload);
+        bytes
+    }
+
+    /// Whether this frame carries command `C`. Global commands are the same
+    /// in both directions, cluster specific command ids depend on the
+    /// direction.
+    pub fn is<C: Command>(&self) -> bool {
+        let control = self.header.frame_control;
+        control.frame_type == C::FRAME_TYPE
+            && self.header.command_id == C::ID
+            && (C::FRAME_TYPE == FrameType::Global
+                || control.direction == C::DIRECTION)
+    }
+
+    pub fn parse<C: Command>(&self) -> Result<C, ParseError> {
+        if !self.is::<C>() {
+            return Err(ParseError::WrongCommand {
+                expected: C::ID,
+                got: self.header.command_id,
+            });
+        }
+        data_format::from_bytes(&self.payload).map_err(ParseError::Decoding)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn manufacturer_specific_header() {
+        let bytes = [0b0001_1101, 0x7c, 0x11, 42, 0x0a, 0xff];
+        let frame = Frame::from_bytes(&bytes).unwrap();
+        assert_eq!(
+            frame.header,
+            Header {
+                frame_control: FrameControl {
+                    frame_type: FrameType::ClusterSpecific,
+                    direction: Direction::ServerToClient,
+                    disable_default_response: true,
+                },
+                manufacturer_code: Some(0x117c),
+                sequence_number: 42,
+                command_id: 0x0a,
+            }
+        );
+        assert_eq!(frame.payload, [0xff]);
+        assert_eq!(frame.to_bytes(), bytes);
+    }
+}
@@ -0,0 +1,570 @@
+//! Commands that act on every cluster, chapter 2.5 of the ZCL specification.
+
+use serde::de::{SeqAccess, Visitor};
+use serde::ser::SerializeTuple;
+use serde::{Deserialize, Serialize};
+use serde_repr::{Deserialize_repr, Serialize_repr};
+
+use crate::data_type::next;
+use crate::{AttributeId, AttributeValue, Command, DataType, Direction};
+use crate::{FrameType, frame};
+
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize_repr, Deserialize_repr,
+)]
+#[repr(u8)]
+pub enum Status {
+    Success = 0x00,
+    Failure = 0x01,
+    NotAuthorized = 0x7e,
+    ReservedFieldNotZero = 0x7f,
+    MalformedCommand = 0x80,
+    UnsupClusterCommand = 0x81,
+    UnsupGeneralCommand = 0x82,
+    UnsupManufClusterCommand = 0x83,
+    UnsupManufGeneralCommand = 0x84,
+    InvalidField = 0x85,
+    UnsupportedAttribute = 0x86,
+    InvalidValue = 0x87,
+    ReadOnly = 0x88,
+    InsufficientSpace = 0x89,
+    DuplicateExists = 0x8a,
+    NotFound = 0x8b,
+    UnreportableAttribute = 0x8c,
+    InvalidDataType = 0x8d,
+    InvalidSelector = 0x8e,
+    WriteOnly = 0x8f,
+    InconsistentStartupState = 0x90,
+    DefinedOutOfBand = 0x91,
+    Inconsistent = 0x92,
+    ActionDenied = 0x93,
+    Timeout = 0x94,
+    Abort = 0x95,
+    InvalidImage = 0x96,
+    WaitForData = 0x97,
+    NoImageAvailable = 0x98,
+    RequireMoreImage = 0x99,
+    NotificationPending = 0x9a,
+    HardwareFailure = 0xc0,
+    SoftwareFailure = 0xc1,
+    CalibrationError = 0xc2,
+    UnsupportedCluster = 0xc3,
+}
+
+impl Status {
+    pub fn is_success(self) -> bool {
+        self == Status::Success
+    }
+}
+
+macro_rules! global_command {
+    ($ty:ty, $id:literal, $direction:ident) => {
+        impl Command for $ty {
+            const ID: u8 = $id;
+            const FRAME_TYPE: FrameType = FrameType::Global;
+            const DIRECTION: Direction = Direction::$direction;
+        }
+    };
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ReadAttributes {
+    pub attributes: Vec<AttributeId>,
+}
+global_command!(ReadAttributes, 0x00, ClientToServer);
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ReadAttributesResponse {
+    pub records: Vec<ReadAttributeStatus>,
+}
+global_command!(ReadAttributesResponse, 0x01, ServerToClient);
+
+/// The value is only present if reading the attribute succeeded
+#[derive(Debug, Clone, PartialEq)]
+pub struct ReadAttributeStatus {
+    pub id: AttributeId,
+    pub value: Result<AttributeValue, Status>,
+}
+
+impl Serialize for ReadAttributeStatus {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        let mut s = serializer.serialize_tuple(3)?;
+        s.serialize_element(&self.id)?;
+        match &self.value {
+            Ok(value) => {
+                s.serialize_element(&Status::Success)?;
+                s.serialize_element(value)?;
+            }
+            Err(status) => s.serialize_element(status)?,
+        }
+        s.end()
+    }
+}
+
+struct ReadAttributeStatusVisitor;
+
+impl<'de> Visitor<'de> for ReadAttributeStatusVisitor {
+    type Value = ReadAttributeStatus;
+
+    fn expecting(
+        &self,
+        formatter: &mut std::fmt::Formatter,
+    ) -> std::fmt::Result {
+        formatter.write_str("attribute id, status and if successful a value")
+    }
+
+    fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+    where
+        A: SeqAccess<'de>,
+    {
+        let id = next(&mut seq)?;
+        let status: Status = next(&mut seq)?;
+        let value = if status.is_success() {
+            Ok(next(&mut seq)?)
+        } else {
+            Err(status)
+        };
+        Ok(ReadAttributeStatus { id, value })
+    }
+}
+
+impl<'de> Deserialize<'de> for ReadAttributeStatus {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        deserializer.deserialize_tuple(3, ReadAttributeStatusVisitor)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct WriteAttributeRecord {
+    pub id: AttributeId,
+    pub value: AttributeValue,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct WriteAttributes {
+    pub records: Vec<WriteAttributeRecord>,
+}
+global_command!(WriteAttributes, 0x02, ClientToServer);
+
+/// Only writes the attributes if all of them can be written
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct WriteAttributesUndivided {
+    pub records: Vec<WriteAttributeRecord>,
+}
+global_command!(WriteAttributesUndivided, 0x03, ClientToServer);
+
+/// Only lists the attributes that failed. If all attributes were written
+/// there is a single record with status success and no attribute id.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct WriteAttributesResponse {
+    pub records: Vec<WriteAttributeStatus>,
+}
+global_command!(WriteAttributesResponse, 0x04, ServerToClient);
+
+impl WriteAttributesResponse {
+    pub fn all_succeeded(&self) -> bool {
+        self.records.iter().all(|record| record.status.is_success())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct WriteAttributeStatus {
+    pub status: Status,
+    /// Missing in the record signalling all writes succeeded
+    pub id: Option<AttributeId>,
+}
+
+impl Serialize for WriteAttributeStatus {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        let mut s = serializer.serialize_tuple(2)?;
+        s.serialize_element(&self.status)?;
+        if let Some(id) = self.id {
+            s.serialize_element(&id)?;
+        }
+        s.end()
+    }
+}
+
+struct WriteAttributeStatusVisitor;
+
+impl<'de> Visitor<'de> for WriteAttributeStatusVisitor {
+    type Value = WriteAttributeStatus;
+
+    fn expecting(
+        &self,
+        formatter: &mut std::fmt::Formatter,
+    ) -> std::fmt::Result {
+        formatter.write_str("status optionally followed by an attribute id")
+    }
+
+    fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+    where
+        A: SeqAccess<'de>,
+    {
+        Ok(WriteAttributeStatus {
+            status: next(&mut seq)?,
+            id: seq.next_element()?,
+        })
+    }
+}
+
+impl<'de> Deserialize<'de> for WriteAttributeStatus {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        deserializer.deserialize_tuple(2, WriteAttributeStatusVisitor)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct WriteAttributesNoResponse {
+    pub records: Vec<WriteAttributeRecord>,
+}
+global_command!(WriteAttributesNoResponse, 0x05, ClientToServer);
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ConfigureReporting {
+    pub records: Vec<ReportingConfiguration>,
+}
+global_command!(ConfigureReporting, 0x06, ClientToServer);
+
+const REPORTED: u8 = 0x00;
+const RECEIVED: u8 = 0x01;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ReportingConfiguration {
+    /// The receiver of the command should report the attribute
+    Report {
+        id: AttributeId,
+        data_type: DataType,
+        /// Seconds
+        min_interval: u16,
+        /// Seconds, 0xffff disables periodic reports
+        max_interval: u16,
+        /// Only for analog data types, the change that triggers a report.
+        /// Must be of type `data_type`.
+        reportable_change: Option<AttributeValue>,
+    },
+    /// The receiver of the command should expect reports about the attribute
+    Expect {
+        id: AttributeId,
+        /// Seconds, the receiver may raise an alarm if no report arrived
+        /// in that time.
+        timeout: u16,
+    },
+}
+
+impl Serialize for ReportingConfiguration {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        let mut s = serializer.serialize_tuple(6)?;
+        match self {
+            ReportingConfiguration::Report {
+                id,
+                data_type,
+                min_interval,
+                max_interval,
+                reportable_change,
+            } => {
+                s.serialize_element(&REPORTED)?;
+                s.serialize_element(id)?;
+                s.serialize_element(data_type)?;
+                s.serialize_element(min_interval)?;
+                s.serialize_element(max_interval)?;
+                match (data_type.is_analog(), reportable_change) {
+                    (true, Some(change)) => {
+                        s.serialize_element(&change.untagged())?
+                    }
+                    (false, None) => (),
+                    (true, None) => {
+                        return Err(serde::ser::Error::custom(
+                            "analog attributes need a reportable change",
+                        ));
+                    }
+                    (false, Some(_)) => {
+                        return Err(serde::ser::Error::custom(
+                            "discrete attributes have no reportable change",
+                        ));
+                    }
+                }
+            }
+            ReportingConfiguration::Expect { id, timeout } => {
+                s.serialize_element(&RECEIVED)?;
+                s.serialize_element(id)?;
+                s.serialize_element(timeout)?;
+            }
+        }
+        s.end()
+    }
+}
+
+struct ReportingConfigurationVisitor;
+
+impl<'de> Visitor<'de> for ReportingConfigurationVisitor {
+    type Value = ReportingConfiguration;
+
+    fn expecting(
+        &self,
+        formatter: &mut std::fmt::Formatter,
+    ) -> std::fmt::Result {
+        formatter.write_str("an attribute reporting configuration record")
+    }
+
+    fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+    where
+        A: SeqAccess<'de>,
+    {
+        let direction: u8 = next(&mut seq)?;
+        let id = next(&mut seq)?;
+        match direction {
+            REPORTED => {
+                let data_type: DataType = next(&mut seq)?;
+                Ok(ReportingConfiguration::Report {
+                    id,
+                    data_type,
+                    min_interval: next(&mut seq)?,
+                    max_interval: next(&mut seq)?,
+                    reportable_change: if data_type.is_analog() {
+                        Some(AttributeValue::read(data_type, &mut seq)?)
+                    } else {
+                        None
+                    },
+                })
+            }
+            RECEIVED => Ok(ReportingConfiguration::Expect {
+                id,
+                timeout: next(&mut seq)?,
+            }),
+            other => Err(serde::de::Error::custom(format!(
+                "unknown reporting direction: {other}"
+            ))),
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for ReportingConfiguration {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        deserializer.deserialize_tuple(6, ReportingConfigurationVisitor)
+    }
+}
+
+/// Like [`WriteAttributesResponse`] only failed records are listed. If all
+/// succeeded there is a single record with just status success.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ConfigureReportingResponse {
+    pub records: Vec<ConfigureReportingStatus>,
+}
+global_command!(ConfigureReportingResponse, 0x07, ServerToClient);
+
+impl ConfigureReportingResponse {
+    pub fn all_succeeded(&self) -> bool {
+        self.records.iter().all(|record| record.status.is_success())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ConfigureReportingStatus {
+    pub status: Status,
+    /// Whether the attribute was to be reported (`false`) or its reports
+    /// received (`true`) and its id. Missing if all records succeeded.
+    pub attribute: Option<(bool, AttributeId)>,
+}
+
+impl Serialize for ConfigureReportingStatus {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        let mut s = serializer.serialize_tuple(3)?;
+        s.serialize_element(&self.status)?;
+        if let Some((received, id)) = self.attribute {
+            s.serialize_element(&received)?;
+            s.serialize_element(&id)?;
+        }
+        s.end()
+    }
+}
+
+struct ConfigureReportingStatusVisitor;
+
+impl<'de> Visitor<'de> for ConfigureReportingStatusVisitor {
+    type Value = ConfigureReportingStatus;
+
+    fn expecting(
+        &self,
+        formatter: &mut std::fmt::Formatter,
+    ) -> std::fmt::Result {
+        formatter.write_str("status optionally followed by direction and id")
+    }
+
+    fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+    where
+        A: SeqAccess<'de>,
+    {
+        let status = next(&mut seq)?;
+        let attribute = match seq.next_element()? {
+            Some(received) => Some((received, next(&mut seq)?)),
+            None => None,
+        };
+        Ok(ConfigureReportingStatus { status, attribute })
+    }
+}
+
+impl<'de> Deserialize<'de> for ConfigureReportingStatus {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        deserializer.deserialize_tuple(3, ConfigureReportingStatusVisitor)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AttributeReport {
+    pub id: AttributeId,
+    pub value: AttributeValue,
+}
+
+/// Send by a device when a reported attribute changed or its maximum
+/// reporting interval passed.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ReportAttributes {
+    pub reports: Vec<AttributeReport>,
+}
+global_command!(ReportAttributes, 0x0a, ServerToClient);
+
+/// Reply to a command that has no specific response, unless the sender
+/// disabled it in the [`FrameControl`](crate::FrameControl). Errors are
+/// always reported.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DefaultResponse {
+    pub command_id: u8,
+    pub status: Status,
+}
+global_command!(DefaultResponse, 0x0b, ServerToClient);
+
+impl DefaultResponse {
+    /// The response to the command in `frame`
+    pub fn reply_to(frame: &frame::Header, status: Status) -> Self {
+        Self {
+            command_id: frame.command_id,
+            status,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DiscoverAttributes {
+    pub start_attribute: AttributeId,
+    pub max_attributes: u8,
+}
+global_command!(DiscoverAttributes, 0x0c, ClientToServer);
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DiscoverAttributesResponse {
+    /// No more attributes to discover
+    pub complete: bool,
+    pub attributes: Vec<AttributeInfo>,
+}
+global_command!(DiscoverAttributesResponse, 0x0d, ServerToClient);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AttributeInfo {
+    pub id: AttributeId,
+    pub data_type: DataType,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Frame;
+
+    #[test]
+    fn read_attributes_response() {
+        let bytes = [
+            0x18, 7, 0x01, // header
+            0x04, 0x00, 0x00, 0x42, 4, b'I', b'K', b'E', b'A', // vendor
+            0x05, 0x00, 0x86, // model, unsupported
+        ];
+        let frame = Frame::from_bytes(&bytes).unwrap();
+        let response: ReadAttributesResponse = frame.parse().unwrap();
+        assert_eq!(
+            response.records,
+            [
+                ReadAttributeStatus {
+                    id: 4,
+                    value: Ok(AttributeValue::CharStr("IKEA".to_owned())),
+                },
+                ReadAttributeStatus {
+                    id: 5,
+                    value: Err(Status::UnsupportedAttribute),
+                },
+            ]
+        );
+        let encoded =
+            Frame::new(7, &response).unwrap().without_default_response();
+        assert_eq!(encoded.to_bytes(), bytes);
+    }
+
+    #[test]
+    fn all_writes_succeeded() {
+        let response: WriteAttributesResponse =
+            crate::data_format::from_bytes(&[0x00]).unwrap();
+        assert_eq!(
+            response.records,
+            [WriteAttributeStatus {
+                status: Status::Success,
+                id: None
+            }]
+        );
+        assert!(response.all_succeeded());
+    }
+
+    #[test]
+    fn configure_reporting_roundtrip() {
+        let command = ConfigureReporting {
+            records: vec![
+                ReportingConfiguration::Report {
+                    id: 0x0000,
+                    data_type: DataType::Int16,
+                    min_interval: 10,
+                    max_interval: 3600,
+                    reportable_change: Some(AttributeValue::Int16(50)),
+                },
+                ReportingConfiguration::Report {
+                    id: 0x0000,
+                    data_type: DataType::Bool,
+                    min_interval: 0,
+                    max_interval: 300,
+                    reportable_change: None,
+                },
+            ],
+        };
+        let bytes = crate::data_format::to_vec(&command).unwrap();
+        assert_eq!(
+            bytes,
+            [
+                0, 0, 0, 0x29, 10, 0, 0x10, 0x0e, 50, 0, //
+                0, 0, 0, 0x10, 0, 0, 0x2c, 0x01,
+            ]
+        );
+        let decoded: ConfigureReporting =
+            crate::data_format::from_bytes(&bytes).unwrap();
+        assert_eq!(decoded, command);
+    }
+}
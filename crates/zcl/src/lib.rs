@@ -0,0 +1,48 @@
+//! Encoding and decoding of Zigbee Cluster Library (ZCL) frames. These are
+//! the payload of application framework (AF) messages.
+//!
+//! From: `ZigBee Cluster Library Specification` revision 6 (14/01/2016)
+
+pub mod clusters;
+pub mod data_format;
+pub mod data_type;
+pub mod frame;
+pub mod global;
+
+pub use data_type::{AttributeValue, DataType};
+pub use frame::{
+    Direction, Frame, FrameControl, FrameType, Header, ParseError,
+};
+pub use global::Status;
+
+/// A ZCL command, global or specific to a cluster
+pub trait Command:
+    serde::Serialize + serde::de::DeserializeOwned + std::fmt::Debug
+{
+    const ID: u8;
+    const FRAME_TYPE: FrameType;
+    const DIRECTION: Direction;
+}
+
+/// A command that only exists on one cluster
+pub trait ClusterCommand: Command {
+    const CLUSTER: ClusterId;
+}
+
+/// Identifies an attribute within a cluster
+pub type AttributeId = u16;
+
+/// Identifies a cluster, see [`clusters`] for the ones we know about
+pub type ClusterId = u16;
+
+/// An attribute of a cluster along with the rust type of its value
+pub trait Attribute {
+    const CLUSTER: ClusterId;
+    const ID: AttributeId;
+    const DATA_TYPE: DataType;
+    type Value;
+
+    /// `None` if the value does not have [`Self::DATA_TYPE`]
+    fn from_value(value: AttributeValue) -> Option<Self::Value>;
+    fn to_value(value: Self::Value) -> AttributeValue;
+}
@@ -15,6 +15,13 @@ pub use command_types::{
 #[cfg(feature = "mocking")]
 pub use command_types::to_frame;
 
+mod key;
+pub use key::Key;
+mod long_data;
+pub use long_data::LongData;
+mod shared_responses;
+pub use shared_responses::Status;
+
 pub mod af;
 pub mod app;
 pub mod appconfig;
@@ -35,6 +42,7 @@ pub enum BasicStatus {
 }
 
 impl BasicStatus {
+    #[allow(clippy::result_unit_err)]
     pub fn as_result(self) -> Result<(), ()> {
         match self {
             BasicStatus::Ok => Ok(()),
@@ -259,6 +267,7 @@ impl std::fmt::Display for ShortAddr {
     }
 }
 
+#[deprecated(note = "endpoints are plain `u8` fields in every command")]
 #[derive(Debug, Clone, Copy, Serialize, Deserialize)]
 pub struct Endpoint(u8);
 
@@ -282,14 +291,3 @@ impl<T> PartialList<T> {
         self.offset_in_total + self.list.len() as u8
     }
 }
-
-/// See Z-Stack Monitor and Test API section 3.12.2.18 revision 1.14
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct BindTable {
-    pub src: IeeeAddr,
-    pub src_endpoint: Endpoint,
-    pub cluster_id: u8,
-    pub dst_addr_mode: u8,
-    pub dst: IeeeAddr,
-    pub dst_endpoint: Endpoint,
-}
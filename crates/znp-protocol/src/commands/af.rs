@@ -1,7 +1,15 @@
-use serde::{Deserialize, Serialize};
+use std::ops::BitOr;
+use std::time::Duration;
+
+use serde::de::{IntoDeserializer, SeqAccess, Visitor};
+use serde::ser::SerializeStruct;
+use serde::{Deserialize, Serialize, de};
 use serde_repr::Serialize_repr;
 
-use super::{SubSystem, SyncReply, SyncRequest, basic_reply};
+use super::{
+    AsyncNotify, AsyncReply, AsyncRequest, IeeeAddr, LongData, Pattern,
+    ShortAddr, Status, SubSystem, SyncReply, SyncRequest, basic_reply,
+};
 
 #[derive(Debug, Clone, Serialize_repr)]
 #[repr(u8)]
@@ -11,7 +19,7 @@ pub enum LatencyRequirement {
     SlowBeacons = 2,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub struct ClusterId(pub u16);
 
 #[derive(Debug, Clone, Serialize)]
@@ -33,72 +41,326 @@ impl SyncRequest for Register {
 
 basic_reply! {Register, RegisterReply}
 
-// #[derive(Debug, Clone, Serialize)]
-// pub struct DataRequest {
-//     pub dstaddr: u16,
-//     pub destendpoint: u8,
-//     pub srcendpoint: u8,
-//     pub clusterid: u16,
-//     pub transid: u8,
-//     pub options: u8,
-//     pub radius: u8,
-//     pub len: u8,
-//     pub data: Vec<u8>,
-// }
-//
-// impl SyncRequest for DataRequest {
-//     const ID: u8 = 1;
-//     const SUBSYSTEM: SubSystem = SubSystem::Af;
-//     type Reply = DataRequestReply;
-// }
-//
-// basic_reply! { DataRequest, DataRequestReply }
-//
-// #[derive(Debug, Clone, Serialize)]
-// pub struct DataRequestExt {
-//     pub dstaddrmode: u8,
-//     pub dstaddr: IeeeAddr,
-//     pub destendpoint: u8,
-//     pub dstpanid: u16,
-//     pub srcendpoint: u8,
-//     pub clusterid: u16,
-//     pub transid: u8,
-//     pub options: u8,
-//     pub radius: u8,
-//     pub len: u16,
-//     pub data: Vec<u8>,
-// }
-//
-// impl SyncRequest for DataRequestExt {
-//     const ID: u8 = 2;
-//     const SUBSYSTEM: SubSystem = SubSystem::Af;
-//     type Reply = DataRequestExtReply;
-// }
-//
-// basic_reply! { DataRequestExt, DataRequestExtReply }
-//
-// #[derive(Debug, Clone, Serialize)]
-// pub struct DataRequestSrcRtg {
-//     pub dstaddr: u16,
-//     pub destendpoint: u8,
-//     pub srcendpoint: u8,
-//     pub clusterid: u16,
-//     pub transid: u8,
-//     pub options: u8,
-//     pub radius: u8,
-//     pub relaylist: Vec<u16>,
-//     pub len: u8,
-//     pub data: Vec<u8>,
-// }
-//
-// impl SyncRequest for DataRequestSrcRtg {
-//     const ID: u8 = 3;
-//     const SUBSYSTEM: SubSystem = SubSystem::Af;
-//     type Reply = DataRequestSrcRtgReply;
-// }
-//
-// basic_reply! { DataRequestSrcRtg, DataRequestSrcRtgReply }
-//
+/// Options for sending data, combine them using `|`
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+pub struct TxOptions(pub u8);
+
+impl TxOptions {
+    pub const NONE: Self = Self(0);
+    /// Send to all endpoints regardless of their profile id
+    pub const WILDCARD_PROFILE_ID: Self = Self(0x02);
+    /// Ask for an APS acknowledgement from the destination
+    pub const ACK_REQUEST: Self = Self(0x10);
+    /// Discover a route if there is none
+    pub const DISCOVER_ROUTE: Self = Self(0x20);
+    /// Use APS layer security
+    pub const APS_SECURITY: Self = Self(0x40);
+    /// Send only to direct neighbors
+    pub const SKIP_ROUTING: Self = Self(0x80);
+}
+
+impl BitOr for TxOptions {
+    type Output = Self;
+
+    fn bitor(self, rhs: Self) -> Self::Output {
+        Self(self.0 | rhs.0)
+    }
+}
+
+/// Send data to an endpoint on another device. The device answers with a
+/// status right away. Then once the message has been delivered, or delivery
+/// failed, with a [`DataConfirm`].
+#[derive(Debug, Clone, Serialize)]
+pub struct DataRequest {
+    pub dst_addr: ShortAddr,
+    pub dst_endpoint: u8,
+    pub src_endpoint: u8,
+    pub cluster_id: ClusterId,
+    /// Used to match the [`DataConfirm`] to this request
+    pub trans_id: u8,
+    pub options: TxOptions,
+    /// Maximum number of hops
+    pub radius: u8,
+    pub data: Vec<u8>,
+}
+
+impl AsyncRequest for DataRequest {
+    const ID: u8 = 1;
+    const SUBSYSTEM: SubSystem = SubSystem::Af;
+    /// zigbee-herdsman uses 10s too
+    const TIMEOUT: Duration = Duration::from_secs(10);
+    const HAS_SYNC_STATUS_RPLY: bool = true;
+    type Reply = DataConfirm;
+
+    fn reply_pattern(&self) -> Pattern {
+        data_confirm_pattern(self.src_endpoint, self.trans_id)
+    }
+}
+
+/// Like [`DataRequest`] but can address groups, send to other PANs and
+/// carry more data.
+#[derive(Debug, Clone, Serialize)]
+pub struct DataRequestExt {
+    pub dst: Address,
+    pub dst_endpoint: u8,
+    pub dst_pan_id: u16,
+    pub src_endpoint: u8,
+    pub cluster_id: ClusterId,
+    /// Used to match the [`DataConfirm`] to this request
+    pub trans_id: u8,
+    pub options: TxOptions,
+    /// Maximum number of hops
+    pub radius: u8,
+    pub data: LongData,
+}
+
+impl AsyncRequest for DataRequestExt {
+    const ID: u8 = 2;
+    const SUBSYSTEM: SubSystem = SubSystem::Af;
+    const TIMEOUT: Duration = Duration::from_secs(10);
+    const HAS_SYNC_STATUS_RPLY: bool = true;
+    type Reply = DataConfirm;
+
+    fn reply_pattern(&self) -> Pattern {
+        data_confirm_pattern(self.src_endpoint, self.trans_id)
+    }
+}
+
+/// Like [`DataRequest`] but the message follows the route given in
+/// `relay_list` instead of the route known by the network.
+#[derive(Debug, Clone, Serialize)]
+pub struct DataRequestSrcRtg {
+    pub dst_addr: ShortAddr,
+    pub dst_endpoint: u8,
+    pub src_endpoint: u8,
+    pub cluster_id: ClusterId,
+    /// Used to match the [`DataConfirm`] to this request
+    pub trans_id: u8,
+    pub options: TxOptions,
+    /// Maximum number of hops
+    pub radius: u8,
+    /// Routers to pass the message through on the way to `dst_addr`
+    pub relay_list: Vec<ShortAddr>,
+    pub data: Vec<u8>,
+}
+
+impl AsyncRequest for DataRequestSrcRtg {
+    const ID: u8 = 3;
+    const SUBSYSTEM: SubSystem = SubSystem::Af;
+    const TIMEOUT: Duration = Duration::from_secs(10);
+    const HAS_SYNC_STATUS_RPLY: bool = true;
+    type Reply = DataConfirm;
+
+    fn reply_pattern(&self) -> Pattern {
+        data_confirm_pattern(self.src_endpoint, self.trans_id)
+    }
+}
+
+fn data_confirm_pattern(src_endpoint: u8, trans_id: u8) -> Pattern {
+    Pattern::default()
+        .skip(1)
+        .match_exact(&src_endpoint)
+        .match_exact(&trans_id)
+}
+
+/// Send by the device once a data request has been delivered or when
+/// delivery failed.
+#[cfg_attr(feature = "mocking", derive(Serialize))]
+#[derive(Debug, Clone, Deserialize)]
+pub struct DataConfirm {
+    pub status: ConfirmStatus,
+    pub endpoint: u8,
+    pub trans_id: u8,
+}
+
+impl AsyncReply for DataConfirm {
+    const ID: u8 = 128;
+    const SUBSYSTEM: SubSystem = SubSystem::Af;
+    type Request = DataRequest;
+}
+
+/// Status of a [`DataConfirm`]. The MAC and NWK layers report failures with
+/// codes that are not in [`Status`], those are kept as is.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConfirmStatus {
+    Known(Status),
+    Other(u8),
+}
+
+impl ConfirmStatus {
+    pub fn is_success(self) -> bool {
+        self == ConfirmStatus::Known(Status::ZSuccess)
+    }
+}
+
+impl<'de> Deserialize<'de> for ConfirmStatus {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: de::Deserializer<'de>,
+    {
+        let code = u8::deserialize(deserializer)?;
+        let known: Result<Status, de::value::Error> =
+            Status::deserialize(code.into_deserializer());
+        Ok(known.map_or(ConfirmStatus::Other(code), ConfirmStatus::Known))
+    }
+}
+
+#[cfg(feature = "mocking")]
+impl Serialize for ConfirmStatus {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        match self {
+            ConfirmStatus::Known(status) => status.serialize(serializer),
+            ConfirmStatus::Other(code) => serializer.serialize_u8(*code),
+        }
+    }
+}
+
+/// Send by the device when a message arrives for one of its registered
+/// endpoints.
+#[cfg_attr(feature = "mocking", derive(Serialize))]
+#[derive(Debug, Clone, Deserialize)]
+pub struct IncomingMsg {
+    pub group_id: u16,
+    pub cluster_id: ClusterId,
+    pub src_addr: ShortAddr,
+    pub src_endpoint: u8,
+    pub dst_endpoint: u8,
+    pub was_broadcast: bool,
+    pub link_quality: u8,
+    pub security_use: bool,
+    pub timestamp: u32,
+    pub trans_seq_number: u8,
+    pub data: Vec<u8>,
+}
+
+impl AsyncNotify for IncomingMsg {
+    const ID: u8 = 129;
+    const SUBSYSTEM: SubSystem = SubSystem::Af;
+}
+
+/// Send instead of [`IncomingMsg`] for messages that need an extended
+/// source address or carry a lot of data.
+#[cfg_attr(feature = "mocking", derive(Serialize))]
+#[derive(Debug, Clone, Deserialize)]
+pub struct IncomingMsgExt {
+    pub group_id: u16,
+    pub cluster_id: ClusterId,
+    pub src: Address,
+    pub src_endpoint: u8,
+    pub src_pan_id: u16,
+    pub dst_endpoint: u8,
+    pub was_broadcast: bool,
+    pub link_quality: u8,
+    pub security_use: bool,
+    pub timestamp: u32,
+    pub trans_seq_number: u8,
+    pub data: LongData,
+}
+
+impl AsyncNotify for IncomingMsgExt {
+    const ID: u8 = 130;
+    const SUBSYSTEM: SubSystem = SubSystem::Af;
+}
+
+/// An address together with its addressing mode. On the wire this is the
+/// mode byte followed by 8 bytes, shorter addresses are zero padded.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Address {
+    NotPresent,
+    Group(u16),
+    Short(ShortAddr),
+    Ieee(IeeeAddr),
+    /// One of the broadcast addresses, for example `0xFFFC` for all routers
+    Broadcast(ShortAddr),
+}
+
+impl Address {
+    fn mode(&self) -> u8 {
+        match self {
+            Address::NotPresent => 0,
+            Address::Group(_) => 1,
+            Address::Short(_) => 2,
+            Address::Ieee(_) => 3,
+            Address::Broadcast(_) => 15,
+        }
+    }
+
+    fn padded(&self) -> u64 {
+        match *self {
+            Address::NotPresent => 0,
+            Address::Group(group) => group.into(),
+            Address::Short(ShortAddr(addr))
+            | Address::Broadcast(ShortAddr(addr)) => addr.into(),
+            Address::Ieee(IeeeAddr(addr)) => addr,
+        }
+    }
+}
+
+impl Serialize for Address {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        let mut s = serializer.serialize_struct("Address", 2)?;
+        s.serialize_field("mode", &self.mode())?;
+        s.serialize_field("addr", &self.padded())?;
+        s.end()
+    }
+}
+
+struct AddressVisitor;
+
+impl<'de> Visitor<'de> for AddressVisitor {
+    type Value = Address;
+
+    fn expecting(
+        &self,
+        formatter: &mut std::fmt::Formatter,
+    ) -> std::fmt::Result {
+        formatter.write_str("address mode byte followed by 8 address bytes")
+    }
+
+    fn visit_seq<V>(self, mut seq: V) -> Result<Self::Value, V::Error>
+    where
+        V: SeqAccess<'de>,
+    {
+        let mode = seq
+            .next_element::<u8>()?
+            .ok_or_else(|| de::Error::invalid_length(0, &self))?;
+        let addr = seq
+            .next_element::<u64>()?
+            .ok_or_else(|| de::Error::invalid_length(1, &self))?;
+        Ok(match mode {
+            0 => Address::NotPresent,
+            1 => Address::Group(addr as u16),
+            2 => Address::Short(ShortAddr(addr as u16)),
+            3 => Address::Ieee(IeeeAddr(addr)),
+            15 => Address::Broadcast(ShortAddr(addr as u16)),
+            other => {
+                return Err(de::Error::custom(format!(
+                    "unknown address mode: {other}"
+                )));
+            }
+        })
+    }
+}
+
+impl<'de> Deserialize<'de> for Address {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        deserializer.deserialize_struct(
+            "Address",
+            &["mode", "addr"],
+            AddressVisitor,
+        )
+    }
+}
+
 // #[derive(Debug, Clone, Serialize)]
 // pub struct Delete {
 //     pub endpoint: u8,
@@ -203,62 +465,6 @@ basic_reply! {Register, RegisterReply}
 // }
 //
 // #[derive(Debug, Clone, Serialize)]
-// pub struct DataConfirm {
-//     pub status: u8,
-//     pub endpoint: u8,
-//     pub transid: u8,
-// }
-//
-// impl AsyncReply for DataConfirm {
-//     const ID: u8 = 128;
-//     const SUBSYSTEM: SubSystem = SubSystem::Af;
-// }
-//
-// #[derive(Debug, Clone, Serialize)]
-// pub struct IncomingMsg {
-//     pub groupid: u16,
-//     pub clusterid: u16,
-//     pub srcaddr: u16,
-//     pub srcendpoint: u8,
-//     pub dstendpoint: u8,
-//     pub wasbroadcast: u8,
-//     pub linkquality: u8,
-//     pub securityuse: u8,
-//     pub timestamp: u32,
-//     pub transseqnumber: u8,
-//     pub len: u8,
-//     pub data: Vec<u8>,
-// }
-//
-// impl AsyncRequest for IncomingMsg {
-//     const ID: u8 = 129;
-//     const SUBSYSTEM: SubSystem = SubSystem::Af;
-// }
-//
-// #[derive(Debug, Clone, Serialize)]
-// pub struct IncomingMsgExt {
-//     pub groupid: u16,
-//     pub clusterid: u16,
-//     pub srcaddrmode: u8,
-//     pub srcaddr: IeeeAddr,
-//     pub srcendpoint: u8,
-//     pub srcpanid: u16,
-//     pub dstendpoint: u8,
-//     pub wasbroadcast: u8,
-//     pub linkquality: u8,
-//     pub securityuse: u8,
-//     pub timestamp: u32,
-//     pub transseqnumber: u8,
-//     pub len: u16,
-//     pub data: Vec<u8>,
-// }
-//
-// impl AsyncRequest for IncomingMsgExt {
-//     const ID: u8 = 130;
-//     const SUBSYSTEM: SubSystem = SubSystem::Af;
-// }
-//
-// #[derive(Debug, Clone, Serialize)]
 // pub struct ReflectError {
 //     pub status: u8,
 //     pub endpoint: u8,
@@ -271,3 +477,23 @@ basic_reply! {Register, RegisterReply}
 //     const ID: u8 = 131;
 //     const SUBSYSTEM: SubSystem = SubSystem::Af;
 // }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data_format;
+
+    #[test]
+    fn confirm_status_keeps_unknown_codes() {
+        let decode = |bytes: [u8; 3]| -> DataConfirm {
+            data_format::from_reader(&mut std::io::Cursor::new(bytes)).unwrap()
+        };
+        assert!(decode([0x00, 1, 7]).status.is_success());
+        assert_eq!(
+            decode([0xcd, 1, 7]).status,
+            ConfirmStatus::Known(Status::ZnwkNoRoute)
+        );
+        // MAC no resources
+        assert_eq!(decode([0x1a, 1, 7]).status, ConfirmStatus::Other(0x1a));
+    }
+}
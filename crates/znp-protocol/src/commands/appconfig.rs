@@ -1,117 +1,147 @@
 #![allow(dead_code)]
 
-// use serde::{Deserialize, Serialize};
-// use serde_repr::Serialize_repr;
-//
-// use super::{
-//     basic_reply, AsyncRequest, IeeeAddr, SubSystem, SyncReply, SyncRequest
-// };
-//
-// #[derive(Clone, Copy, Debug, Serialize_repr)]
-// #[repr(u8)]
-// pub enum InstallCodeFormat {
-//     InstallCodePlusCRC = 0x01,
-//     KeyDerivedFromInstallCode = 0x02,
-// }
-//
-// #[derive(Debug, Clone)]
-// pub struct AddInstallCode {
-//     pub format: InstallCodeFormat,
-//     pub addr: IeeeAddr,
-//     pub code: Vec<u8>,
-// }
-//
-// impl Serialize for AddInstallCode {
-//     fn serialize<S>(&self, _: S) -> Result<S::Ok, S::Error>
-//     where
-//         S: serde::Serializer,
-//     {
-//         unimplemented!(
-//             "can not be automatically serialized. Instead uses custom data_to_vec"
-//         )
-//     }
-// }
-//
-// impl SyncRequest for AddInstallCode {
-//     const ID: u8 = 4;
-//     const SUBSYSTEM: SubSystem = SubSystem::AppConfig;
-//     type Reply = AddInstallCodeReply;
-//
-//     fn data_to_vec(&self) -> Result<Vec<u8>, crate::data_format::Error>
-//     where
-//         Self: Sized,
-//     {
-//         let code = match self.format {
-//             InstallCodeFormat::InstallCodePlusCRC => {
-//                 self.code.iter().copied().take(usize::MAX)
-//             }
-//             InstallCodeFormat::KeyDerivedFromInstallCode => {
-//                 self.code.iter().copied().take(16)
-//             }
-//         };
-//
-//         Ok(std::iter::once(self.format as u8)
-//             .chain(self.addr.0.to_le_bytes())
-//             .chain(code)
-//             .collect())
-//     }
-// }
-//
-//
-// basic_reply! { AddInstallCode, AddInstallCodeReply }
-//
-// #[derive(Debug, Clone, Serialize)]
-// pub struct BdbStartCommissioning {
-//     pub mode: u8,
-// }
-//
-// impl SyncRequest for BdbStartCommissioning {
-//     const ID: u8 = 5;
-//     const SUBSYSTEM: SubSystem = SubSystem::AppConfig;
-//     type Reply = BdbStartCommissioningReply;
-// }
-//
-//
-// basic_reply! { BdbStartCommissioning, BdbStartCommissioningReply }
-//
-// #[derive(Debug, Clone, Serialize)]
-// pub struct BdbSetChannel {
-//     pub is_primary: u8,
-//     pub channel: u32,
-// }
-//
-// impl SyncRequest for BdbSetChannel {
-//     const ID: u8 = 8;
-//     const SUBSYSTEM: SubSystem = SubSystem::AppConfig;
-//     type Reply = BdbSetChannelReply;
-// }
-//
-//
-// basic_reply! { BdbSetChannel, BdbSetChannelReply }
-//
-// #[derive(Debug, Clone, Serialize)]
-// pub struct BdbSetTcRequireKeyExchange {
-//     pub value: u8,
-// }
-// impl SyncRequest for BdbSetTcRequireKeyExchange {
-//     const ID: u8 = 9;
-//     const SUBSYSTEM: SubSystem = SubSystem::AppConfig;
-//     type Reply = BdbSetTcRequireKeyExchangeReply;
-// }
-//
-//
-// basic_reply! { BdbSetTcRequireKeyExchange, BdbSetTcRequireKeyExchangeReply }
-//
-// #[derive(Debug, Clone, Serialize)]
-// pub struct BdbComissioningNotification {
-//     pub status: u8,
-// }
-//
-// impl AsyncRequest for BdbComissioningNotification {
-//     const ID: u8 = 128;
-//     const SUBSYSTEM: SubSystem = SubSystem::AppConfig;
-// }
-//
+use serde::ser::SerializeStruct;
+use serde::{Deserialize, Serialize};
+use serde_repr::{Deserialize_repr, Serialize_repr};
+
+use super::{
+    AsyncNotify, IeeeAddr, SubSystem, SyncReply, SyncRequest, basic_reply,
+};
+
+/// What [`AddInstallCode::code`] contains
+#[cfg_attr(feature = "mocking", derive(Deserialize_repr))]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize_repr)]
+#[repr(u8)]
+pub enum InstallCodeFormat {
+    /// The install code followed by its CRC, the adaptor derives the key
+    InstallCodePlusCrc = 0x01,
+    /// The 16 byte link key derived from the install code
+    KeyDerivedFromInstallCode = 0x02,
+}
+
+/// Register the link key of a device that is about to join. The device may
+/// only join using that key.
+#[derive(Debug, Clone)]
+pub struct AddInstallCode {
+    pub format: InstallCodeFormat,
+    pub ieee_addr: IeeeAddr,
+    /// Send without a length prefix
+    pub code: Vec<u8>,
+}
+
+impl Serialize for AddInstallCode {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        let mut s = serializer
+            .serialize_struct("AddInstallCode", 2 + self.code.len())?;
+        s.serialize_field("format", &self.format)?;
+        s.serialize_field("ieee_addr", &self.ieee_addr)?;
+        for byte in &self.code {
+            s.serialize_field("code", byte)?;
+        }
+        s.end()
+    }
+}
+
+impl SyncRequest for AddInstallCode {
+    const ID: u8 = 4;
+    const SUBSYSTEM: SubSystem = SubSystem::AppConfig;
+    type Reply = AddInstallCodeReply;
+}
+
+basic_reply! { AddInstallCode, AddInstallCodeReply }
+
+/// Bitflags for [`BdbStartCommissioning::mode`]
+pub mod commissioning_mode {
+    pub const INITIALIZATION: u8 = 0x00;
+    pub const TOUCHLINK: u8 = 0x01;
+    pub const NWK_STEERING: u8 = 0x02;
+    pub const NWK_FORMATION: u8 = 0x04;
+    pub const FINDING_BINDING: u8 = 0x08;
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct BdbStartCommissioning {
+    /// Bitflags from [`commissioning_mode`]
+    pub mode: u8,
+}
+
+impl SyncRequest for BdbStartCommissioning {
+    const ID: u8 = 5;
+    const SUBSYSTEM: SubSystem = SubSystem::AppConfig;
+    type Reply = BdbStartCommissioningReply;
+}
+
+basic_reply! { BdbStartCommissioning, BdbStartCommissioningReply }
+
+#[derive(Debug, Clone, Serialize)]
+pub struct BdbSetChannel {
+    /// The primary channels are tried first
+    pub is_primary: bool,
+    /// Bitmask of channels, bit 11 to 26
+    pub channel: u32,
+}
+
+impl SyncRequest for BdbSetChannel {
+    const ID: u8 = 8;
+    const SUBSYSTEM: SubSystem = SubSystem::AppConfig;
+    type Reply = BdbSetChannelReply;
+}
+
+basic_reply! { BdbSetChannel, BdbSetChannelReply }
+
+#[derive(Debug, Clone, Serialize)]
+pub struct BdbSetTcRequireKeyExchange {
+    pub value: bool,
+}
+
+impl SyncRequest for BdbSetTcRequireKeyExchange {
+    const ID: u8 = 9;
+    const SUBSYSTEM: SubSystem = SubSystem::AppConfig;
+    type Reply = BdbSetTcRequireKeyExchangeReply;
+}
+
+basic_reply! { BdbSetTcRequireKeyExchange, BdbSetTcRequireKeyExchangeReply }
+
+#[cfg_attr(feature = "mocking", derive(Serialize_repr))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize_repr)]
+#[repr(u8)]
+pub enum BdbCommissioningStatus {
+    Success = 0x00,
+    InProgress = 0x01,
+    NoNetwork = 0x02,
+    TlTargetFailure = 0x03,
+    TlNotAaCapable = 0x04,
+    TlNoScanResponse = 0x05,
+    TlNotPermitted = 0x06,
+    TclkExFailure = 0x07,
+    FormationFailure = 0x08,
+    FbTargetInProgress = 0x09,
+    FbInitiatorInProgress = 0x0A,
+    FbNoIdentifyQueryResponse = 0x0B,
+    FbBindingTableFull = 0x0C,
+    NetworkRestored = 0x0D,
+    Failure = 0x0E,
+}
+
+/// Send by the device as commissioning progresses
+#[cfg_attr(feature = "mocking", derive(Serialize))]
+#[derive(Debug, Clone, Deserialize)]
+pub struct BdbCommissioningNotification {
+    pub status: BdbCommissioningStatus,
+    /// The mode this notification is about, see [`commissioning_mode`]
+    pub mode: u8,
+    /// Modes that still have to run
+    pub remaining_modes: u8,
+}
+
+impl AsyncNotify for BdbCommissioningNotification {
+    const ID: u8 = 128;
+    const SUBSYSTEM: SubSystem = SubSystem::AppConfig;
+}
+
 // #[derive(Debug, Clone, Serialize)]
 // pub struct SetNwkFrameCounter {
 //     pub value: u32,
@@ -97,8 +97,8 @@ pub trait SyncReply: DeserializeOwned + std::fmt::Debug {
 /// These map to two separate mt command types:
 /// - `Self::HAS_SYNC_STATUS_RPLY` is false: an AREQ send by the host
 /// - `Self::HAS_SYNC_STATUS_RPLY` is true: an SREQ send by the host then
-/// immediately answered with a status SRSP from the device. Then at some
-/// later time an AREQ from the device
+///   immediately answered with a status SRSP from the device. Then at some
+///   later time an AREQ from the device
 pub trait AsyncRequest: Serialize + std::fmt::Debug {
     const ID: u8;
     const SUBSYSTEM: SubSystem;
@@ -117,7 +117,7 @@ pub trait AsyncRequest: Serialize + std::fmt::Debug {
     type Reply: AsyncReply;
 
     fn status_reply_meta() -> Option<CommandMeta> {
-        Self::HAS_SYNC_STATUS_RPLY.then(|| CommandMeta {
+        Self::HAS_SYNC_STATUS_RPLY.then_some(CommandMeta {
             ty: CommandType::SRSP,
             sub_system: Self::SUBSYSTEM,
             id: Self::ID,
@@ -152,9 +152,25 @@ pub trait AsyncRequest: Serialize + std::fmt::Debug {
 ///
 /// # Note
 /// that state change could very well be caused by an AsyncRequest
-pub trait AsyncNotify {
+pub trait AsyncNotify: DeserializeOwned + std::fmt::Debug {
     const ID: u8;
     const SUBSYSTEM: SubSystem;
+    const META: CommandMeta = CommandMeta {
+        ty: CommandType::AREQ,
+        sub_system: Self::SUBSYSTEM,
+        id: Self::ID,
+    };
+
+    fn from_data(data: &[u8]) -> Result<Self, ReplyError> {
+        use crate::commands::ReplyErrorCause as E;
+        let mut data = std::io::Cursor::new(data);
+        data_format::from_reader(&mut data)
+            .map_err(E::Deserialize)
+            .map_err(|cause| ReplyError {
+                reply: std::any::type_name::<Self>(),
+                cause,
+            })
+    }
 }
 
 /// Only send by the device in response to an AsyncRequest
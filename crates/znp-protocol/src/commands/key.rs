@@ -0,0 +1,46 @@
+use serde::ser::SerializeStruct;
+use serde::{Deserialize, Serialize};
+
+/// A 128 bit security key, for example the network key or a link key.
+/// Serialized as 16 bytes without a length prefix.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct Key(pub [u8; 16]);
+
+impl<'de> Deserialize<'de> for Key {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        <[u8; 16]>::deserialize(deserializer).map(Key)
+    }
+}
+
+impl Serialize for Key {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        // Arrays are serialized as tuples which get a length prefix
+        let mut s = serializer.serialize_struct("Key", self.0.len())?;
+        for byte in &self.0 {
+            s.serialize_field("byte", byte)?;
+        }
+        s.end()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data_format;
+
+    #[test]
+    fn roundtrip() {
+        let key = Key(std::array::from_fn(|i| i as u8));
+        let bytes = data_format::to_vec(&key).unwrap();
+        assert_eq!(bytes, key.0);
+        let mut reader = std::io::Cursor::new(bytes);
+        let decoded: Key = data_format::from_reader(&mut reader).unwrap();
+        assert_eq!(decoded, key);
+    }
+}
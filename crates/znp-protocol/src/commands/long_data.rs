@@ -0,0 +1,87 @@
+use serde::de::{SeqAccess, Visitor};
+use serde::ser::SerializeStruct;
+use serde::{Deserialize, Serialize, de};
+
+/// Bytes prefixed with a 16 bit length. A normal `Vec<u8>` is
+/// prefixed with an 8 bit length.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct LongData(pub Vec<u8>);
+
+struct LongDataVisitor;
+
+impl<'de> Visitor<'de> for LongDataVisitor {
+    type Value = LongData;
+
+    fn expecting(
+        &self,
+        formatter: &mut std::fmt::Formatter,
+    ) -> std::fmt::Result {
+        formatter.write_str(
+            "16 bit little endian length followed by that many bytes",
+        )
+    }
+
+    fn visit_seq<V>(self, mut seq: V) -> Result<Self::Value, V::Error>
+    where
+        V: SeqAccess<'de>,
+    {
+        let len = seq
+            .next_element::<u16>()?
+            .ok_or_else(|| de::Error::invalid_length(0, &self))?;
+        let mut data = Vec::with_capacity(len.into());
+        for i in 0..len as usize {
+            data.push(
+                seq.next_element::<u8>()?
+                    .ok_or_else(|| de::Error::invalid_length(i + 1, &self))?,
+            );
+        }
+        Ok(LongData(data))
+    }
+}
+
+impl<'de> Deserialize<'de> for LongData {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        // The length is only known after reading the first element. The
+        // data format stops at whatever the visitor does not ask for.
+        deserializer.deserialize_tuple(usize::MAX, LongDataVisitor)
+    }
+}
+
+impl Serialize for LongData {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        use serde::ser::Error;
+        let len = u16::try_from(self.0.len())
+            .map_err(|_| S::Error::custom("data longer then u16::MAX"))?;
+        // A struct is the only compound type that is serialized without a
+        // length prefix
+        let mut s =
+            serializer.serialize_struct("LongData", 1 + self.0.len())?;
+        s.serialize_field("len", &len)?;
+        for byte in &self.0 {
+            s.serialize_field("byte", byte)?;
+        }
+        s.end()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data_format;
+
+    #[test]
+    fn roundtrip() {
+        let data = LongData((0..=255).chain(0..10).collect());
+        let bytes = data_format::to_vec(&data).unwrap();
+        assert_eq!(bytes[..2], 266u16.to_le_bytes());
+        let mut reader = std::io::Cursor::new(bytes);
+        let decoded: LongData = data_format::from_reader(&mut reader).unwrap();
+        assert_eq!(decoded, data);
+    }
+}
@@ -300,7 +300,7 @@ impl SyncRequest for OrphanRsp {
 
 basic_reply! { OrphanRsp, OrphanRspReply }
 
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SyncLossInd {
     pub status: u8,
     pub panid: u16,
@@ -317,7 +317,7 @@ impl AsyncNotify for SyncLossInd {
     const SUBSYSTEM: SubSystem = SubSystem::Mac;
 }
 
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AssociateInd {
     pub deviceextendedaddress: IeeeAddr,
     pub capabilities: u8,
@@ -332,7 +332,7 @@ impl AsyncNotify for AssociateInd {
     const SUBSYSTEM: SubSystem = SubSystem::Mac;
 }
 
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AssociateCnf {
     pub status: u8,
     pub deviceshortaddress: u16,
@@ -347,7 +347,7 @@ impl AsyncNotify for AssociateCnf {
     const SUBSYSTEM: SubSystem = SubSystem::Mac;
 }
 
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct BeaconNotifyInd {
     pub bsn: u8,
     pub timestamp: u32,
@@ -365,7 +365,6 @@ pub struct BeaconNotifyInd {
     pub keyindex: u8,
     pub pendingaddrspec: u8,
     pub addresslist: [u8; 32],
-    pub sdulength: u8,
     pub nsdu: Vec<u8>,
 }
 
@@ -374,7 +373,7 @@ impl AsyncNotify for BeaconNotifyInd {
     const SUBSYSTEM: SubSystem = SubSystem::Mac;
 }
 
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct DataCnf {
     pub status: u8,
     pub handle: u8,
@@ -387,7 +386,7 @@ impl AsyncNotify for DataCnf {
     const SUBSYSTEM: SubSystem = SubSystem::Mac;
 }
 
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct DataInd {
     pub srcaddrmode: u8,
     pub srcaddr: IeeeAddr,
@@ -405,7 +404,6 @@ pub struct DataInd {
     pub securitylevel: u8,
     pub keyidmode: u8,
     pub keyindex: u8,
-    pub length: u8,
     pub data: Vec<u8>,
 }
 
@@ -414,7 +412,7 @@ impl AsyncNotify for DataInd {
     const SUBSYSTEM: SubSystem = SubSystem::Mac;
 }
 
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct DisassociateInd {
     pub extendedaddress: IeeeAddr,
     pub disassociatereason: u8,
@@ -429,7 +427,7 @@ impl AsyncNotify for DisassociateInd {
     const SUBSYSTEM: SubSystem = SubSystem::Mac;
 }
 
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct DisassociateCnf {
     pub status: u8,
     pub deviceaddrmode: u8,
@@ -442,7 +440,7 @@ impl AsyncNotify for DisassociateCnf {
     const SUBSYSTEM: SubSystem = SubSystem::Mac;
 }
 
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct OrphanInd {
     pub extendedaddr: IeeeAddr,
     pub keysource: [u8; 8],
@@ -456,7 +454,7 @@ impl AsyncNotify for OrphanInd {
     const SUBSYSTEM: SubSystem = SubSystem::Mac;
 }
 
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PollCnf {
     pub status: u8,
 }
@@ -466,7 +464,7 @@ impl AsyncNotify for PollCnf {
     const SUBSYSTEM: SubSystem = SubSystem::Mac;
 }
 
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ScanCnf {
     pub status: u8,
     pub ed: u8,
@@ -483,7 +481,7 @@ impl AsyncNotify for ScanCnf {
     const SUBSYSTEM: SubSystem = SubSystem::Mac;
 }
 
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CommStatusInd {
     pub status: u8,
     pub srcaddrmode: u8,
@@ -503,7 +501,7 @@ impl AsyncNotify for CommStatusInd {
     const SUBSYSTEM: SubSystem = SubSystem::Mac;
 }
 
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct StartCnf {
     pub status: u8,
 }
@@ -513,7 +511,7 @@ impl AsyncNotify for StartCnf {
     const SUBSYSTEM: SubSystem = SubSystem::Mac;
 }
 
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct RxEnableCnf {
     pub status: u8,
 }
@@ -523,7 +521,7 @@ impl AsyncNotify for RxEnableCnf {
     const SUBSYSTEM: SubSystem = SubSystem::Mac;
 }
 
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PurgeCnf {
     pub status: u8,
     pub handle: u8,
@@ -1,12 +1,14 @@
 use serde_repr::Deserialize_repr;
+#[cfg(feature = "mocking")]
+use serde_repr::Serialize_repr;
 
 /// The status parameter that is returned from the `ZNP` device
 ///
 /// From: `Z-Stack ZNP Interface Specification.pdf` revision 1.1 (11/11/2016)
 /// url: https://community.silabs.com/s/contentversion/0681M00000EWPKrQAP
 /// viewed: 2025-04-15
-#[allow(dead_code)]
-#[derive(Debug, Clone, Deserialize_repr)]
+#[cfg_attr(feature = "mocking", derive(Serialize_repr))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize_repr)]
 #[repr(u8)]
 pub enum Status {
     ZSuccess = 0x00,
@@ -20,6 +22,7 @@ pub enum Status {
     ZunsupportedMode = 0x12,
     ZmacMemError = 0x13,
     ZdoInvalidRequestType = 0x80,
+    ZdoDeviceNotFound = 0x81,
     ZdoInvalidEndpoint = 0x82,
     ZdoUnsupported = 0x84,
     ZdoTimeout = 0x85,
@@ -48,5 +51,14 @@ pub enum Status {
     ZnwkLeaveUnconfirmed = 0xcb,
     ZnwkNoAck = 0xcc,
     ZnwkNoRoute = 0xcd,
+    ZMacChannelAccessFailure = 0xe1,
     ZMacNoACK = 0xe9,
+    ZMacTransactionExpired = 0xf0,
+    ZMacTransactionOverflow = 0xf1,
+}
+
+impl Status {
+    pub fn is_success(self) -> bool {
+        self == Status::ZSuccess
+    }
 }
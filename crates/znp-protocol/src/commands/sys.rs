@@ -3,8 +3,8 @@
 use std::time::Duration;
 
 use super::{
-    AsyncNotify, AsyncReply, AsyncRequest, BasicStatus, SubSystem, SyncReply,
-    SyncRequest, basic_reply,
+    AsyncNotify, AsyncReply, AsyncRequest, BasicStatus, LongData, SubSystem,
+    SyncReply, SyncRequest, basic_reply,
 };
 use serde::{Deserialize, Serialize};
 use serde_repr::{Deserialize_repr, Serialize_repr};
@@ -12,6 +12,44 @@ use serde_repr::{Deserialize_repr, Serialize_repr};
 mod osal_nv_length_reply;
 pub use osal_nv_length_reply::OsalNvLengthReply;
 
+/// An id for an item in non volatile ram (NVRAM)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize)]
+pub struct NvId(pub u16);
+
+/// Status returned by the commands that access NVRAM
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize_repr)]
+#[cfg_attr(feature = "mocking", derive(Serialize_repr))]
+#[repr(u8)]
+pub enum NvStatus {
+    Success = 0x00,
+    Failure = 0x01,
+    /// The item did not exist before the request
+    ItemUninit = 0x09,
+    OperationFailed = 0x0a,
+    BadItemLength = 0x0c,
+}
+
+impl NvStatus {
+    pub fn is_success(self) -> bool {
+        self == Self::Success
+    }
+}
+
+/// A reply carrying only an [`NvStatus`]
+macro_rules! nv_reply {
+    ($request_name:ident, $reply_name:ident) => {
+        #[derive(Debug, Clone, Deserialize)]
+        #[cfg_attr(feature = "mocking", derive(Serialize))]
+        pub struct $reply_name {
+            pub status: NvStatus,
+        }
+
+        impl SyncReply for $reply_name {
+            type Request = $request_name;
+        }
+    };
+}
+
 #[derive(Debug, Clone, Copy, Serialize_repr)]
 #[repr(u8)]
 pub enum ResetType {
@@ -215,60 +253,73 @@ impl SyncRequest for Version {
 //
 // basic_reply! { RamWrite, RamWriteReply }
 //
-// #[derive(Debug, Clone, Serialize)]
-// pub struct OsalNvItemInit {
-//     pub id: u16,
-//     pub len: u16,
-//     pub initlen: u8,
-//     pub initvalue: Vec<u8>,
-// }
-//
-// impl SyncRequest for OsalNvItemInit {
-//     const ID: u8 = 7;
-//     const SUBSYSTEM: SubSystem = SubSystem::Sys;
-//     type Reply = OsalNvItemInitReply;
-// }
-//
-// basic_reply! { OsalNvItemInit, OsalNvItemInitReply }
-//
-// #[derive(Debug, Clone, Serialize)]
-// pub struct OsalNvRead {
-//     pub id: u16,
-//     pub offset: u8,
-// }
-//
-// #[derive(Debug, Clone, Deserialize)]
-// pub struct OsalNvReadReply {
-//     pub status: u8,
-//     pub len: u8,
-//     pub value: Vec<u8>,
-// }
-//
-// impl SyncReply for OsalNvReadReply {
-//     type Request = OsalNvRead;
-// }
-//
-// impl SyncRequest for OsalNvRead {
-//     const ID: u8 = 8;
-//     const SUBSYSTEM: SubSystem = SubSystem::Sys;
-//     type Reply = OsalNvReadReply;
-// }
-//
-// #[derive(Debug, Clone, Serialize)]
-// pub struct OsalNvWrite {
-//     pub id: u16,
-//     pub offset: u8,
-//     pub len: u8,
-//     pub value: Vec<u8>,
-// }
-//
-// impl SyncRequest for OsalNvWrite {
-//     const ID: u8 = 9;
-//     const SUBSYSTEM: SubSystem = SubSystem::Sys;
-//     type Reply = OsalNvWriteReply;
-// }
-//
-// basic_reply! { OsalNvWrite, OsalNvWriteReply }
+/// Creates the item if it does not exist yet, initialised with
+/// `init_value`. Items that already exist are left as is.
+#[derive(Debug, Clone, Serialize)]
+pub struct OsalNvItemInit {
+    pub id: NvId,
+    /// Length of the item in bytes
+    pub len: u16,
+    pub init_value: Vec<u8>,
+}
+
+impl SyncRequest for OsalNvItemInit {
+    const ID: u8 = 7;
+    const SUBSYSTEM: SubSystem = SubSystem::Sys;
+    type Reply = OsalNvItemInitReply;
+}
+
+/// [`NvStatus::Success`] if the item already existed and
+/// [`NvStatus::ItemUninit`] if it was created.
+#[derive(Debug, Clone, Deserialize)]
+#[cfg_attr(feature = "mocking", derive(Serialize))]
+pub struct OsalNvItemInitReply {
+    pub status: NvStatus,
+}
+
+impl SyncReply for OsalNvItemInitReply {
+    type Request = OsalNvItemInit;
+}
+
+/// Only reaches the first 255 bytes of an item, see [`OsalNvReadExt`]
+#[derive(Debug, Clone, Serialize)]
+pub struct OsalNvRead {
+    pub id: NvId,
+    pub offset: u8,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[cfg_attr(feature = "mocking", derive(Serialize))]
+pub struct OsalNvReadReply {
+    pub status: NvStatus,
+    pub value: Vec<u8>,
+}
+
+impl SyncReply for OsalNvReadReply {
+    type Request = OsalNvRead;
+}
+
+impl SyncRequest for OsalNvRead {
+    const ID: u8 = 8;
+    const SUBSYSTEM: SubSystem = SubSystem::Sys;
+    type Reply = OsalNvReadReply;
+}
+
+/// Only reaches the first 255 bytes of an item, see [`OsalNvWriteExt`]
+#[derive(Debug, Clone, Serialize)]
+pub struct OsalNvWrite {
+    pub id: NvId,
+    pub offset: u8,
+    pub value: Vec<u8>,
+}
+
+impl SyncRequest for OsalNvWrite {
+    const ID: u8 = 9;
+    const SUBSYSTEM: SubSystem = SubSystem::Sys;
+    type Reply = OsalNvWriteReply;
+}
+
+nv_reply! { OsalNvWrite, OsalNvWriteReply }
 //
 // #[derive(Debug, Clone, Serialize)]
 // pub struct OsalStartTimer {
@@ -421,23 +472,20 @@ impl SyncRequest for Version {
 //     type Reply = GetTimeReply;
 // }
 //
-// #[derive(Debug, Clone, Serialize)]
-// pub struct OsalNvDelete {
-//     pub id: u16,
-//     pub len: u16,
-// }
-//
-// impl SyncRequest for OsalNvDelete {
-//     const ID: u8 = 18;
-//     const SUBSYSTEM: SubSystem = SubSystem::Sys;
-//     type Reply = OsalNvDeleteReply;
-// }
-//
-// basic_reply! { OsalNvDelete, OsalNvDeleteReply }
+#[derive(Debug, Clone, Serialize)]
+pub struct OsalNvDelete {
+    pub id: NvId,
+    /// Must match the length of the item
+    pub len: u16,
+}
 
-/// An id for an item in non volatile ram (NVRAM)
-#[derive(Debug, Clone, Copy, Serialize)]
-pub struct NvId(pub u16);
+impl SyncRequest for OsalNvDelete {
+    const ID: u8 = 18;
+    const SUBSYSTEM: SubSystem = SubSystem::Sys;
+    type Reply = OsalNvDeleteReply;
+}
+
+nv_reply! { OsalNvDelete, OsalNvDeleteReply }
 
 #[derive(Debug, Clone, Serialize)]
 pub struct OsalNvLength {
@@ -579,6 +627,7 @@ pub struct OsalNvReadExt {
 }
 
 #[derive(Debug, Clone, Deserialize)]
+#[cfg_attr(feature = "mocking", derive(Serialize))]
 pub struct OsalNvReadExtReply {
     pub status: BasicStatus,
     pub bytes: Vec<u8>,
@@ -594,118 +643,130 @@ impl SyncRequest for OsalNvReadExt {
     type Reply = OsalNvReadExtReply;
 }
 
-// #[derive(Debug, Clone, Serialize)]
-// pub struct OsalNvWriteExt {
-//     pub id: u16,
-//     pub offset: u16,
-//     pub len: u16,
-//     pub value: Vec<u8>,
-// }
-//
-// impl SyncRequest for OsalNvWriteExt {
-//     const ID: u8 = 29;
-//     const SUBSYSTEM: SubSystem = SubSystem::Sys;
-//     type Reply = OsalNvWriteExtReply;
-// }
-//
-// basic_reply! { OsalNvWriteExt, OsalNvWriteExtReply }
-//
-// #[derive(Debug, Clone, Serialize)]
-// pub struct NvCreate {
-//     pub sysid: u8,
-//     pub itemid: u16,
-//     pub subid: u16,
-//     pub len: u32,
-// }
-//
-// impl SyncRequest for NvCreate {
-//     const ID: u8 = 48;
-//     const SUBSYSTEM: SubSystem = SubSystem::Sys;
-//     type Reply = NvCreateReply;
-// }
-//
-// basic_reply! { NvCreate, NvCreateReply }
-//
-// #[derive(Debug, Clone, Serialize)]
-// pub struct NvDelete {
-//     pub sysid: u8,
-//     pub itemid: u16,
-//     pub subid: u16,
-// }
-//
-// impl SyncRequest for NvDelete {
-//     const ID: u8 = 49;
-//     const SUBSYSTEM: SubSystem = SubSystem::Sys;
-//     type Reply = NvDeleteReply;
-// }
-//
-// basic_reply! { NvDelete, NvDeleteReply }
-//
-// #[derive(Debug, Clone, Serialize)]
-// pub struct NvLength {
-//     pub sysid: u8,
-//     pub itemid: u16,
-//     pub subid: u16,
-// }
-//
-// #[derive(Debug, Clone, Deserialize)]
-// pub struct NvLengthReply {
-//     pub len: u8,
-// }
-//
-// impl SyncReply for NvLengthReply {
-//     type Request = NvLength;
-// }
-//
-// impl SyncRequest for NvLength {
-//     const ID: u8 = 50;
-//     const SUBSYSTEM: SubSystem = SubSystem::Sys;
-//     type Reply = NvLengthReply;
-// }
-//
-// #[derive(Debug, Clone, Serialize)]
-// pub struct NvRead {
-//     pub sysid: u8,
-//     pub itemid: u16,
-//     pub subid: u16,
-//     pub offset: u16,
-//     pub len: u8,
-// }
-//
-// #[derive(Debug, Clone, Deserialize)]
-// pub struct NvReadReply {
-//     pub status: u8,
-//     pub len: u8,
-//     pub value: Vec<u8>,
-// }
-//
-// impl SyncReply for NvReadReply {
-//     type Request = NvRead;
-// }
-//
-// impl SyncRequest for NvRead {
-//     const ID: u8 = 51;
-//     const SUBSYSTEM: SubSystem = SubSystem::Sys;
-//     type Reply = NvReadReply;
-// }
-//
-// #[derive(Debug, Clone, Serialize)]
-// pub struct NvWrite {
-//     pub sysid: u8,
-//     pub itemid: u16,
-//     pub subid: u16,
-//     pub offset: u16,
-//     pub len: u8,
-//     pub value: Vec<u8>,
-// }
-//
-// impl SyncRequest for NvWrite {
-//     const ID: u8 = 52;
-//     const SUBSYSTEM: SubSystem = SubSystem::Sys;
-//     type Reply = NvWriteReply;
-// }
-//
-// basic_reply! { NvWrite, NvWriteReply }
+#[derive(Debug, Clone, Serialize)]
+pub struct OsalNvWriteExt {
+    pub id: NvId,
+    pub offset: u16,
+    pub value: LongData,
+}
+
+impl SyncRequest for OsalNvWriteExt {
+    const ID: u8 = 29;
+    const SUBSYSTEM: SubSystem = SubSystem::Sys;
+    type Reply = OsalNvWriteExtReply;
+}
+
+nv_reply! { OsalNvWriteExt, OsalNvWriteExtReply }
+
+/// Owner of an item in the extended NVRAM of Z-Stack 3.x.0
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize_repr)]
+#[repr(u8)]
+pub enum NvSysId {
+    Driver = 0,
+    ZStack = 1,
+    TiMac = 2,
+    RemoTi = 3,
+    Znp = 4,
+    App = 5,
+}
+
+/// An item in the extended NVRAM of Z-Stack 3.x.0. Items that hold a table
+/// use one sub id per entry.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize)]
+pub struct ExNvId {
+    pub sys_id: NvSysId,
+    pub item_id: u16,
+    pub sub_id: u16,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct NvCreate {
+    pub id: ExNvId,
+    /// Length of the item in bytes
+    pub len: u32,
+}
+
+impl SyncRequest for NvCreate {
+    const ID: u8 = 48;
+    const SUBSYSTEM: SubSystem = SubSystem::Sys;
+    type Reply = NvCreateReply;
+}
+
+nv_reply! { NvCreate, NvCreateReply }
+
+#[derive(Debug, Clone, Serialize)]
+pub struct NvDelete {
+    pub id: ExNvId,
+}
+
+impl SyncRequest for NvDelete {
+    const ID: u8 = 49;
+    const SUBSYSTEM: SubSystem = SubSystem::Sys;
+    type Reply = NvDeleteReply;
+}
+
+nv_reply! { NvDelete, NvDeleteReply }
+
+#[derive(Debug, Clone, Serialize)]
+pub struct NvLength {
+    pub id: ExNvId,
+}
+
+/// Zero if the item does not exist
+#[derive(Debug, Clone, Deserialize)]
+#[cfg_attr(feature = "mocking", derive(Serialize))]
+pub struct NvLengthReply {
+    pub len: u32,
+}
+
+impl SyncReply for NvLengthReply {
+    type Request = NvLength;
+}
+
+impl SyncRequest for NvLength {
+    const ID: u8 = 50;
+    const SUBSYSTEM: SubSystem = SubSystem::Sys;
+    type Reply = NvLengthReply;
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct NvRead {
+    pub id: ExNvId,
+    pub offset: u16,
+    pub len: u8,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[cfg_attr(feature = "mocking", derive(Serialize))]
+pub struct NvReadReply {
+    pub status: NvStatus,
+    pub value: Vec<u8>,
+}
+
+impl SyncReply for NvReadReply {
+    type Request = NvRead;
+}
+
+impl SyncRequest for NvRead {
+    const ID: u8 = 51;
+    const SUBSYSTEM: SubSystem = SubSystem::Sys;
+    type Reply = NvReadReply;
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct NvWrite {
+    pub id: ExNvId,
+    pub offset: u16,
+    pub value: Vec<u8>,
+}
+
+impl SyncRequest for NvWrite {
+    const ID: u8 = 52;
+    const SUBSYSTEM: SubSystem = SubSystem::Sys;
+    type Reply = NvWriteReply;
+}
+
+nv_reply! { NvWrite, NvWriteReply }
 //
 // #[derive(Debug, Clone, Serialize)]
 // pub struct NvUpdate {
@@ -48,7 +48,7 @@ where
 
 #[cfg(feature = "mocking")]
 fn device_type_to_u8<S>(
-    device_type: &Vec<DeviceType>,
+    device_type: &[DeviceType],
     serializer: S,
 ) -> Result<S::Ok, S::Error>
 where
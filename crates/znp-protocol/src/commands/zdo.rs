@@ -3,9 +3,11 @@ use std::time::Duration;
 use serde::{Deserialize, Serialize};
 use serde_repr::{Deserialize_repr, Serialize_repr};
 
+use super::af::ClusterId;
 use super::{
-    AsyncReply, AsyncRequest, BasicStatus, IeeeAddr, PartialList, Pattern,
-    ShortAddr, SubSystem, SyncReply, SyncRequest,
+    AsyncNotify, AsyncReply, AsyncRequest, BasicStatus, DeviceState, IeeeAddr,
+    PartialList, Pattern, ShortAddr, Status, SubSystem, SyncReply, SyncRequest,
+    basic_reply,
 };
 
 mod neighbor_lqi;
@@ -14,42 +16,95 @@ pub use neighbor_lqi::NeighborLqi;
 mod ext_find_group_reply;
 pub use ext_find_group_reply::{ExtFindGroupReply, GroupName};
 
-// #[derive(Debug, Clone, Serialize, Deserialize)]
-// pub struct NwkAddrReq {
-//     pub ieeeaddr: IeeeAddr,
-//     pub reqtype: u8,
-//     pub startindex: u8,
-// }
-//
-// impl SyncRequest for NwkAddrReq {
-//     const ID: u8 = 0;
-//     const SUBSYSTEM: SubSystem = SubSystem::Zdo;
-//     type Reply = NwkAddrReqReply;
-// }
-// basic_reply! {NwkAddrReq, NwkAddrReqReply }
+mod bind_table;
+pub use bind_table::{BindAddrMode, BindDestination, BindTable};
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+mod simple_desc_rsp;
+pub use simple_desc_rsp::{SimpleDescRsp, SimpleDescriptor};
+
+/// How much an address request should return
+#[cfg_attr(feature = "mocking", derive(Deserialize_repr))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize_repr)]
+#[repr(u8)]
+pub enum AddrReqType {
+    /// Only the address of the device
+    Single = 0,
+    /// Also the addresses of the devices associated with it
+    Extended = 1,
+}
+
+/// Look up the network address of a device. The request is broadcast, the
+/// device with `ieee_addr` answers.
+#[cfg_attr(feature = "mocking", derive(Deserialize))]
+#[derive(Debug, Clone, Serialize)]
+pub struct NwkAddrReq {
+    pub ieee_addr: IeeeAddr,
+    pub req_type: AddrReqType,
+    /// First associated device to list in an extended response
+    pub start_index: u8,
+}
+
+impl AsyncRequest for NwkAddrReq {
+    const ID: u8 = 0;
+    const SUBSYSTEM: SubSystem = SubSystem::Zdo;
+    const TIMEOUT: Duration = Duration::from_secs(10);
+    const HAS_SYNC_STATUS_RPLY: bool = true;
+    type Reply = NwkAddrRsp;
+
+    fn reply_pattern(&self) -> Pattern {
+        Pattern::default().skip(1).match_exact(&self.ieee_addr)
+    }
+}
+
+#[cfg_attr(feature = "mocking", derive(Serialize))]
+#[derive(Debug, Clone, Deserialize)]
+pub struct NwkAddrRsp {
+    pub status: Status,
+    pub ieee_addr: IeeeAddr,
+    pub nwk_addr: ShortAddr,
+    pub start_index: u8,
+    /// Only filled for extended requests
+    pub assoc_dev_list: Vec<ShortAddr>,
+}
+
+impl AsyncReply for NwkAddrRsp {
+    const ID: u8 = 0x80;
+    const SUBSYSTEM: SubSystem = SubSystem::Zdo;
+    type Request = NwkAddrReq;
+}
+
+/// Look up the IEEE address of a device, the request is send to the device
+/// itself.
+#[cfg_attr(feature = "mocking", derive(Deserialize))]
+#[derive(Debug, Clone, Serialize)]
 pub struct IeeeAddrReq {
-    pub shortaddr: u16,
-    pub reqtype: u8,
-    pub startindex: u8,
+    pub short_addr: ShortAddr,
+    pub req_type: AddrReqType,
+    /// First associated device to list in an extended response
+    pub start_index: u8,
 }
 
 impl AsyncRequest for IeeeAddrReq {
     const ID: u8 = 1;
     const SUBSYSTEM: SubSystem = SubSystem::Zdo;
-    const TIMEOUT: Duration = Duration::from_millis(500);
+    const TIMEOUT: Duration = Duration::from_secs(10);
     const HAS_SYNC_STATUS_RPLY: bool = true;
     type Reply = IeeeAddrRsp;
+
+    fn reply_pattern(&self) -> Pattern {
+        Pattern::default().skip(1 + 8).match_exact(&self.short_addr)
+    }
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "mocking", derive(Serialize))]
+#[derive(Debug, Clone, Deserialize)]
 pub struct IeeeAddrRsp {
-    pub status: u8,
-    pub ieeeaddr: IeeeAddr,
-    pub nwkaddr: u16,
-    pub startindex: u8,
-    pub assocdevlist: Vec<u16>,
+    pub status: Status,
+    pub ieee_addr: IeeeAddr,
+    pub nwk_addr: ShortAddr,
+    pub start_index: u8,
+    /// Only filled for extended requests
+    pub assoc_dev_list: Vec<ShortAddr>,
 }
 
 impl AsyncReply for IeeeAddrRsp {
@@ -58,59 +113,115 @@ impl AsyncReply for IeeeAddrRsp {
     type Request = IeeeAddrReq;
 }
 
-// #[derive(Debug, Clone, Serialize, Deserialize)]
-// pub struct NodeDescReq {
-//     pub dstaddr: u16,
-//     pub nwkaddrofinterest: u16,
-// }
-//
-// impl SyncRequest for NodeDescReq {
-//     const ID: u8 = 2;
-//     const SUBSYSTEM: SubSystem = SubSystem::Zdo;
-//     type Reply = NodeDescReqReply;
-// }
-// basic_reply! {NodeDescReq, NodeDescReqReply }
-//
-// #[derive(Debug, Clone, Serialize, Deserialize)]
-// pub struct PowerDescReq {
-//     pub dstaddr: u16,
-//     pub nwkaddrofinterest: u16,
-// }
-//
-// impl SyncRequest for PowerDescReq {
-//     const ID: u8 = 3;
-//     const SUBSYSTEM: SubSystem = SubSystem::Zdo;
-//     type Reply = PowerDescReqReply;
-// }
-// basic_reply! {PowerDescReq, PowerDescReqReply }
-//
-// #[derive(Debug, Clone, Serialize, Deserialize)]
-// pub struct SimpleDescReq {
-//     pub dstaddr: u16,
-//     pub nwkaddrofinterest: u16,
-//     pub endpoint: u8,
-// }
-//
-// impl SyncRequest for SimpleDescReq {
-//     const ID: u8 = 4;
-//     const SUBSYSTEM: SubSystem = SubSystem::Zdo;
-//     type Reply = SimpleDescReqReply;
-// }
-// basic_reply! {SimpleDescReq, SimpleDescReqReply }
-//
-// #[derive(Debug, Clone, Serialize, Deserialize)]
-// pub struct ActiveEpReq {
-//     pub dstaddr: u16,
-//     pub nwkaddrofinterest: u16,
-// }
-//
-// impl SyncRequest for ActiveEpReq {
-//     const ID: u8 = 5;
-//     const SUBSYSTEM: SubSystem = SubSystem::Zdo;
-//     type Reply = ActiveEpReqReply;
-// }
-// basic_reply! {ActiveEpReq, ActiveEpReqReply }
-//
+/// Lets subscribers learn of every address lookup, whoever requested it
+impl AsyncNotify for IeeeAddrRsp {
+    const ID: u8 = 0x81;
+    const SUBSYSTEM: SubSystem = SubSystem::Zdo;
+}
+
+/// Request the node descriptor of a device, it holds the logical type and
+/// manufacturer code.
+#[cfg_attr(feature = "mocking", derive(Deserialize))]
+#[derive(Debug, Clone, Serialize)]
+pub struct NodeDescReq {
+    /// Device to send the request to
+    pub dst_addr: ShortAddr,
+    /// Device to describe, the destination or one of its children
+    pub nwk_addr_of_interest: ShortAddr,
+}
+
+impl AsyncRequest for NodeDescReq {
+    const ID: u8 = 2;
+    const SUBSYSTEM: SubSystem = SubSystem::Zdo;
+    const TIMEOUT: Duration = Duration::from_secs(10);
+    const HAS_SYNC_STATUS_RPLY: bool = true;
+    type Reply = NodeDescRsp;
+
+    fn reply_pattern(&self) -> Pattern {
+        Pattern::default()
+            .match_exact(&self.dst_addr)
+            .skip(1)
+            .match_exact(&self.nwk_addr_of_interest)
+    }
+}
+
+/// Request the power descriptor of a device
+#[cfg_attr(feature = "mocking", derive(Deserialize))]
+#[derive(Debug, Clone, Serialize)]
+pub struct PowerDescReq {
+    /// Device to send the request to
+    pub dst_addr: ShortAddr,
+    /// Device to describe, the destination or one of its children
+    pub nwk_addr_of_interest: ShortAddr,
+}
+
+impl AsyncRequest for PowerDescReq {
+    const ID: u8 = 3;
+    const SUBSYSTEM: SubSystem = SubSystem::Zdo;
+    const TIMEOUT: Duration = Duration::from_secs(10);
+    const HAS_SYNC_STATUS_RPLY: bool = true;
+    type Reply = PowerDescRsp;
+
+    fn reply_pattern(&self) -> Pattern {
+        Pattern::default()
+            .match_exact(&self.dst_addr)
+            .skip(1)
+            .match_exact(&self.nwk_addr_of_interest)
+    }
+}
+
+/// Request the simple descriptor of one endpoint of a device, it lists
+/// the clusters on the endpoint.
+#[cfg_attr(feature = "mocking", derive(Deserialize))]
+#[derive(Debug, Clone, Serialize)]
+pub struct SimpleDescReq {
+    /// Device to send the request to
+    pub dst_addr: ShortAddr,
+    /// Device to describe, the destination or one of its children
+    pub nwk_addr_of_interest: ShortAddr,
+    pub endpoint: u8,
+}
+
+impl AsyncRequest for SimpleDescReq {
+    const ID: u8 = 4;
+    const SUBSYSTEM: SubSystem = SubSystem::Zdo;
+    const TIMEOUT: Duration = Duration::from_secs(10);
+    const HAS_SYNC_STATUS_RPLY: bool = true;
+    type Reply = SimpleDescRsp;
+
+    fn reply_pattern(&self) -> Pattern {
+        Pattern::default()
+            .match_exact(&self.dst_addr)
+            .skip(1)
+            .match_exact(&self.nwk_addr_of_interest)
+    }
+}
+
+/// Request the list of endpoints on a device
+#[cfg_attr(feature = "mocking", derive(Deserialize))]
+#[derive(Debug, Clone, Serialize)]
+pub struct ActiveEpReq {
+    /// Device to send the request to
+    pub dst_addr: ShortAddr,
+    /// Device to describe, the destination or one of its children
+    pub nwk_addr_of_interest: ShortAddr,
+}
+
+impl AsyncRequest for ActiveEpReq {
+    const ID: u8 = 5;
+    const SUBSYSTEM: SubSystem = SubSystem::Zdo;
+    const TIMEOUT: Duration = Duration::from_secs(10);
+    const HAS_SYNC_STATUS_RPLY: bool = true;
+    type Reply = ActiveEpRsp;
+
+    fn reply_pattern(&self) -> Pattern {
+        Pattern::default()
+            .match_exact(&self.dst_addr)
+            .skip(1)
+            .match_exact(&self.nwk_addr_of_interest)
+    }
+}
+
 // #[derive(Debug, Clone, Serialize, Deserialize)]
 // pub struct MatchDescReq {
 //     pub dstaddr: u16,
@@ -209,43 +320,66 @@ impl AsyncReply for IeeeAddrRsp {
 //     type Reply = EndDeviceBindReqReply;
 // }
 // basic_reply! {EndDeviceBindReq, EndDeviceBindReqReply }
-//
-// #[derive(Debug, Clone, Serialize, Deserialize)]
-// pub struct BindReq {
-//     pub dstaddr: u16,
-//     pub srcaddr: IeeeAddr,
-//     pub srcendpoint: u8,
-//     pub clusterid: u16,
-//     pub dstaddrmode: u8,
-//     pub dstaddress: IeeeAddr,
-//     pub dstendpoint: u8,
-// }
-//
-// impl SyncRequest for BindReq {
-//     const ID: u8 = 33;
-//     const SUBSYSTEM: SubSystem = SubSystem::Zdo;
-//     type Reply = BindReqReply;
-// }
-// basic_reply! {BindReq, BindReqReply }
-//
-// #[derive(Debug, Clone, Serialize, Deserialize)]
-// pub struct UnbindReq {
-//     pub dstaddr: u16,
-//     pub srcaddr: IeeeAddr,
-//     pub srcendpoint: u8,
-//     pub clusterid: u16,
-//     pub dstaddrmode: u8,
-//     pub dstaddress: IeeeAddr,
-//     pub dstendpoint: u8,
-// }
-//
-// impl SyncRequest for UnbindReq {
-//     const ID: u8 = 34;
-//     const SUBSYSTEM: SubSystem = SubSystem::Zdo;
-//     type Reply = UnbindReqReply;
-// }
-// basic_reply! {UnbindReq, UnbindReqReply }
-//
+
+/// Add an entry to the binding table of a device. Messages from the source
+/// endpoint in the cluster then go to the destination.
+#[cfg_attr(feature = "mocking", derive(Deserialize))]
+#[derive(Debug, Clone, Serialize)]
+pub struct BindReq {
+    /// Device to send the request to, the one holding the binding table
+    pub dst_addr: ShortAddr,
+    /// IEEE address of the device to send the request to
+    pub src_addr: IeeeAddr,
+    pub src_endpoint: u8,
+    pub cluster_id: ClusterId,
+    pub dst_addr_mode: BindAddrMode,
+    /// A group id is send in the lowest two bytes
+    pub dst_address: IeeeAddr,
+    /// Ignored for groups
+    pub dst_endpoint: u8,
+}
+
+impl AsyncRequest for BindReq {
+    const ID: u8 = 33;
+    const SUBSYSTEM: SubSystem = SubSystem::Zdo;
+    const TIMEOUT: Duration = Duration::from_secs(10);
+    const HAS_SYNC_STATUS_RPLY: bool = true;
+    type Reply = BindRsp;
+
+    fn reply_pattern(&self) -> Pattern {
+        Pattern::default().match_exact(&self.dst_addr)
+    }
+}
+
+/// Remove an entry from the binding table of a device
+#[cfg_attr(feature = "mocking", derive(Deserialize))]
+#[derive(Debug, Clone, Serialize)]
+pub struct UnbindReq {
+    /// Device to send the request to, the one holding the binding table
+    pub dst_addr: ShortAddr,
+    /// IEEE address of the device to send the request to
+    pub src_addr: IeeeAddr,
+    pub src_endpoint: u8,
+    pub cluster_id: ClusterId,
+    pub dst_addr_mode: BindAddrMode,
+    /// A group id is send in the lowest two bytes
+    pub dst_address: IeeeAddr,
+    /// Ignored for groups
+    pub dst_endpoint: u8,
+}
+
+impl AsyncRequest for UnbindReq {
+    const ID: u8 = 34;
+    const SUBSYSTEM: SubSystem = SubSystem::Zdo;
+    const TIMEOUT: Duration = Duration::from_secs(10);
+    const HAS_SYNC_STATUS_RPLY: bool = true;
+    type Reply = UnbindRsp;
+
+    fn reply_pattern(&self) -> Pattern {
+        Pattern::default().match_exact(&self.dst_addr)
+    }
+}
+
 // #[derive(Debug, Clone, Serialize, Deserialize)]
 // pub struct SetLinkKey {
 //     pub shortaddr: u16,
@@ -436,33 +570,63 @@ impl AsyncReply for MgmtRtgRsp {
     type Request = MgmtRtgReq;
 }
 
-// #[derive(Debug, Clone, Serialize, Deserialize)]
-// pub struct MgmtBindReq {
-//     pub dstaddr: u16,
-//     pub startindex: u8,
-// }
-//
-// impl SyncRequest for MgmtBindReq {
-//     const ID: u8 = 51;
-//     const SUBSYSTEM: SubSystem = SubSystem::Zdo;
-//     type Reply = MgmtBindReqReply;
-// }
-// basic_reply! {MgmtBindReq, MgmtBindReqReply }
-//
-// #[derive(Debug, Clone, Serialize, Deserialize)]
-// pub struct MgmtLeaveReq {
-//     pub dstaddr: u16,
-//     pub deviceaddress: IeeeAddr,
-//     pub removechildren_rejoin: u8,
-// }
-//
-// impl SyncRequest for MgmtLeaveReq {
-//     const ID: u8 = 52;
-//     const SUBSYSTEM: SubSystem = SubSystem::Zdo;
-//     type Reply = MgmtLeaveReqReply;
-// }
-// basic_reply! {MgmtLeaveReq, MgmtLeaveReqReply }
-//
+/// Request the binding table of the destination device
+#[cfg_attr(feature = "mocking", derive(Deserialize))]
+#[derive(Debug, Clone, Serialize)]
+pub struct MgmtBindReq {
+    pub dst_addr: ShortAddr,
+    /// Where to start in the response list, devices only return a few
+    /// entries at a time.
+    pub start_index: u8,
+}
+
+impl AsyncRequest for MgmtBindReq {
+    const ID: u8 = 51;
+    const SUBSYSTEM: SubSystem = SubSystem::Zdo;
+    const TIMEOUT: Duration = Duration::from_secs(10);
+    const HAS_SYNC_STATUS_RPLY: bool = true;
+    type Reply = MgmtBindRsp;
+
+    fn reply_pattern(&self) -> Pattern {
+        Pattern::default()
+            .match_exact(&self.dst_addr)
+            .skip(2)
+            .match_exact(&self.start_index)
+    }
+}
+
+/// Bitflags for [`MgmtLeaveReq::options`]
+pub mod leave_options {
+    /// The device should rejoin the network after leaving
+    pub const REJOIN: u8 = 0x01;
+    /// The device should also ask its children to leave
+    pub const REMOVE_CHILDREN: u8 = 0x02;
+}
+
+/// Ask a device to leave the network
+#[cfg_attr(feature = "mocking", derive(Deserialize))]
+#[derive(Debug, Clone, Serialize)]
+pub struct MgmtLeaveReq {
+    /// Device to send the request to
+    pub dst_addr: ShortAddr,
+    /// Device that should leave, the destination or one of its children
+    pub device_address: IeeeAddr,
+    /// Bitflags from [`leave_options`]
+    pub options: u8,
+}
+
+impl AsyncRequest for MgmtLeaveReq {
+    const ID: u8 = 52;
+    const SUBSYSTEM: SubSystem = SubSystem::Zdo;
+    const TIMEOUT: Duration = Duration::from_secs(10);
+    const HAS_SYNC_STATUS_RPLY: bool = true;
+    type Reply = MgmtLeaveRsp;
+
+    fn reply_pattern(&self) -> Pattern {
+        Pattern::default().match_exact(&self.dst_addr)
+    }
+}
+
 // #[derive(Debug, Clone, Serialize, Deserialize)]
 // pub struct MgmtDirectJoinReq {
 //     pub dstaddr: u16,
@@ -476,22 +640,49 @@ impl AsyncReply for MgmtRtgRsp {
 //     type Reply = MgmtDirectJoinReqReply;
 // }
 // basic_reply! {MgmtDirectJoinReq, MgmtDirectJoinReqReply }
-//
-// #[derive(Debug, Clone, Serialize, Deserialize)]
-// pub struct MgmtPermitJoinReq {
-//     pub addrmode: u8,
-//     pub dstaddr: u16,
-//     pub duration: u8,
-//     pub tcsignificance: u8,
-// }
-//
-// impl SyncRequest for MgmtPermitJoinReq {
-//     const ID: u8 = 54;
-//     const SUBSYSTEM: SubSystem = SubSystem::Zdo;
-//     type Reply = MgmtPermitJoinReqReply;
-// }
-// basic_reply! {MgmtPermitJoinReq, MgmtPermitJoinReqReply }
-//
+
+/// How the destination of a [`MgmtPermitJoinReq`] is addressed
+#[cfg_attr(feature = "mocking", derive(Deserialize_repr))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize_repr)]
+#[repr(u8)]
+pub enum PermitJoinAddrMode {
+    Addr16Bit = 0x02,
+    Broadcast = 0x0F,
+}
+
+/// Open or close the network for joining devices on the destination, or
+/// on every router when broadcast.
+#[cfg_attr(feature = "mocking", derive(Deserialize))]
+#[derive(Debug, Clone, Serialize)]
+pub struct MgmtPermitJoinReq {
+    pub addr_mode: PermitJoinAddrMode,
+    /// Short address of the device, or a broadcast address such as
+    /// `0xFFFC` for all routers
+    pub dst_addr: ShortAddr,
+    /// Seconds to allow joining for, 0 closes the network and 255 opens it
+    /// until closed again
+    pub duration: u8,
+    /// Ignored by Z-Stack 3, should be true
+    pub tc_significance: bool,
+}
+
+impl AsyncRequest for MgmtPermitJoinReq {
+    const ID: u8 = 54;
+    const SUBSYSTEM: SubSystem = SubSystem::Zdo;
+    const TIMEOUT: Duration = Duration::from_secs(10);
+    const HAS_SYNC_STATUS_RPLY: bool = true;
+    type Reply = MgmtPermitJoinRsp;
+
+    fn reply_pattern(&self) -> Pattern {
+        // A broadcast is answered by the coordinator itself
+        let src_addr = match self.addr_mode {
+            PermitJoinAddrMode::Addr16Bit => self.dst_addr,
+            PermitJoinAddrMode::Broadcast => ShortAddr(0x0000),
+        };
+        Pattern::default().match_exact(&src_addr)
+    }
+}
+
 // #[derive(Debug, Clone, Serialize, Deserialize)]
 // pub struct MgmtNwkUpdateReq {
 //     pub dstaddr: u16,
@@ -570,88 +761,73 @@ impl SyncReply for StartupFromAppReply {
 //     const ID: u8 = 65;
 //     const SUBSYSTEM: SubSystem = SubSystem::Zdo;
 // }
-//
-// #[derive(Debug, Clone, Serialize, Deserialize)]
-// pub struct NwkAddrRsp {
-//     pub status: u8,
-//     // Parse the ieeeaddr as it is needed for ZNP waitFor (see zStackAdapter.requestNetworkAddress())
-//     pub ieeeaddr: IeeeAddr,
-//     pub nwkaddr: u16,
-//     pub startindex: u8,
-//     pub assocdevlist: Vec<u16>,
-// }
-//
-// impl AsyncReply for NwkAddrRsp {
-//     const ID: u8 = 0x80;
-//     const SUBSYSTEM: SubSystem = SubSystem::Zdo;
-// }
-//
-// #[derive(Debug, Clone, Serialize, Deserialize)]
-// pub struct NodeDescRsp {
-//     pub srcaddr: u16,
-//     pub status: u8,
-//     pub nwkaddr: u16,
-//     pub logicaltype_cmplxdescavai_userdescavai: u8,
-//     pub apsflags_freqband: u8,
-//     pub maccapflags: u8,
-//     pub manufacturercode: u16,
-//     pub maxbuffersize: u8,
-//     pub maxintransfersize: u16,
-//     pub servermask: u16,
-//     pub maxouttransfersize: u16,
-//     pub descriptorcap: u8,
-// }
-//
-// impl SyncReply for NodeDescRsp {
-//     const CMD0: u8 = 0; // placeholder
-//     const CMD1: u8 = 0; // placeholder
-// }
-//
-// #[derive(Debug, Clone, Serialize, Deserialize)]
-// pub struct PowerDescRsp {
-//     pub srcaddr: u16,
-//     pub status: u8,
-//     pub nwkaddr: u16,
-//     pub currentpowermode_avaipowersrc: u8,
-//     pub currentpowersrc_currentpowersrclevel: u8,
-// }
-//
-// impl SyncReply for PowerDescRsp {
-//     const CMD0: u8 = 0; // placeholder
-//     const CMD1: u8 = 0; // placeholder
-// }
-//
-// #[derive(Debug, Clone, Serialize, Deserialize)]
-// pub struct SimpleDescRsp {
-//     pub srcaddr: u16,
-//     pub status: u8,
-//     pub nwkaddr: u16,
-//     pub len: u8,
-//     pub endpoint: u8,
-//     pub profileid: u16,
-//     pub deviceid: u16,
-//     pub deviceversion: u8,
-//     pub inclusterlist: Vec<u16>,
-// }
-//
-// impl SyncReply for SimpleDescRsp {
-//     const CMD0: u8 = 0; // placeholder
-//     const CMD1: u8 = 0; // placeholder
-// }
-//
-// #[derive(Debug, Clone, Serialize, Deserialize)]
-// pub struct ActiveEpRsp {
-//     pub srcaddr: u16,
-//     pub status: u8,
-//     pub nwkaddr: u16,
-//     pub activeeplist: Vec<u8>,
-// }
-//
-// impl SyncReply for ActiveEpRsp {
-//     const CMD0: u8 = 0; // placeholder
-//     const CMD1: u8 = 0; // placeholder
-// }
-//
+
+#[cfg_attr(feature = "mocking", derive(Serialize))]
+#[derive(Debug, Clone, Deserialize)]
+pub struct NodeDescRsp {
+    pub src_addr: ShortAddr,
+    pub status: Status,
+    pub nwk_addr: ShortAddr,
+    /// Logical type in bits 0-2, complex and user descriptor available in
+    /// bits 3 and 4
+    pub logical_type_flags: u8,
+    /// Frequency band in bits 3-7
+    pub aps_flags_freq_band: u8,
+    /// Mac capabilities bitfield
+    pub mac_capabilities: u8,
+    pub manufacturer_code: u16,
+    pub max_buffer_size: u8,
+    pub max_in_transfer_size: u16,
+    pub server_mask: u16,
+    pub max_out_transfer_size: u16,
+    pub descriptor_capabilities: u8,
+}
+
+impl AsyncReply for NodeDescRsp {
+    const ID: u8 = 130;
+    const SUBSYSTEM: SubSystem = SubSystem::Zdo;
+    type Request = NodeDescReq;
+}
+
+#[cfg_attr(feature = "mocking", derive(Serialize))]
+#[derive(Debug, Clone, Deserialize)]
+pub struct PowerDescRsp {
+    pub src_addr: ShortAddr,
+    pub status: Status,
+    pub nwk_addr: ShortAddr,
+    /// Current power mode in bits 0-3, available power sources in bits 4-7
+    pub current_mode_available_sources: u8,
+    /// Current power source in bits 0-3, its level in bits 4-7
+    pub current_source_level: u8,
+}
+
+impl AsyncReply for PowerDescRsp {
+    const ID: u8 = 131;
+    const SUBSYSTEM: SubSystem = SubSystem::Zdo;
+    type Request = PowerDescReq;
+}
+
+impl AsyncReply for SimpleDescRsp {
+    const ID: u8 = 132;
+    const SUBSYSTEM: SubSystem = SubSystem::Zdo;
+    type Request = SimpleDescReq;
+}
+
+#[cfg_attr(feature = "mocking", derive(Serialize))]
+#[derive(Debug, Clone, Deserialize)]
+pub struct ActiveEpRsp {
+    pub src_addr: ShortAddr,
+    pub status: Status,
+    pub nwk_addr: ShortAddr,
+    pub active_endpoints: Vec<u8>,
+}
+
+impl AsyncReply for ActiveEpRsp {
+    const ID: u8 = 133;
+    const SUBSYSTEM: SubSystem = SubSystem::Zdo;
+    type Request = ActiveEpReq;
+}
+
 // #[derive(Debug, Clone, Deserialize)]
 // pub struct MatchDescRsp {
 //     pub srcaddr: u16,
@@ -732,29 +908,33 @@ impl SyncReply for StartupFromAppReply {
 //     const CMD0: u8 = 0; // placeholder
 //     const CMD1: u8 = 0; // placeholder
 // }
-//
-// #[derive(Debug, Clone, Serialize, Deserialize)]
-// pub struct BindRsp {
-//     pub srcaddr: u16,
-//     pub status: u8,
-// }
-//
-// impl SyncReply for BindRsp {
-//     const CMD0: u8 = 0; // placeholder
-//     const CMD1: u8 = 0; // placeholder
-// }
-//
-// #[derive(Debug, Clone, Serialize, Deserialize)]
-// pub struct UnbindRsp {
-//     pub srcaddr: u16,
-//     pub status: u8,
-// }
-//
-// impl SyncReply for UnbindRsp {
-//     const CMD0: u8 = 0; // placeholder
-//     const CMD1: u8 = 0; // placeholder
-// }
-//
+
+#[cfg_attr(feature = "mocking", derive(Serialize))]
+#[derive(Debug, Clone, Deserialize)]
+pub struct BindRsp {
+    pub src_addr: ShortAddr,
+    pub status: Status,
+}
+
+impl AsyncReply for BindRsp {
+    const ID: u8 = 161;
+    const SUBSYSTEM: SubSystem = SubSystem::Zdo;
+    type Request = BindReq;
+}
+
+#[cfg_attr(feature = "mocking", derive(Serialize))]
+#[derive(Debug, Clone, Deserialize)]
+pub struct UnbindRsp {
+    pub src_addr: ShortAddr,
+    pub status: Status,
+}
+
+impl AsyncReply for UnbindRsp {
+    const ID: u8 = 162;
+    const SUBSYSTEM: SubSystem = SubSystem::Zdo;
+    type Request = UnbindReq;
+}
+
 // #[derive(Debug, Clone, Serialize, Deserialize)]
 // pub struct MgmtNwkDiscRsp {
 //     pub srcaddr: u16,
@@ -785,33 +965,34 @@ impl SyncReply for StartupFromAppReply {
 //     const CMD0: u8 = 0; // placeholder
 //     const CMD1: u8 = 0; // placeholder
 // }
-//
-// #[derive(Debug, Clone, Serialize, Deserialize)]
-// pub struct MgmtBindRsp {
-//     pub srcaddr: u16,
-//     pub status: u8,
-//     pub bindingtableentries: u8,
-//     pub startindex: u8,
-//     pub bindingtablelistcount: u8,
-//     pub bindingtablelist: BindTable,
-// }
-//
-// impl SyncReply for MgmtBindRsp {
-//     const CMD0: u8 = 0; // placeholder
-//     const CMD1: u8 = 0; // placeholder
-// }
-//
-// #[derive(Debug, Clone, Serialize, Deserialize)]
-// pub struct MgmtLeaveRsp {
-//     pub srcaddr: u16,
-//     pub status: u8,
-// }
-//
-// impl SyncReply for MgmtLeaveRsp {
-//     const CMD0: u8 = 0; // placeholder
-//     const CMD1: u8 = 0; // placeholder
-// }
-//
+
+#[cfg_attr(feature = "mocking", derive(Serialize))]
+#[derive(Debug, Clone, Deserialize)]
+pub struct MgmtBindRsp {
+    pub src_addr: ShortAddr,
+    pub status: Status,
+    pub binding_table: PartialList<BindTable>,
+}
+
+impl AsyncReply for MgmtBindRsp {
+    const ID: u8 = 179;
+    const SUBSYSTEM: SubSystem = SubSystem::Zdo;
+    type Request = MgmtBindReq;
+}
+
+#[cfg_attr(feature = "mocking", derive(Serialize))]
+#[derive(Debug, Clone, Deserialize)]
+pub struct MgmtLeaveRsp {
+    pub src_addr: ShortAddr,
+    pub status: Status,
+}
+
+impl AsyncReply for MgmtLeaveRsp {
+    const ID: u8 = 180;
+    const SUBSYSTEM: SubSystem = SubSystem::Zdo;
+    type Request = MgmtLeaveReq;
+}
+
 // #[derive(Debug, Clone, Serialize, Deserialize)]
 // pub struct MgmtDirectJoinRsp {
 //     pub srcaddr: u16,
@@ -822,18 +1003,20 @@ impl SyncReply for StartupFromAppReply {
 //     const CMD0: u8 = 0; // placeholder
 //     const CMD1: u8 = 0; // placeholder
 // }
-//
-// #[derive(Debug, Clone, Serialize, Deserialize)]
-// pub struct MgmtPermitJoinRsp {
-//     pub srcaddr: u16,
-//     pub status: u8,
-// }
-//
-// impl SyncReply for MgmtPermitJoinRsp {
-//     const CMD0: u8 = 0; // placeholder
-//     const CMD1: u8 = 0; // placeholder
-// }
-//
+
+#[cfg_attr(feature = "mocking", derive(Serialize))]
+#[derive(Debug, Clone, Deserialize)]
+pub struct MgmtPermitJoinRsp {
+    pub src_addr: ShortAddr,
+    pub status: Status,
+}
+
+impl AsyncReply for MgmtPermitJoinRsp {
+    const ID: u8 = 182;
+    const SUBSYSTEM: SubSystem = SubSystem::Zdo;
+    type Request = MgmtPermitJoinReq;
+}
+
 // #[derive(Debug, Clone, Serialize, Deserialize)]
 // pub struct MgmtNwkUpdateNotify {
 //     pub srcaddr: u16,
@@ -850,29 +1033,37 @@ impl SyncReply for StartupFromAppReply {
 //     const SUBSYSTEM: SubSystem = SubSystem::Zdo;
 // }
 //
-// #[derive(Debug, Clone, Serialize, Deserialize)]
-// pub struct StateChangeInd {
-//     pub state: u8,
-// }
-//
-// impl AsyncRequest for StateChangeInd {
-//     const ID: u8 = 192;
-//     const SUBSYSTEM: SubSystem = SubSystem::Zdo;
-// }
-//
-// #[derive(Debug, Clone, Serialize, Deserialize)]
-// pub struct EndDeviceAnnceInd {
-//     pub srcaddr: u16,
-//     pub nwkaddr: u16,
-//     pub ieeeaddr: IeeeAddr,
-//     pub capabilities: u8,
-// }
-//
-// impl AsyncRequest for EndDeviceAnnceInd {
-//     const ID: u8 = 193;
-//     const SUBSYSTEM: SubSystem = SubSystem::Zdo;
-// }
-//
+/// Send by the device every time its state in the network changes.
+#[cfg_attr(feature = "mocking", derive(Serialize))]
+#[derive(Debug, Clone, Deserialize)]
+pub struct StateChangeInd {
+    pub state: DeviceState,
+}
+
+impl AsyncNotify for StateChangeInd {
+    const ID: u8 = 192;
+    const SUBSYSTEM: SubSystem = SubSystem::Zdo;
+}
+
+/// Send by the device when a device announces itself on the network. That
+/// happens when it joins or rejoins the network.
+#[cfg_attr(feature = "mocking", derive(Serialize))]
+#[derive(Debug, Clone, Deserialize)]
+pub struct EndDeviceAnnceInd {
+    /// Address of the device that send the announcement
+    pub src_addr: ShortAddr,
+    /// The (possibly new) network address of the announced device
+    pub nwk_addr: ShortAddr,
+    pub ieee_addr: IeeeAddr,
+    /// Mac capabilities bitfield of the announced device
+    pub capabilities: u8,
+}
+
+impl AsyncNotify for EndDeviceAnnceInd {
+    const ID: u8 = 193;
+    const SUBSYSTEM: SubSystem = SubSystem::Zdo;
+}
+
 // #[derive(Debug, Clone, Serialize, Deserialize)]
 // pub struct MatchDescRspSent {
 //     pub nwkaddr: u16,
@@ -951,20 +1142,24 @@ impl SyncReply for StartupFromAppReply {
 //     const SUBSYSTEM: SubSystem = SubSystem::Zdo;
 // }
 //
-// #[derive(Debug, Clone, Serialize, Deserialize)]
-// pub struct LeaveInd {
-//     pub srcaddr: u16,
-//     pub extaddr: IeeeAddr,
-//     pub request: u8,
-//     pub removechildren: u8,
-//     pub rejoin: u8,
-// }
-//
-// impl AsyncRequest for LeaveInd {
-//     const ID: u8 = 201;
-//     const SUBSYSTEM: SubSystem = SubSystem::Zdo;
-// }
-//
+/// Send by the device when a device leaves the network or when it is
+/// requested to leave.
+#[cfg_attr(feature = "mocking", derive(Serialize))]
+#[derive(Debug, Clone, Deserialize)]
+pub struct LeaveInd {
+    pub src_addr: ShortAddr,
+    pub ext_addr: IeeeAddr,
+    /// True if this is a leave request, false if it is an indication
+    pub request: bool,
+    pub remove_children: bool,
+    pub rejoin: bool,
+}
+
+impl AsyncNotify for LeaveInd {
+    const ID: u8 = 201;
+    const SUBSYSTEM: SubSystem = SubSystem::Zdo;
+}
+
 // #[derive(Debug, Clone, Serialize, Deserialize)]
 // pub struct SetRejoinParametersReq {
 //     pub backoffduration: u32,
@@ -1075,19 +1270,23 @@ impl SyncReply for StartupFromAppReply {
 //     const SUBSYSTEM: SubSystem = SubSystem::Zdo;
 //     type Reply = SecEntryLookupExtReply;
 // }
-//
-// #[derive(Debug, Clone, Serialize, Deserialize)]
-// pub struct SecDeviceRemove {
-//     pub extaddr: IeeeAddr,
-// }
-//
-// impl SyncRequest for SecDeviceRemove {
-//     const ID: u8 = 68;
-//     const SUBSYSTEM: SubSystem = SubSystem::Zdo;
-//     type Reply = SecDeviceRemoveReply;
-// }
-// basic_reply! {SecDeviceRemove, SecDeviceRemoveReply }
-//
+
+/// Remove a device from the trust center, forgetting its link key and
+/// address. The device itself is not told.
+#[cfg_attr(feature = "mocking", derive(Deserialize))]
+#[derive(Debug, Clone, Serialize)]
+pub struct SecDeviceRemove {
+    pub ieee_addr: IeeeAddr,
+}
+
+impl SyncRequest for SecDeviceRemove {
+    const ID: u8 = 68;
+    const SUBSYSTEM: SubSystem = SubSystem::Zdo;
+    type Reply = SecDeviceRemoveReply;
+}
+
+basic_reply! { SecDeviceRemove, SecDeviceRemoveReply }
+
 // #[derive(Debug, Clone, Serialize, Deserialize)]
 // pub struct ExtRouteDisc {
 //     pub dst_addr: u16,
@@ -1115,52 +1314,60 @@ impl SyncReply for StartupFromAppReply {
 //     type Reply = ExtRouteCheckReply;
 // }
 // basic_reply! {ExtRouteCheck, ExtRouteCheckReply }
-//
-// #[derive(Debug, Clone, Serialize, Deserialize)]
-// pub struct ExtRemoveGroup {
-//     pub endpoint: u8,
-//     pub groupid: u16,
-// }
-//
-// impl SyncRequest for ExtRemoveGroup {
-//     const ID: u8 = 71;
-//     const SUBSYSTEM: SubSystem = SubSystem::Zdo;
-//     type Reply = ExtRemoveGroupReply;
-// }
-// basic_reply! {ExtRemoveGroup, ExtRemoveGroupReply }
-//
-// #[derive(Debug, Clone, Serialize, Deserialize)]
-// pub struct ExtRemoveAllGroup {
-//     pub endpoint: u8,
-// }
-//
-// impl SyncRequest for ExtRemoveAllGroup {
-//     const ID: u8 = 72;
-//     const SUBSYSTEM: SubSystem = SubSystem::Zdo;
-//     type Reply = ExtRemoveAllGroupReply;
-// }
-// basic_reply! {ExtRemoveAllGroup, ExtRemoveAllGroupReply }
-//
-// #[derive(Debug, Clone, Serialize, Deserialize)]
-// pub struct ExtFindAllGroupsEndpoint {
-//     pub endpoint: u8,
-// }
-//
-// #[derive(Debug, Clone, Deserialize)]
-// pub struct ExtFindAllGroupsEndpointReply {
-//     pub grouplist: Vec<u16>,
-// }
-//
-// impl SyncReply for ExtFindAllGroupsEndpointReply {
-//     const CMD0: u8 = 0; // placeholder
-//     const CMD1: u8 = 0; // placeholder
-// }
-//
-// impl SyncRequest for ExtFindAllGroupsEndpoint {
-//     const ID: u8 = 73;
-//     const SUBSYSTEM: SubSystem = SubSystem::Zdo;
-//     type Reply = ExtFindAllGroupsEndpointReply;
-// }
+
+/// Remove an endpoint of the coordinator from a group
+#[cfg_attr(feature = "mocking", derive(Deserialize))]
+#[derive(Debug, Clone, Serialize)]
+pub struct ExtRemoveGroup {
+    pub endpoint: u8,
+    pub group_id: u16,
+}
+
+impl SyncRequest for ExtRemoveGroup {
+    const ID: u8 = 71;
+    const SUBSYSTEM: SubSystem = SubSystem::Zdo;
+    type Reply = ExtRemoveGroupReply;
+}
+
+basic_reply! { ExtRemoveGroup, ExtRemoveGroupReply }
+
+/// Remove an endpoint of the coordinator from all its groups
+#[cfg_attr(feature = "mocking", derive(Deserialize))]
+#[derive(Debug, Clone, Serialize)]
+pub struct ExtRemoveAllGroup {
+    pub endpoint: u8,
+}
+
+impl SyncRequest for ExtRemoveAllGroup {
+    const ID: u8 = 72;
+    const SUBSYSTEM: SubSystem = SubSystem::Zdo;
+    type Reply = ExtRemoveAllGroupReply;
+}
+
+basic_reply! { ExtRemoveAllGroup, ExtRemoveAllGroupReply }
+
+/// List the groups an endpoint of the coordinator is a member of
+#[cfg_attr(feature = "mocking", derive(Deserialize))]
+#[derive(Debug, Clone, Serialize)]
+pub struct ExtFindAllGroupsEndpoint {
+    pub endpoint: u8,
+}
+
+#[cfg_attr(feature = "mocking", derive(Serialize))]
+#[derive(Debug, Clone, Deserialize)]
+pub struct ExtFindAllGroupsEndpointReply {
+    pub group_list: Vec<u16>,
+}
+
+impl SyncRequest for ExtFindAllGroupsEndpoint {
+    const ID: u8 = 73;
+    const SUBSYSTEM: SubSystem = SubSystem::Zdo;
+    type Reply = ExtFindAllGroupsEndpointReply;
+}
+
+impl SyncReply for ExtFindAllGroupsEndpointReply {
+    type Request = ExtFindAllGroupsEndpoint;
+}
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ExtFindGroup {
@@ -1180,31 +1387,46 @@ impl SyncReply for ExtFindGroupReply {
     type Request = ExtFindGroup;
 }
 
-// #[derive(Debug, Clone, Serialize, Deserialize)]
-// pub struct ExtAddGroup {
-//     pub endpoint: u8,
-//     pub groupid: u16,
-//     pub namelen: u8,
-//     pub groupname: Vec<u8>,
-// }
-//
-// impl SyncRequest for ExtAddGroup {
-//     const ID: u8 = 75;
-//     const SUBSYSTEM: SubSystem = SubSystem::Zdo;
-//     type Reply = ExtAddGroupReply;
-// }
-// basic_reply! {ExtAddGroup, ExtAddGroupReply }
-//
-// #[derive(Debug, Clone, Serialize, Deserialize)]
-// pub struct ExtCountAllGroups {}
-//
-// impl SyncRequest for ExtCountAllGroups {
-//     const ID: u8 = 76;
-//     const SUBSYSTEM: SubSystem = SubSystem::Zdo;
-//     type Reply = ExtCountAllGroupsReply;
-// }
-// basic_reply! {ExtCountAllGroups, ExtCountAllGroupsReply }
-//
+/// Add an endpoint of the coordinator to a group, it then receives the
+/// messages send to the group.
+#[cfg_attr(feature = "mocking", derive(Deserialize))]
+#[derive(Debug, Clone, Serialize)]
+pub struct ExtAddGroup {
+    pub endpoint: u8,
+    pub group_id: u16,
+    /// At most 16 bytes, send with a length prefix
+    pub group_name: Vec<u8>,
+}
+
+impl SyncRequest for ExtAddGroup {
+    const ID: u8 = 75;
+    const SUBSYSTEM: SubSystem = SubSystem::Zdo;
+    type Reply = ExtAddGroupReply;
+}
+
+basic_reply! { ExtAddGroup, ExtAddGroupReply }
+
+/// Count the groups of all endpoints on the coordinator
+#[cfg_attr(feature = "mocking", derive(Deserialize))]
+#[derive(Debug, Clone, Serialize)]
+pub struct ExtCountAllGroups;
+
+#[cfg_attr(feature = "mocking", derive(Serialize))]
+#[derive(Debug, Clone, Deserialize)]
+pub struct ExtCountAllGroupsReply {
+    pub count: u8,
+}
+
+impl SyncRequest for ExtCountAllGroups {
+    const ID: u8 = 76;
+    const SUBSYSTEM: SubSystem = SubSystem::Zdo;
+    type Reply = ExtCountAllGroupsReply;
+}
+
+impl SyncReply for ExtCountAllGroupsReply {
+    type Request = ExtCountAllGroups;
+}
+
 // #[derive(Debug, Clone, Serialize, Deserialize)]
 // pub struct ExtRxIdle {
 //     pub setflag: u8,
@@ -1305,25 +1527,32 @@ impl SyncReply for ExtFindGroupReply {
 //     type Reply = ExtSetParamsReply;
 // }
 // basic_reply! {ExtSetParams, ExtSetParamsReply }
-//
-// #[derive(Debug, Clone, Serialize, Deserialize)]
-// pub struct TcDeviceInd {
-//     pub nwkaddr: u16,
-//     pub extaddr: IeeeAddr,
-//     pub parentaddr: u16,
-// }
-//
-// impl AsyncRequest for TcDeviceInd {
-//     const ID: u8 = 202;
-//     const SUBSYSTEM: SubSystem = SubSystem::Zdo;
-// }
-//
-// #[derive(Debug, Clone, Serialize, Deserialize)]
-// pub struct PermitJoinInd {
-//     pub duration: u8,
-// }
-//
-// impl AsyncRequest for PermitJoinInd {
-//     const ID: u8 = 203;
-//     const SUBSYSTEM: SubSystem = SubSystem::Zdo;
-// }
+
+/// Send by the coordinator, as trust center, when a device joins or
+/// rejoins the network through any router.
+#[cfg_attr(feature = "mocking", derive(Serialize))]
+#[derive(Debug, Clone, Deserialize)]
+pub struct TcDeviceInd {
+    pub nwk_addr: ShortAddr,
+    pub ext_addr: IeeeAddr,
+    /// The router the device joined through
+    pub parent_addr: ShortAddr,
+}
+
+impl AsyncNotify for TcDeviceInd {
+    const ID: u8 = 202;
+    const SUBSYSTEM: SubSystem = SubSystem::Zdo;
+}
+
+/// Send by the device when joining is enabled or disabled on it.
+#[cfg_attr(feature = "mocking", derive(Serialize))]
+#[derive(Debug, Clone, Deserialize)]
+pub struct PermitJoinInd {
+    /// Seconds joining stays allowed, 0 once it is no longer allowed
+    pub duration: u8,
+}
+
+impl AsyncNotify for PermitJoinInd {
+    const ID: u8 = 203;
+    const SUBSYSTEM: SubSystem = SubSystem::Zdo;
+}
@@ -0,0 +1,190 @@
+#[cfg(feature = "mocking")]
+use serde::Serialize;
+use serde::de::{SeqAccess, Visitor};
+#[cfg(feature = "mocking")]
+use serde::ser::SerializeStruct;
+use serde::{Deserialize, de};
+use serde_repr::{Deserialize_repr, Serialize_repr};
+
+use crate::commands::IeeeAddr;
+use crate::commands::af::ClusterId;
+
+/// How the destination of a binding is addressed
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, Serialize_repr, Deserialize_repr,
+)]
+#[repr(u8)]
+pub enum BindAddrMode {
+    Group = 0x01,
+    Ieee = 0x03,
+}
+
+/// Where the messages of a binding go
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BindDestination {
+    Group(u16),
+    Device { ieee_addr: IeeeAddr, endpoint: u8 },
+}
+
+impl BindDestination {
+    pub fn addr_mode(&self) -> BindAddrMode {
+        match self {
+            Self::Group(_) => BindAddrMode::Group,
+            Self::Device { .. } => BindAddrMode::Ieee,
+        }
+    }
+}
+
+/// An entry in the binding table of a device.
+///
+/// See Z-Stack Monitor and Test API section 3.12.2.18 revision 1.14
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BindTable {
+    pub src: IeeeAddr,
+    pub src_endpoint: u8,
+    pub cluster_id: ClusterId,
+    pub dst: BindDestination,
+}
+
+struct BindTableVisitor;
+
+impl<'de> Visitor<'de> for BindTableVisitor {
+    type Value = BindTable;
+
+    fn expecting(
+        &self,
+        formatter: &mut std::fmt::Formatter,
+    ) -> std::fmt::Result {
+        formatter.write_str(
+            "struct BindTable with a destination that is either a group id \
+            or an IEEE address followed by an endpoint",
+        )
+    }
+
+    fn visit_seq<V>(self, mut seq: V) -> Result<Self::Value, V::Error>
+    where
+        V: SeqAccess<'de>,
+    {
+        let src = seq
+            .next_element::<IeeeAddr>()?
+            .ok_or_else(|| de::Error::invalid_length(0, &self))?;
+        let src_endpoint = seq
+            .next_element::<u8>()?
+            .ok_or_else(|| de::Error::invalid_length(1, &self))?;
+        let cluster_id = seq
+            .next_element::<ClusterId>()?
+            .ok_or_else(|| de::Error::invalid_length(2, &self))?;
+        let dst_addr_mode = seq
+            .next_element::<BindAddrMode>()?
+            .ok_or_else(|| de::Error::invalid_length(3, &self))?;
+        let dst = match dst_addr_mode {
+            BindAddrMode::Group => BindDestination::Group(
+                seq.next_element::<u16>()?
+                    .ok_or_else(|| de::Error::invalid_length(4, &self))?,
+            ),
+            BindAddrMode::Ieee => BindDestination::Device {
+                ieee_addr: seq
+                    .next_element::<IeeeAddr>()?
+                    .ok_or_else(|| de::Error::invalid_length(4, &self))?,
+                endpoint: seq
+                    .next_element::<u8>()?
+                    .ok_or_else(|| de::Error::invalid_length(5, &self))?,
+            },
+        };
+
+        Ok(BindTable {
+            src,
+            src_endpoint,
+            cluster_id,
+            dst,
+        })
+    }
+}
+
+impl<'de> Deserialize<'de> for BindTable {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        deserializer.deserialize_struct(
+            "BindTable",
+            &[
+                "src",
+                "src_endpoint",
+                "cluster_id",
+                "dst_addr_mode",
+                "dst_addr",
+                "dst_endpoint",
+            ],
+            BindTableVisitor,
+        )
+    }
+}
+
+#[cfg(feature = "mocking")]
+impl Serialize for BindTable {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        let mut s = serializer.serialize_struct("BindTable", 6)?;
+        s.serialize_field("src", &self.src)?;
+        s.serialize_field("src_endpoint", &self.src_endpoint)?;
+        s.serialize_field("cluster_id", &self.cluster_id)?;
+        s.serialize_field("dst_addr_mode", &self.dst.addr_mode())?;
+        match &self.dst {
+            BindDestination::Group(group_id) => {
+                s.serialize_field("dst_addr", group_id)?;
+            }
+            BindDestination::Device {
+                ieee_addr,
+                endpoint,
+            } => {
+                s.serialize_field("dst_addr", ieee_addr)?;
+                s.serialize_field("dst_endpoint", endpoint)?;
+            }
+        }
+        s.end()
+    }
+}
+
+#[cfg(all(test, feature = "mocking"))]
+mod tests {
+    use std::io::Cursor;
+
+    use super::*;
+    use crate::data_format;
+
+    #[test]
+    fn group_destination_is_shorter() {
+        let entry = BindTable {
+            src: IeeeAddr(0x00124b0001020304),
+            src_endpoint: 1,
+            cluster_id: ClusterId(0x0006),
+            dst: BindDestination::Group(0x0102),
+        };
+        let bytes = data_format::to_vec(&entry).unwrap();
+        assert_eq!(bytes.len(), 8 + 1 + 2 + 1 + 2);
+        let decoded: BindTable =
+            data_format::from_reader(&mut Cursor::new(bytes)).unwrap();
+        assert_eq!(decoded, entry);
+    }
+
+    #[test]
+    fn device_destination() {
+        let entry = BindTable {
+            src: IeeeAddr(0x00124b0001020304),
+            src_endpoint: 1,
+            cluster_id: ClusterId(0x0300),
+            dst: BindDestination::Device {
+                ieee_addr: IeeeAddr(0x00124b0005060708),
+                endpoint: 2,
+            },
+        };
+        let bytes = data_format::to_vec(&entry).unwrap();
+        assert_eq!(bytes.len(), 8 + 1 + 2 + 1 + 8 + 1);
+        let decoded: BindTable =
+            data_format::from_reader(&mut Cursor::new(bytes)).unwrap();
+        assert_eq!(decoded, entry);
+    }
+}
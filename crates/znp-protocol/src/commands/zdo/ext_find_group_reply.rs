@@ -5,8 +5,8 @@ use serde::{Deserialize, Serialize};
 #[derive(Debug, Clone)]
 pub struct GroupName(pub String);
 
-#[cfg_attr(feature = "mocking", derive(Deserialize))]
-#[derive(Debug, Clone, Serialize)]
+#[cfg_attr(feature = "mocking", derive(Serialize))]
+#[derive(Debug, Clone, Deserialize)]
 pub struct ExtFindGroupReply {
     pub group_id: u16,
     pub group_name: GroupName,
@@ -4,9 +4,12 @@ use serde::{Deserialize, Serialize, de};
 
 use crate::commands::{DeviceType, IeeeAddr, ShortAddr};
 
+/// An entry in the neighbor table of a device.
+///
+/// See Z-Stack Monitor and Test API section 3.12.2.16 revision 1.14
 #[derive(Debug, Clone)]
 pub struct NeighborLqi {
-    pub extended_pan_id: u8,
+    pub extended_pan_id: u64,
     pub extended_address: IeeeAddr,
     pub network_address: ShortAddr,
     pub device_type: DeviceType,
@@ -17,6 +20,25 @@ pub struct NeighborLqi {
     pub lqi: u8,
 }
 
+/// The neighbor table numbers device types differently from [`DeviceType`]
+fn device_type_from_bits(bits: u8) -> DeviceType {
+    match bits {
+        0 => DeviceType::Coordinator,
+        1 => DeviceType::Router,
+        2 => DeviceType::EndDevice,
+        _ => DeviceType::None,
+    }
+}
+
+fn device_type_bits(device_type: DeviceType) -> u8 {
+    match device_type {
+        DeviceType::Coordinator => 0,
+        DeviceType::Router => 1,
+        DeviceType::EndDevice => 2,
+        DeviceType::None => 3,
+    }
+}
+
 struct NeighborLqiVisitor;
 
 impl<'de> Visitor<'de> for NeighborLqiVisitor {
@@ -37,7 +59,7 @@ impl<'de> Visitor<'de> for NeighborLqiVisitor {
         V: SeqAccess<'de>,
     {
         let extended_pan_id = seq
-            .next_element::<u8>()?
+            .next_element::<u64>()?
             .ok_or_else(|| de::Error::invalid_length(0, &self))?;
         let extended_address = seq
             .next_element::<IeeeAddr>()?
@@ -62,11 +84,10 @@ impl<'de> Visitor<'de> for NeighborLqiVisitor {
             extended_pan_id,
             extended_address,
             network_address,
-            device_type: DeviceType::from_repr(packed & 0b0000_0011)
-                .expect("can only represent valid values for DeviceType"),
+            device_type: device_type_from_bits(packed & 0b0000_0011),
             rx_on_when_idle: (packed & 0b0000_1100) >> 2,
             relationship: (packed & 0b1111_0000) >> 4,
-            permit_joining: (permit_joining & 0b1100_0000 == 1),
+            permit_joining: (permit_joining & 0b0000_0011 == 1),
             depth,
             lqi,
         })
@@ -99,9 +120,9 @@ impl Serialize for NeighborLqi {
     where
         S: serde::Serializer,
     {
-        let bit_packed_lqi_entry = self.device_type as u8
-            | (self.rx_on_when_idle as u8) << 2
-            | (self.relationship as u8) << 4;
+        let bit_packed_lqi_entry = device_type_bits(self.device_type)
+            | self.rx_on_when_idle << 2
+            | self.relationship << 4;
         let mut s = serializer.serialize_struct("NeighborLqi", 7)?;
         s.serialize_field("extended_pan_id", &self.extended_pan_id)?;
         s.serialize_field("extended_address", &self.extended_address)?;
@@ -113,3 +134,39 @@ impl Serialize for NeighborLqi {
         s.end()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use super::*;
+    use crate::data_format;
+
+    #[test]
+    fn router_neighbor() {
+        #[rustfmt::skip]
+        let bytes = vec![
+            // extended pan id
+            0xdd, 0xdd, 0xdd, 0xdd, 0xdd, 0xdd, 0xdd, 0xdd,
+            // extended address
+            0x04, 0x03, 0x02, 0x01, 0x00, 0x4b, 0x12, 0x00,
+            // network address
+            0x04, 0x03,
+            // router, receiver on when idle, child
+            0b0001_0101,
+            // permit joining, depth, lqi
+            0x01, 0x02, 0x80,
+        ];
+        let entry: NeighborLqi =
+            data_format::from_reader(&mut Cursor::new(bytes.clone())).unwrap();
+        assert_eq!(entry.extended_pan_id, 0xdddd_dddd_dddd_dddd);
+        assert_eq!(entry.extended_address, IeeeAddr(0x00124b0001020304));
+        assert_eq!(entry.network_address, ShortAddr(0x0304));
+        assert!(matches!(entry.device_type, DeviceType::Router));
+        assert_eq!(entry.rx_on_when_idle, 1);
+        assert_eq!(entry.relationship, 1);
+        assert!(entry.permit_joining);
+        assert_eq!((entry.depth, entry.lqi), (2, 0x80));
+        assert_eq!(data_format::to_vec(&entry).unwrap(), bytes);
+    }
+}
@@ -0,0 +1,158 @@
+use serde::de::{SeqAccess, Visitor};
+#[cfg(feature = "mocking")]
+use serde::ser::SerializeStruct;
+use serde::{Deserialize, Serialize, de};
+
+use crate::commands::af::ClusterId;
+use crate::commands::{ShortAddr, Status};
+
+/// Describes one endpoint of a device
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SimpleDescriptor {
+    pub endpoint: u8,
+    pub profile_id: u16,
+    pub device_id: u16,
+    pub device_version: u8,
+    /// Clusters the endpoint implements the server side of
+    pub in_clusters: Vec<ClusterId>,
+    /// Clusters the endpoint implements the client side of
+    pub out_clusters: Vec<ClusterId>,
+}
+
+#[derive(Debug, Clone)]
+pub struct SimpleDescRsp {
+    pub src_addr: ShortAddr,
+    pub status: Status,
+    pub nwk_addr: ShortAddr,
+    /// Missing if the status is not success
+    pub descriptor: Option<SimpleDescriptor>,
+}
+
+struct SimpleDescRspVisitor;
+
+impl<'de> Visitor<'de> for SimpleDescRspVisitor {
+    type Value = SimpleDescRsp;
+
+    fn expecting(
+        &self,
+        formatter: &mut std::fmt::Formatter,
+    ) -> std::fmt::Result {
+        formatter.write_str(
+            "struct SimpleDescRsp with a simple descriptor prefixed by \
+            its length, which is zero if there is no descriptor",
+        )
+    }
+
+    fn visit_seq<V>(self, mut seq: V) -> Result<Self::Value, V::Error>
+    where
+        V: SeqAccess<'de>,
+    {
+        let src_addr = seq
+            .next_element::<ShortAddr>()?
+            .ok_or_else(|| de::Error::invalid_length(0, &self))?;
+        let status = seq
+            .next_element::<Status>()?
+            .ok_or_else(|| de::Error::invalid_length(1, &self))?;
+        let nwk_addr = seq
+            .next_element::<ShortAddr>()?
+            .ok_or_else(|| de::Error::invalid_length(2, &self))?;
+        let len = seq
+            .next_element::<u8>()?
+            .ok_or_else(|| de::Error::invalid_length(3, &self))?;
+        let descriptor = if len == 0 {
+            None
+        } else {
+            Some(
+                seq.next_element::<SimpleDescriptor>()?
+                    .ok_or_else(|| de::Error::invalid_length(4, &self))?,
+            )
+        };
+
+        Ok(SimpleDescRsp {
+            src_addr,
+            status,
+            nwk_addr,
+            descriptor,
+        })
+    }
+}
+
+impl<'de> Deserialize<'de> for SimpleDescRsp {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        deserializer.deserialize_struct(
+            "SimpleDescRsp",
+            &["src_addr", "status", "nwk_addr", "len", "descriptor"],
+            SimpleDescRspVisitor,
+        )
+    }
+}
+
+#[cfg(feature = "mocking")]
+impl Serialize for SimpleDescRsp {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        use serde::ser::Error;
+        let len = self.descriptor.as_ref().map_or(0, |descriptor| {
+            6 + 1
+                + 2 * descriptor.in_clusters.len()
+                + 1
+                + 2 * descriptor.out_clusters.len()
+        });
+        let len = u8::try_from(len)
+            .map_err(|_| S::Error::custom("descriptor longer then u8::MAX"))?;
+        let mut s = serializer.serialize_struct("SimpleDescRsp", 5)?;
+        s.serialize_field("src_addr", &self.src_addr)?;
+        s.serialize_field("status", &self.status)?;
+        s.serialize_field("nwk_addr", &self.nwk_addr)?;
+        s.serialize_field("len", &len)?;
+        if let Some(descriptor) = &self.descriptor {
+            s.serialize_field("descriptor", descriptor)?;
+        }
+        s.end()
+    }
+}
+
+#[cfg(all(test, feature = "mocking"))]
+mod tests {
+    use std::io::Cursor;
+
+    use super::*;
+    use crate::data_format;
+
+    #[test]
+    fn without_descriptor() {
+        let bytes = [0x34, 0x12, 0x82, 0x34, 0x12, 0];
+        let rsp: SimpleDescRsp =
+            data_format::from_reader(&mut Cursor::new(bytes)).unwrap();
+        assert_eq!(rsp.status, Status::ZdoInvalidEndpoint);
+        assert!(rsp.descriptor.is_none());
+        assert_eq!(data_format::to_vec(&rsp).unwrap(), bytes);
+    }
+
+    #[test]
+    fn with_descriptor() {
+        let rsp = SimpleDescRsp {
+            src_addr: ShortAddr(0x1234),
+            status: Status::ZSuccess,
+            nwk_addr: ShortAddr(0x1234),
+            descriptor: Some(SimpleDescriptor {
+                endpoint: 1,
+                profile_id: 0x0104,
+                device_id: 0x0100,
+                device_version: 1,
+                in_clusters: vec![ClusterId(0x0000), ClusterId(0x0006)],
+                out_clusters: vec![ClusterId(0x0019)],
+            }),
+        };
+        let bytes = data_format::to_vec(&rsp).unwrap();
+        assert_eq!(usize::from(bytes[5]), bytes.len() - 6);
+        let decoded: SimpleDescRsp =
+            data_format::from_reader(&mut Cursor::new(bytes)).unwrap();
+        assert_eq!(decoded.descriptor, rsp.descriptor);
+    }
+}
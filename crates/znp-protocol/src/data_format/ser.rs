@@ -474,7 +474,7 @@ mod tests {
             &mut data,
             out_clusters_offset + 1,
         );
-        (&data[..data_len as usize]).to_vec()
+        data[..data_len as usize].to_vec()
     }
 
     #[test]